@@ -0,0 +1,117 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use petgraph::graph::{Graph, NodeIndex};
+use seiri_cli::analysis::GraphAnalysis;
+use seiri_cli::core::defs::Language;
+use seiri_cli::core::resolvers::GraphBuilder;
+use seiri_cli::{parse_files, walk_directory};
+use std::collections::HashMap;
+use std::fs;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Generate a Rust project with `file_count` modules spread over nested directories,
+/// where each module declares the next few as submodules
+fn generate_rust_project(file_count: usize) -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    for i in 0..file_count {
+        let dir = temp_dir.path().join(format!("group_{}", i % 10));
+        fs::create_dir_all(&dir).unwrap();
+        let mut content = String::new();
+        for dep in 1..=3 {
+            content.push_str(&format!("mod module_{};\n", (i + dep) % file_count));
+        }
+        content.push_str(&format!(
+            "pub fn function_{i}() {{}}\npub struct Type{i};\n"
+        ));
+        fs::write(dir.join(format!("module_{i}.rs")), content).unwrap();
+    }
+    temp_dir
+}
+
+fn language_files(root: &Path) -> HashMap<PathBuf, Language> {
+    walk_directory(root, false, &[], None)
+        .into_iter()
+        .filter_map(|path| {
+            let lang = Language::from_file(path.to_str()?)?;
+            Some((path, lang))
+        })
+        .collect()
+}
+
+/// Deterministic pseudo-random graph with `edges_per_node` outgoing edges per node
+fn generate_graph(node_count: usize, edges_per_node: usize) -> Graph<(), ()> {
+    let mut graph = Graph::new();
+    let nodes: Vec<NodeIndex> = (0..node_count).map(|_| graph.add_node(())).collect();
+    let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+    for &from in &nodes {
+        for _ in 0..edges_per_node {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let to = nodes[(state % node_count as u64) as usize];
+            if to != from {
+                graph.add_edge(from, to, ());
+            }
+        }
+    }
+    graph
+}
+
+fn bench_walk(c: &mut Criterion) {
+    let project = generate_rust_project(500);
+    c.bench_function("walk_directory/500", |b| {
+        b.iter(|| walk_directory(black_box(project.path()), false, &[], None))
+    });
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_files");
+    for file_count in [50, 200] {
+        let project = generate_rust_project(file_count);
+        let files = language_files(project.path());
+        group.bench_with_input(
+            BenchmarkId::from_parameter(file_count),
+            &files,
+            |b, files| b.iter(|| parse_files(black_box(files))),
+        );
+    }
+    group.finish();
+}
+
+fn bench_build_edges(c: &mut Criterion) {
+    let project = generate_rust_project(200);
+    let node_map = parse_files(&language_files(project.path()));
+    c.bench_function("build_graph_edges/200", |b| {
+        b.iter(|| GraphBuilder::new().build_graph_edges(black_box(&node_map), project.path()))
+    });
+}
+
+fn bench_analysis(c: &mut Criterion) {
+    // Sanity check that the largest scenario is analyzed completely
+    let graph = generate_graph(1_000, 3);
+    let analysis = GraphAnalysis::analyze_graph(&graph);
+    assert_eq!(analysis.betweenness_centrality.len(), 1_000);
+    assert_eq!(analysis.scc_sizes.iter().sum::<usize>(), 1_000);
+
+    let mut group = c.benchmark_group("analyze_graph");
+    group.sample_size(10);
+    for (node_count, edges_per_node) in [(100, 2), (100, 8), (1_000, 3)] {
+        let graph = generate_graph(node_count, edges_per_node);
+        group.bench_with_input(
+            BenchmarkId::new(format!("{edges_per_node}_edges_per_node"), node_count),
+            &graph,
+            |b, graph| b.iter(|| GraphAnalysis::analyze_graph(black_box(graph))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_walk,
+    bench_parse,
+    bench_build_edges,
+    bench_analysis
+);
+criterion_main!(benches);
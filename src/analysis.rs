@@ -1,11 +1,164 @@
+use crate::core::defs::GraphNode;
 use petgraph::{
+    Direction,
     graph::{Graph, NodeIndex},
     visit::EdgeRef,
 };
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+/// Build a list of `(from, to)` edges between node positions in `graph_nodes`.
+/// Edges pointing at files that are not part of the graph are skipped.
+pub fn build_edge_list(graph_nodes: &[GraphNode]) -> Vec<(usize, usize)> {
+    let index_of: HashMap<&PathBuf, usize> = graph_nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.data().file(), i))
+        .collect();
+
+    let mut edges = Vec::new();
+    for (from, node) in graph_nodes.iter().enumerate() {
+        for edge in node.edges() {
+            if let Some(&to) = index_of.get(edge) {
+                edges.push((from, to));
+            }
+        }
+    }
+    edges
+}
+
+/// Count the outgoing edges of each of the `node_count` nodes in an edge list
+pub fn out_degrees(node_count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut degrees = vec![0; node_count];
+    for &(from, _) in edges {
+        degrees[from] += 1;
+    }
+    degrees
+}
+
+/// Number of edges touching each node, incoming and outgoing, for `node_count` nodes
+pub fn total_degrees(node_count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut degrees = vec![0; node_count];
+    for &(from, to) in edges {
+        degrees[from] += 1;
+        degrees[to] += 1;
+    }
+    degrees
+}
+
+/// Build a petgraph graph mirroring `graph_nodes`, where node `i` corresponds to `graph_nodes[i]`
+pub fn build_dependency_graph(graph_nodes: &[GraphNode]) -> Graph<(), ()> {
+    let mut graph = Graph::new();
+    let node_indices: Vec<NodeIndex> = graph_nodes.iter().map(|_| graph.add_node(())).collect();
+    for (from, to) in build_edge_list(graph_nodes) {
+        graph.add_edge(node_indices[from], node_indices[to], ());
+    }
+    graph
+}
+
+/// Find pairs of nodes that depend on each other (A -> B and B -> A).
+/// Each pair is returned once, ordered as `(smaller index, larger index)`.
+pub fn find_bidirectional_pairs(edges: &[(usize, usize)]) -> HashSet<(usize, usize)> {
+    let edge_set: HashSet<(usize, usize)> = edges.iter().copied().collect();
+    edge_set
+        .iter()
+        .filter(|&&(from, to)| from != to && edge_set.contains(&(to, from)))
+        .map(|&(from, to)| (from.min(to), from.max(to)))
+        .collect()
+}
+
+/// Mean import locality (see [`crate::core::defs::FileNode::import_locality`]) over the files that
+/// import anything, or `None` if none do
+pub fn average_import_locality(graph_nodes: &[GraphNode]) -> Option<f64> {
+    let ratios: Vec<f64> = graph_nodes
+        .iter()
+        .filter_map(|node| node.data().import_locality())
+        .collect();
+    if ratios.is_empty() {
+        return None;
+    }
+    Some(ratios.iter().sum::<f64>() / ratios.len() as f64)
+}
+
+/// How many of the edges touching a directory's files stay inside that directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryCohesion {
+    pub directory: PathBuf,
+    /// Edges between two files of the directory
+    pub internal_edges: usize,
+    /// Edges with at least one end in the directory
+    pub total_edges: usize,
+}
+
+impl DirectoryCohesion {
+    /// Share of the directory's edges that stay inside it
+    pub fn ratio(&self) -> f64 {
+        self.internal_edges as f64 / self.total_edges as f64
+    }
+}
+
+/// Compute the cohesion of each file's parent directory, most cohesive first.
+/// Ties go to the directory with more internal edges, then to the smaller path;
+/// directories without any edges are left out.
+pub fn directory_cohesion(graph_nodes: &[GraphNode]) -> Vec<DirectoryCohesion> {
+    let directory_of = |i: usize| {
+        graph_nodes[i]
+            .data()
+            .file()
+            .parent()
+            .map(PathBuf::from)
+            .unwrap_or_default()
+    };
+
+    let mut counts: HashMap<PathBuf, (usize, usize)> = HashMap::new();
+    for (from, to) in build_edge_list(graph_nodes) {
+        let (from_dir, to_dir) = (directory_of(from), directory_of(to));
+        if from_dir == to_dir {
+            let entry = counts.entry(from_dir).or_default();
+            entry.0 += 1;
+            entry.1 += 1;
+        } else {
+            counts.entry(from_dir).or_default().1 += 1;
+            counts.entry(to_dir).or_default().1 += 1;
+        }
+    }
+
+    let mut cohesion: Vec<DirectoryCohesion> = counts
+        .into_iter()
+        .map(
+            |(directory, (internal_edges, total_edges))| DirectoryCohesion {
+                directory,
+                internal_edges,
+                total_edges,
+            },
+        )
+        .collect();
+    cohesion.sort_by(|a, b| {
+        b.ratio()
+            .total_cmp(&a.ratio())
+            .then(b.internal_edges.cmp(&a.internal_edges))
+            .then_with(|| a.directory.cmp(&b.directory))
+    });
+    cohesion
+}
+
+/// Whether metrics follow the direction of dependencies or treat every edge as two-way
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MetricMode {
+    /// Paths only follow edges from a file to its dependencies
+    #[default]
+    Directed,
+    /// Paths may follow edges in either direction
+    Undirected,
+}
 
 #[derive(Debug)]
 pub struct GraphAnalysis {
+    /// Mode the path-based metrics were computed in. SCCs are always directed.
+    pub metric_mode: MetricMode,
     /// Size of each strongly connected component
     pub scc_sizes: Vec<usize>,
     /// Mapping of nodes to their SCC index
@@ -20,6 +173,27 @@ pub struct GraphAnalysis {
     /// Betweenness centrality scores for each node
     /// Higher values indicate nodes that appear on more shortest paths
     pub betweenness_centrality: HashMap<NodeIndex, f64>,
+    /// Local clustering coefficient of each node on the undirected projection of the graph
+    pub clustering: HashMap<NodeIndex, f64>,
+    /// Mean of the local clustering coefficients
+    pub average_clustering: f64,
+    /// Share of the possible directed edges between distinct nodes that exist
+    pub density: f64,
+    /// PageRank of each node, following edges from a file to its dependencies. Sums to 1.
+    pub pagerank: HashMap<NodeIndex, f64>,
+    /// Number of edges pointing at each node (files depending on it, or fan-in)
+    pub in_degree: HashMap<NodeIndex, usize>,
+    /// Number of edges leaving each node (its dependencies, or fan-out)
+    pub out_degree: HashMap<NodeIndex, usize>,
+    /// Community of each node found by Louvain on the undirected projection, numbered
+    /// from 0 in order of each community's first node
+    pub community: HashMap<NodeIndex, usize>,
+    /// Number of distinct communities
+    pub community_count: usize,
+    /// Modularity of the community assignment, from -0.5 (worse than random) to 1
+    pub modularity: f64,
+    /// Dependencies-first ordering of the nodes, or `None` if the graph has a cycle
+    build_order: Option<Vec<NodeIndex>>,
 }
 
 impl GraphAnalysis {
@@ -27,6 +201,7 @@ impl GraphAnalysis {
     fn calculate_betweenness_from_source(
         graph: &Graph<(), ()>,
         source: NodeIndex,
+        mode: MetricMode,
         centrality: &mut HashMap<NodeIndex, f64>,
     ) {
         let mut stack = Vec::new();
@@ -54,7 +229,16 @@ impl GraphAnalysis {
             let v_dist = distance[&v];
             let v_sigma = sigma[&v];
 
-            for neighbor in graph.neighbors(v) {
+            // Duplicate edges (or both halves of a mutual dependency when undirected)
+            // must not count as separate shortest paths
+            let mut neighbors: Vec<NodeIndex> = match mode {
+                MetricMode::Directed => graph.neighbors(v).collect(),
+                MetricMode::Undirected => graph.neighbors_undirected(v).collect(),
+            };
+            neighbors.sort_unstable();
+            neighbors.dedup();
+
+            for neighbor in neighbors {
                 // First time we found this node?
                 if distance[&neighbor] < 0 {
                     queue.push_back(neighbor);
@@ -83,16 +267,19 @@ impl GraphAnalysis {
     }
 
     /// Calculate betweenness centrality for all nodes
-    fn calculate_betweenness_centrality(graph: &Graph<(), ()>) -> HashMap<NodeIndex, f64> {
+    fn calculate_betweenness_centrality(
+        graph: &Graph<(), ()>,
+        mode: MetricMode,
+    ) -> HashMap<NodeIndex, f64> {
         let mut centrality: HashMap<NodeIndex, f64> =
             graph.node_indices().map(|n| (n, 0.0)).collect();
 
         // Calculate betweenness from each source node
         for source in graph.node_indices() {
-            Self::calculate_betweenness_from_source(graph, source, &mut centrality);
+            Self::calculate_betweenness_from_source(graph, source, mode, &mut centrality);
         }
 
-        // Normalize for undirected graphs
+        // Every ordered (source, target) pair was visited, so one factor normalizes both modes
         if graph.node_count() > 2 {
             let norm = 1.0 / ((graph.node_count() - 1) * (graph.node_count() - 2)) as f64;
             for score in centrality.values_mut() {
@@ -103,15 +290,227 @@ impl GraphAnalysis {
         centrality
     }
 
-    /// Analyze the graph to find both SCCs and betweenness centrality
+    /// Calculate PageRank by power iteration. Dangling nodes (no outgoing edges) spread
+    /// their rank evenly over every node, so the ranks keep summing to 1.
+    fn calculate_pagerank(graph: &Graph<(), ()>) -> HashMap<NodeIndex, f64> {
+        const DAMPING: f64 = 0.85;
+        const TOLERANCE: f64 = 1e-9;
+        const MAX_ITERATIONS: usize = 100;
+
+        let n = graph.node_count();
+        let out_degree: Vec<usize> = graph
+            .node_indices()
+            .map(|node| graph.neighbors(node).count())
+            .collect();
+        let mut rank = vec![1.0 / n as f64; n];
+
+        for _ in 0..MAX_ITERATIONS {
+            let dangling: f64 = graph
+                .node_indices()
+                .filter(|node| out_degree[node.index()] == 0)
+                .map(|node| rank[node.index()])
+                .sum();
+            let base = (1.0 - DAMPING) / n as f64 + DAMPING * dangling / n as f64;
+            let mut next = vec![base; n];
+            for edge in graph.edge_references() {
+                let source = edge.source().index();
+                next[edge.target().index()] += DAMPING * rank[source] / out_degree[source] as f64;
+            }
+
+            let change: f64 = rank.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+            rank = next;
+            if change < TOLERANCE {
+                break;
+            }
+        }
+
+        graph
+            .node_indices()
+            .map(|node| (node, rank[node.index()]))
+            .collect()
+    }
+
+    /// Calculate the local clustering coefficient of every node: the fraction of pairs of its
+    /// neighbors that are themselves connected. Edge direction, self-loops and duplicate
+    /// edges are ignored. Nodes with fewer than two neighbors have a coefficient of 0.
+    fn calculate_clustering_coefficients(graph: &Graph<(), ()>) -> HashMap<NodeIndex, f64> {
+        let neighbors: HashMap<NodeIndex, HashSet<NodeIndex>> = graph
+            .node_indices()
+            .map(|node| {
+                let set = graph
+                    .neighbors_undirected(node)
+                    .filter(|&neighbor| neighbor != node)
+                    .collect();
+                (node, set)
+            })
+            .collect();
+
+        neighbors
+            .iter()
+            .map(|(&node, node_neighbors)| {
+                let degree = node_neighbors.len();
+                if degree < 2 {
+                    return (node, 0.0);
+                }
+                let links = node_neighbors
+                    .iter()
+                    .map(|a| {
+                        neighbors[a]
+                            .iter()
+                            .filter(|b| node_neighbors.contains(b))
+                            .count()
+                    })
+                    .sum::<usize>()
+                    / 2;
+                let possible = degree * (degree - 1) / 2;
+                (node, links as f64 / possible as f64)
+            })
+            .collect()
+    }
+
+    /// Detect communities with the Louvain method: move each node to the neighboring
+    /// community that raises modularity the most until nothing moves, then merge every
+    /// community into a single node and repeat on the smaller graph. Edges are undirected
+    /// and unweighted, so a mutual dependency counts once, and self-loops are ignored.
+    /// Nodes are visited in index order, so the result is deterministic.
+    /// Returns the community of each node and the modularity of the assignment.
+    fn calculate_communities(graph: &Graph<(), ()>) -> (Vec<usize>, f64) {
+        let n = graph.node_count();
+        let mut adjacency: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); n];
+        for edge in graph.edge_references() {
+            let (a, b) = (edge.source().index(), edge.target().index());
+            if a != b {
+                adjacency[a].insert(b, 1.0);
+                adjacency[b].insert(a, 1.0);
+            }
+        }
+        let original = adjacency.clone();
+        // Twice the number of edges, i.e. the sum of all degrees
+        let total_weight: f64 = adjacency.iter().flat_map(|links| links.values()).sum();
+        let mut membership: Vec<usize> = (0..n).collect();
+        if total_weight == 0.0 {
+            return (membership, 0.0);
+        }
+
+        // Weight of the edges inside each node of the current level, counted in both
+        // directions, which still counts toward its degree
+        let mut internal = vec![0.0; n];
+        loop {
+            let size = adjacency.len();
+            let degree: Vec<f64> = (0..size)
+                .map(|i| adjacency[i].values().sum::<f64>() + internal[i])
+                .collect();
+            let mut community: Vec<usize> = (0..size).collect();
+            let mut community_degree = degree.clone();
+
+            let mut moved = true;
+            while moved {
+                moved = false;
+                for i in 0..size {
+                    let current = community[i];
+                    community_degree[current] -= degree[i];
+
+                    let mut links: BTreeMap<usize, f64> = BTreeMap::new();
+                    for (&j, &weight) in &adjacency[i] {
+                        *links.entry(community[j]).or_default() += weight;
+                    }
+                    let gain = |c: usize| {
+                        links.get(&c).copied().unwrap_or(0.0)
+                            - community_degree[c] * degree[i] / total_weight
+                    };
+                    let mut best = current;
+                    let mut best_gain = gain(current);
+                    for &c in links.keys() {
+                        if gain(c) > best_gain + 1e-12 {
+                            best = c;
+                            best_gain = gain(c);
+                        }
+                    }
+
+                    community_degree[best] += degree[i];
+                    if best != current {
+                        community[i] = best;
+                        moved = true;
+                    }
+                }
+            }
+
+            // Renumber communities in order of their first member
+            let mut renumber = HashMap::new();
+            for c in community.iter_mut() {
+                let next = renumber.len();
+                *c = *renumber.entry(*c).or_insert(next);
+            }
+            for m in membership.iter_mut() {
+                *m = community[*m];
+            }
+            let count = renumber.len();
+            if count == size {
+                break;
+            }
+
+            // Merge each community into a single node for the next level
+            let mut merged = vec![BTreeMap::new(); count];
+            let mut merged_internal = vec![0.0; count];
+            for i in 0..size {
+                merged_internal[community[i]] += internal[i];
+                for (&j, &weight) in &adjacency[i] {
+                    if community[i] == community[j] {
+                        merged_internal[community[i]] += weight;
+                    } else {
+                        *merged[community[i]].entry(community[j]).or_default() += weight;
+                    }
+                }
+            }
+            adjacency = merged;
+            internal = merged_internal;
+        }
+
+        // Q = sum over communities of (inside / 2m) - (degree / 2m)^2
+        let community_count = membership.iter().max().map_or(0, |&c| c + 1);
+        let mut inside = vec![0.0; community_count];
+        let mut degree = vec![0.0; community_count];
+        for (i, links) in original.iter().enumerate() {
+            for (&j, &weight) in links {
+                degree[membership[i]] += weight;
+                if membership[i] == membership[j] {
+                    inside[membership[i]] += weight;
+                }
+            }
+        }
+        let modularity = inside
+            .iter()
+            .zip(&degree)
+            .map(|(inside, degree)| inside / total_weight - (degree / total_weight).powi(2))
+            .sum();
+        (membership, modularity)
+    }
+
+    /// Analyze the graph to find both SCCs and betweenness centrality, following edge direction
     pub fn analyze_graph(graph: &Graph<(), ()>) -> Self {
+        Self::analyze_graph_with_mode(graph, MetricMode::Directed)
+    }
+
+    /// Analyze the graph, computing path-based metrics in the given mode
+    pub fn analyze_graph_with_mode(graph: &Graph<(), ()>, mode: MetricMode) -> Self {
         let mut analysis = Self {
+            metric_mode: mode,
             scc_sizes: Vec::new(),
             node_to_scc: HashMap::new(),
             largest_scc_size: 0,
             largest_scc_nodes: HashSet::new(),
             sccs_by_size: HashMap::new(),
             betweenness_centrality: HashMap::new(),
+            clustering: HashMap::new(),
+            average_clustering: 0.0,
+            density: 0.0,
+            pagerank: HashMap::new(),
+            in_degree: HashMap::new(),
+            out_degree: HashMap::new(),
+            community: HashMap::new(),
+            community_count: 0,
+            modularity: 0.0,
+            build_order: Some(Vec::new()),
         };
 
         if graph.node_count() == 0 {
@@ -119,7 +518,27 @@ impl GraphAnalysis {
         }
 
         // Calculate betweenness centrality
-        analysis.betweenness_centrality = Self::calculate_betweenness_centrality(graph);
+        analysis.betweenness_centrality = Self::calculate_betweenness_centrality(graph, mode);
+
+        // Clustering is always computed on the undirected projection
+        analysis.clustering = Self::calculate_clustering_coefficients(graph);
+        analysis.average_clustering =
+            analysis.clustering.values().sum::<f64>() / graph.node_count() as f64;
+
+        let n = graph.node_count();
+        if n >= 2 {
+            analysis.density = graph.edge_count() as f64 / (n * (n - 1)) as f64;
+        }
+
+        analysis.pagerank = Self::calculate_pagerank(graph);
+
+        let (community, modularity) = Self::calculate_communities(graph);
+        analysis.community_count = community.iter().max().map_or(0, |&c| c + 1);
+        analysis.community = graph
+            .node_indices()
+            .map(|node| (node, community[node.index()]))
+            .collect();
+        analysis.modularity = modularity;
 
         // Step 1: First DFS to get finishing times
         let mut visited = HashSet::new();
@@ -138,11 +557,15 @@ impl GraphAnalysis {
         // Add all nodes
         for node in graph.node_indices() {
             node_map.insert(node, transposed.add_node(()));
+            analysis.in_degree.insert(node, 0);
+            analysis.out_degree.insert(node, 0);
         }
 
-        // Add reversed edges
+        // Add reversed edges, counting degrees along the way
         for edge in graph.edge_references() {
             transposed.add_edge(node_map[&edge.target()], node_map[&edge.source()], ());
+            *analysis.out_degree.get_mut(&edge.source()).unwrap() += 1;
+            *analysis.in_degree.get_mut(&edge.target()).unwrap() += 1;
         }
 
         // Step 3: Second DFS to find SCCs
@@ -191,9 +614,54 @@ impl GraphAnalysis {
             }
         }
 
+        // Any SCC with more than one node is a cycle, so there is no valid order
+        analysis.build_order =
+            (analysis.largest_scc_size <= 1).then(|| Self::dependencies_first_order(graph));
+
         analysis
     }
 
+    /// Kahn's algorithm over reversed edges, so every file comes after the files it depends on.
+    /// Ties are broken by the lowest node index to keep the order deterministic, and
+    /// self-loops are ignored. Only valid for acyclic graphs.
+    fn dependencies_first_order(graph: &Graph<(), ()>) -> Vec<NodeIndex> {
+        let mut remaining_deps: HashMap<NodeIndex, usize> = graph
+            .node_indices()
+            .map(|node| {
+                let mut deps: Vec<NodeIndex> =
+                    graph.neighbors(node).filter(|&dep| dep != node).collect();
+                deps.sort_unstable();
+                deps.dedup();
+                (node, deps.len())
+            })
+            .collect();
+
+        let mut ready: BinaryHeap<Reverse<NodeIndex>> = remaining_deps
+            .iter()
+            .filter(|&(_, &count)| count == 0)
+            .map(|(&node, _)| Reverse(node))
+            .collect();
+        let mut order = Vec::with_capacity(graph.node_count());
+        while let Some(Reverse(node)) = ready.pop() {
+            order.push(node);
+
+            let mut dependents: Vec<NodeIndex> = graph
+                .neighbors_directed(node, Direction::Incoming)
+                .filter(|&dependent| dependent != node)
+                .collect();
+            dependents.sort_unstable();
+            dependents.dedup();
+            for dependent in dependents {
+                let count = remaining_deps.get_mut(&dependent).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    ready.push(Reverse(dependent));
+                }
+            }
+        }
+        order
+    }
+
     fn dfs_first_pass(
         graph: &Graph<(), ()>,
         start: NodeIndex,
@@ -243,6 +711,93 @@ impl GraphAnalysis {
     pub fn get_betweenness_centrality(&self, node: NodeIndex) -> Option<f64> {
         self.betweenness_centrality.get(&node).copied()
     }
+
+    /// Get the number of edges pointing at a node
+    pub fn get_in_degree(&self, node: NodeIndex) -> Option<usize> {
+        self.in_degree.get(&node).copied()
+    }
+
+    /// Get the number of edges leaving a node
+    pub fn get_out_degree(&self, node: NodeIndex) -> Option<usize> {
+        self.out_degree.get(&node).copied()
+    }
+
+    /// Suggested build or review order, with every node after the nodes it depends on.
+    /// Returns `None` if the graph has a cycle.
+    pub fn topological_order(&self) -> Option<Vec<NodeIndex>> {
+        self.build_order.clone()
+    }
+
+    /// Get the PageRank score for a node
+    pub fn get_pagerank(&self, node: NodeIndex) -> Option<f64> {
+        self.pagerank.get(&node).copied()
+    }
+
+    /// Get the local clustering coefficient for a node
+    pub fn get_clustering_coefficient(&self, node: NodeIndex) -> Option<f64> {
+        self.clustering.get(&node).copied()
+    }
+
+    /// Get the community a node was assigned to
+    pub fn get_community(&self, node: NodeIndex) -> Option<usize> {
+        self.community.get(&node).copied()
+    }
+
+    /// Edges present over the `n * (n - 1)` a directed graph without self-loops can have
+    pub fn graph_density(&self) -> f64 {
+        self.density
+    }
+}
+
+/// Hash of a graph's node count and edge set, independent of edge insertion order
+fn graph_fingerprint(graph: &Graph<(), ()>, mode: MetricMode) -> u64 {
+    let mut edges: Vec<(usize, usize)> = graph
+        .edge_references()
+        .map(|edge| (edge.source().index(), edge.target().index()))
+        .collect();
+    edges.sort_unstable();
+    edges.dedup();
+
+    let mut hasher = DefaultHasher::new();
+    graph.node_count().hash(&mut hasher);
+    edges.hash(&mut hasher);
+    mode.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Keeps the last analysis around and only recomputes it when the graph changes
+#[derive(Debug, Default)]
+pub struct AnalysisCache {
+    fingerprint: Option<u64>,
+    analysis: Option<GraphAnalysis>,
+    last_was_hit: bool,
+}
+
+impl AnalysisCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Analyze `graph`, reusing the previous result if its nodes, edges and mode are unchanged
+    pub fn analyze(&mut self, graph: &Graph<(), ()>, mode: MetricMode) -> &GraphAnalysis {
+        let fingerprint = graph_fingerprint(graph, mode);
+        self.last_was_hit = self.fingerprint == Some(fingerprint) && self.analysis.is_some();
+        if !self.last_was_hit {
+            self.analysis = Some(GraphAnalysis::analyze_graph_with_mode(graph, mode));
+            self.fingerprint = Some(fingerprint);
+        }
+        self.analysis.as_ref().unwrap()
+    }
+
+    /// The most recently computed analysis, if any
+    pub fn analysis(&self) -> Option<&GraphAnalysis> {
+        self.analysis.as_ref()
+    }
+
+    /// Whether the most recent `analyze` call reused the cached result
+    pub fn last_was_hit(&self) -> bool {
+        self.last_was_hit
+    }
 }
 
 #[cfg(test)]
@@ -264,6 +819,281 @@ mod tests {
         graph
     }
 
+    #[test]
+    fn test_bidirectional_pairs() {
+        // 0 <-> 1 is mutual, 1 -> 2 and 2 -> 2 are not
+        let edges = [(0, 1), (1, 0), (1, 2), (2, 2)];
+        let pairs = find_bidirectional_pairs(&edges);
+
+        assert_eq!(pairs.len(), 1);
+        assert!(pairs.contains(&(0, 1)));
+    }
+
+    #[test]
+    fn test_directory_cohesion_ranking() {
+        use crate::core::defs::Language;
+
+        let node =
+            |file: &str, edges: &[&str]| GraphNode::for_test(file, Language::Rust, 10, edges);
+        // core/ mostly talks to itself; ui/ mostly reaches into other directories
+        let graph_nodes = vec![
+            node("core/a.rs", &["core/b.rs", "core/c.rs"]),
+            node("core/b.rs", &["core/c.rs"]),
+            node("core/c.rs", &[]),
+            node("ui/view.rs", &["core/a.rs", "util/fmt.rs", "ui/widget.rs"]),
+            node("ui/widget.rs", &["util/fmt.rs"]),
+            node("util/fmt.rs", &[]),
+        ];
+
+        let cohesion = directory_cohesion(&graph_nodes);
+        let ranked: Vec<(&str, usize, usize)> = cohesion
+            .iter()
+            .map(|dir| {
+                (
+                    dir.directory.to_str().unwrap(),
+                    dir.internal_edges,
+                    dir.total_edges,
+                )
+            })
+            .collect();
+        assert_eq!(ranked, vec![("core", 3, 4), ("ui", 1, 4), ("util", 0, 2)]);
+        assert_eq!(cohesion[0].ratio(), 0.75);
+    }
+
+    #[test]
+    fn test_degrees_match_edge_list() {
+        // 0 -> 1, 0 -> 2, 1 -> 2, 3 -> 0; node 2 is a leaf
+        let edges = [(0, 1), (0, 2), (1, 2), (3, 0)];
+        let graph = create_test_graph(&edges);
+        let analysis = GraphAnalysis::analyze_graph(&graph);
+
+        let out_degrees = out_degrees(graph.node_count(), &edges);
+        for node in graph.node_indices() {
+            let incoming = edges.iter().filter(|&&(_, to)| to == node.index()).count();
+            assert_eq!(analysis.get_in_degree(node), Some(incoming));
+            assert_eq!(
+                analysis.get_out_degree(node),
+                Some(out_degrees[node.index()])
+            );
+        }
+
+        assert_eq!(analysis.get_out_degree(NodeIndex::new(2)), Some(0));
+        assert_eq!(analysis.get_in_degree(NodeIndex::new(2)), Some(2));
+        assert_eq!(analysis.get_in_degree(NodeIndex::new(3)), Some(0));
+        assert_eq!(analysis.get_out_degree(NodeIndex::new(9)), None);
+    }
+
+    #[test]
+    fn test_topological_order_chain() {
+        // 0 depends on 1, which depends on 2, which depends on 3
+        let graph = create_test_graph(&[(0, 1), (1, 2), (2, 3)]);
+        let analysis = GraphAnalysis::analyze_graph(&graph);
+
+        let order: Vec<usize> = analysis
+            .topological_order()
+            .unwrap()
+            .iter()
+            .map(|n| n.index())
+            .collect();
+        assert_eq!(order, vec![3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_topological_order_breaks_ties_by_index() {
+        // 2 and 0 both depend on 1; 3 stands alone
+        let graph = create_test_graph(&[(2, 1), (0, 1), (3, 3)]);
+        let analysis = GraphAnalysis::analyze_graph(&graph);
+
+        let order: Vec<usize> = analysis
+            .topological_order()
+            .unwrap()
+            .iter()
+            .map(|n| n.index())
+            .collect();
+        assert_eq!(order, vec![1, 0, 2, 3]);
+    }
+
+    #[test]
+    fn test_topological_order_cycle() {
+        let graph = create_test_graph(&[(0, 1), (1, 2), (2, 0), (2, 3)]);
+        let analysis = GraphAnalysis::analyze_graph(&graph);
+        assert!(analysis.topological_order().is_none());
+    }
+
+    #[test]
+    fn test_pagerank_favors_depended_on_nodes() {
+        // 1, 2 and 3 all depend on 0; 4 depends on 1
+        let graph = create_test_graph(&[(1, 0), (2, 0), (3, 0), (4, 1)]);
+        let analysis = GraphAnalysis::analyze_graph(&graph);
+
+        let rank = |i| analysis.get_pagerank(NodeIndex::new(i)).unwrap();
+        for leaf in [2, 3, 4] {
+            assert!(rank(0) > rank(leaf));
+        }
+        assert!(rank(1) > rank(4));
+
+        let total: f64 = analysis.pagerank.values().sum();
+        assert!((total - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pagerank_sums_to_one_with_cycles() {
+        let graph = create_test_graph(&[(0, 1), (1, 2), (2, 0), (2, 3), (3, 3)]);
+        let analysis = GraphAnalysis::analyze_graph(&graph);
+
+        let total: f64 = analysis.pagerank.values().sum();
+        assert!((total - 1.0).abs() < 1e-6);
+        assert_eq!(analysis.pagerank.len(), 4);
+    }
+
+    #[test]
+    fn test_clustering_triangle() {
+        // Direction doesn't matter: 0 -> 1 -> 2 -> 0
+        let graph = create_test_graph(&[(0, 1), (1, 2), (2, 0)]);
+        let analysis = GraphAnalysis::analyze_graph(&graph);
+
+        for node in graph.node_indices() {
+            assert_eq!(analysis.get_clustering_coefficient(node), Some(1.0));
+        }
+        assert_eq!(analysis.average_clustering, 1.0);
+    }
+
+    #[test]
+    fn test_clustering_star() {
+        // Center 0 connected to three leaves that don't know each other
+        let graph = create_test_graph(&[(0, 1), (0, 2), (3, 0)]);
+        let analysis = GraphAnalysis::analyze_graph(&graph);
+
+        assert_eq!(
+            analysis.get_clustering_coefficient(NodeIndex::new(0)),
+            Some(0.0)
+        );
+        assert_eq!(
+            analysis.get_clustering_coefficient(NodeIndex::new(1)),
+            Some(0.0)
+        );
+        assert_eq!(analysis.average_clustering, 0.0);
+
+        // Closing one pair of leaves gives the center 1 of 3 possible links
+        let graph = create_test_graph(&[(0, 1), (0, 2), (3, 0), (1, 2), (2, 1)]);
+        let analysis = GraphAnalysis::analyze_graph(&graph);
+        let center = analysis
+            .get_clustering_coefficient(NodeIndex::new(0))
+            .unwrap();
+        assert!((center - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_density() {
+        // Every ordered pair of distinct nodes is connected
+        let graph = create_test_graph(&[(0, 1), (1, 0), (1, 2), (2, 1), (0, 2), (2, 0)]);
+        let analysis = GraphAnalysis::analyze_graph(&graph);
+        assert_eq!(analysis.graph_density(), 1.0);
+        assert_eq!(analysis.average_clustering, 1.0);
+
+        // A path uses 3 of the 12 possible edges and closes no triangles
+        let graph = create_test_graph(&[(0, 1), (1, 2), (2, 3)]);
+        let analysis = GraphAnalysis::analyze_graph(&graph);
+        assert_eq!(analysis.graph_density(), 0.25);
+        assert_eq!(analysis.average_clustering, 0.0);
+
+        // A single node has no possible edges
+        let graph = create_test_graph(&[(0, 0)]);
+        assert_eq!(GraphAnalysis::analyze_graph(&graph).graph_density(), 0.0);
+    }
+
+    #[test]
+    fn test_communities_two_cliques() {
+        // Two 4-cliques joined by a single edge between nodes 3 and 4
+        let mut edges = Vec::new();
+        for clique in [0..4, 4..8] {
+            for a in clique.clone() {
+                for b in clique.clone().filter(|&b| b > a) {
+                    edges.push((a, b));
+                }
+            }
+        }
+        edges.push((3, 4));
+        let graph = create_test_graph(&edges);
+        let analysis = GraphAnalysis::analyze_graph(&graph);
+
+        assert_eq!(analysis.community_count, 2);
+        let community = |i| analysis.get_community(NodeIndex::new(i)).unwrap();
+        assert!((0..4).all(|i| community(i) == 0));
+        assert!((4..8).all(|i| community(i) == 1));
+        // Each clique has 6 of the 13 edges and a degree sum of 13 out of 26
+        let expected = 2.0 * (6.0 / 13.0 - 0.25);
+        assert!((analysis.modularity - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_communities_without_edges() {
+        let mut graph = Graph::new();
+        graph.add_node(());
+        graph.add_node(());
+        let analysis = GraphAnalysis::analyze_graph(&graph);
+        assert_eq!(analysis.community_count, 2);
+        assert_eq!(analysis.modularity, 0.0);
+
+        let analysis = GraphAnalysis::analyze_graph(&Graph::new());
+        assert_eq!(analysis.community_count, 0);
+    }
+
+    #[test]
+    fn test_out_degrees() {
+        let edges = [(0, 1), (0, 2), (1, 2), (2, 2)];
+        assert_eq!(out_degrees(4, &edges), vec![2, 1, 1, 0]);
+        assert_eq!(total_degrees(4, &edges), vec![2, 2, 4, 0]);
+    }
+
+    #[test]
+    fn test_bidirectional_pairs_none() {
+        let edges = [(0, 1), (1, 2), (2, 0)];
+        assert!(find_bidirectional_pairs(&edges).is_empty());
+    }
+
+    #[test]
+    fn test_analysis_cache_reuses_identical_graph() {
+        let mut cache = AnalysisCache::new();
+
+        cache.analyze(&create_test_graph(&[(0, 1), (1, 2)]), MetricMode::Directed);
+        assert!(!cache.last_was_hit());
+
+        // Same nodes and edges, inserted in a different order
+        let analysis = cache.analyze(&create_test_graph(&[(1, 2), (0, 1)]), MetricMode::Directed);
+        assert_eq!(analysis.scc_sizes.len(), 3);
+        assert!(cache.last_was_hit());
+
+        cache.analyze(
+            &create_test_graph(&[(1, 2), (0, 1)]),
+            MetricMode::Undirected,
+        );
+        assert!(!cache.last_was_hit());
+
+        cache.analyze(
+            &create_test_graph(&[(0, 1), (1, 2), (2, 0)]),
+            MetricMode::Undirected,
+        );
+        assert!(!cache.last_was_hit());
+    }
+
+    #[test]
+    fn test_betweenness_directed_vs_undirected() {
+        // 0 -> 1 <- 2: no directed path passes through 1, but the undirected path 0 - 1 - 2 does
+        let graph = create_test_graph(&[(0, 1), (2, 1)]);
+
+        let directed = GraphAnalysis::analyze_graph_with_mode(&graph, MetricMode::Directed);
+        let undirected = GraphAnalysis::analyze_graph_with_mode(&graph, MetricMode::Undirected);
+
+        let node = NodeIndex::new(1);
+        assert_eq!(directed.get_betweenness_centrality(node), Some(0.0));
+        assert_eq!(undirected.get_betweenness_centrality(node), Some(1.0));
+        assert_eq!(undirected.metric_mode, MetricMode::Undirected);
+
+        // SCCs follow edge direction regardless of mode
+        assert_eq!(undirected.largest_scc_size, 1);
+    }
+
     #[test]
     fn test_no_cycles() {
         // Create a simple DAG: 0 -> 1 -> 2
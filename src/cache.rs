@@ -0,0 +1,263 @@
+use crate::core::defs::{FileNode, Language};
+use crate::detail;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Parsers change between releases, so a cache written by another version is discarded.
+/// Bump the schema number whenever parsing output changes between releases.
+const CACHE_VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "+schema.2");
+
+/// Files that decide whether an import is local, looked up above the project's files
+const MANIFEST_FILES: [&str; 3] = ["go.mod", "mix.exs", "pyproject.toml"];
+
+/// Directory holding the parse caches of every project: `$XDG_CACHE_HOME/seiri`,
+/// falling back to `~/.cache/seiri`
+pub fn cache_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .map(|dir| dir.join("seiri"))
+}
+
+/// The cache file for the project at `project_root`, named after a hash of its path
+pub fn project_cache_file(project_root: &Path) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    project_root.hash(&mut hasher);
+    Some(cache_dir()?.join(format!("{:016x}.json", hasher.finish())))
+}
+
+/// Hash of a file's language and contents, and of the project around it; a file whose
+/// hash changed is parsed again
+fn content_hash(language: Language, contents: &[u8], context: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    language.to_string().hash(&mut hasher);
+    contents.hash(&mut hasher);
+    context.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash of everything besides a file's own contents that its parse depends on: whether
+/// an import is local depends on which other files exist, and on the manifests above them
+fn project_context_hash(language_files: &HashMap<PathBuf, Language>) -> u64 {
+    let files: BTreeSet<&PathBuf> = language_files.keys().collect();
+    let dirs: BTreeSet<&Path> = files
+        .iter()
+        .filter_map(|file| file.parent())
+        .flat_map(Path::ancestors)
+        .collect();
+    let mut hasher = DefaultHasher::new();
+    files.hash(&mut hasher);
+    for dir in dirs {
+        for name in MANIFEST_FILES {
+            if let Ok(contents) = fs::read(dir.join(name)) {
+                dir.join(name).hash(&mut hasher);
+                contents.hash(&mut hasher);
+            }
+        }
+    }
+    hasher.finish()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    hash: u64,
+    node: FileNode,
+}
+
+/// Parsed files from earlier runs, keyed by path and checked against the hash of
+/// each file's current contents before they are reused
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ParseCache {
+    version: String,
+    entries: HashMap<PathBuf, CacheEntry>,
+    /// Whether anything changed since the cache was loaded
+    #[serde(skip)]
+    dirty: bool,
+    /// Hash of the project the cached files are parsed in, see [`Self::set_project_files`]
+    #[serde(skip)]
+    context: u64,
+}
+
+impl ParseCache {
+    /// Load the cache at `path`, starting empty if it is missing, unreadable, or was
+    /// written by another version
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<ParseCache>(&text).ok())
+            .filter(|cache| cache.version == CACHE_VERSION)
+            .unwrap_or_else(|| ParseCache {
+                version: CACHE_VERSION.to_string(),
+                ..Default::default()
+            })
+    }
+
+    /// Write the cache to `path` if anything changed since it was loaded
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if !self.dirty {
+            return Ok(());
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+        }
+        let text = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(path, text).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    /// Record every file of the project, so cached files are parsed again once files are
+    /// added or removed, or a manifest changes
+    pub fn set_project_files(&mut self, language_files: &HashMap<PathBuf, Language>) {
+        self.context = project_context_hash(language_files);
+    }
+
+    /// Forget files that are no longer part of the project
+    pub fn retain_files(&mut self, language_files: &HashMap<PathBuf, Language>) {
+        let before = self.entries.len();
+        self.entries
+            .retain(|file, _| language_files.contains_key(file));
+        self.dirty |= self.entries.len() != before;
+    }
+
+    /// Like [`crate::parse_files`], but files whose contents match their cached hash are
+    /// taken from the cache, and only the rest are handed to `parse` and then cached
+    pub fn parse_files(
+        &mut self,
+        language_files: &HashMap<PathBuf, Language>,
+        parse: impl Fn(&Path, Language) -> Option<FileNode> + Sync,
+    ) -> HashMap<PathBuf, FileNode> {
+        let results: Vec<(PathBuf, u64, FileNode, bool)> = language_files
+            .par_iter()
+            .filter_map(|(file_path, &lang)| {
+                let hash = content_hash(lang, &fs::read(file_path).ok()?, self.context);
+                if let Some(entry) = self.entries.get(file_path)
+                    && entry.hash == hash
+                {
+                    return Some((file_path.clone(), hash, entry.node.clone(), false));
+                }
+                let node = parse(file_path, lang)?;
+                detail!("Parsed {} file: {}", lang.to_string(), file_path.display());
+                Some((file_path.clone(), hash, node, true))
+            })
+            .collect();
+
+        let mut node_map = HashMap::new();
+        for (file_path, hash, node, parsed) in results {
+            if parsed {
+                let entry = CacheEntry {
+                    hash,
+                    node: node.clone(),
+                };
+                self.entries.insert(file_path.clone(), entry);
+                self.dirty = true;
+            }
+            node_map.insert(file_path, node);
+        }
+        node_map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_file;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_only_changed_files_are_parsed_again() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let cache_file = root.join("cache/parse.json");
+        fs::write(root.join("main.rs"), "mod util;\nfn main() {}\n").unwrap();
+        fs::write(root.join("util.rs"), "pub fn helper() {}\n").unwrap();
+        let language_files = HashMap::from([
+            (root.join("main.rs"), Language::Rust),
+            (root.join("util.rs"), Language::Rust),
+        ]);
+
+        let parsed = Mutex::new(Vec::new());
+        let counting_parse = |file: &Path, lang: Language| {
+            parsed.lock().unwrap().push(file.to_path_buf());
+            parse_file(file, lang)
+        };
+        let run = || {
+            let mut cache = ParseCache::load(&cache_file);
+            let node_map = cache.parse_files(&language_files, counting_parse);
+            cache.save(&cache_file).unwrap();
+            node_map
+        };
+
+        assert_eq!(run().len(), 2);
+        assert_eq!(parsed.lock().unwrap().len(), 2);
+
+        // Nothing changed, so nothing is parsed
+        parsed.lock().unwrap().clear();
+        let node_map = run();
+        assert!(parsed.lock().unwrap().is_empty());
+        assert_eq!(node_map[&root.join("util.rs")].functions().len(), 1);
+
+        // Only the edited file is parsed, and its new contents are returned
+        fs::write(
+            root.join("util.rs"),
+            "pub fn helper() {}\npub fn other() {}\n",
+        )
+        .unwrap();
+        let node_map = run();
+        assert_eq!(*parsed.lock().unwrap(), vec![root.join("util.rs")]);
+        assert_eq!(node_map[&root.join("util.rs")].functions().len(), 2);
+        assert_eq!(node_map[&root.join("main.rs")].imports().len(), 1);
+    }
+
+    #[test]
+    fn test_added_sibling_file_is_linked_on_a_cached_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("project");
+        fs::create_dir(&root).unwrap();
+        fs::write(root.join("a.py"), "import b\n").unwrap();
+        let options = crate::AnalyzeOptions {
+            cache_file: Some(temp_dir.path().join("parse.json")),
+            ..Default::default()
+        };
+        let edges_of_a = || {
+            let graph_nodes = crate::analyze_project(&root, options.clone()).unwrap();
+            let a = graph_nodes
+                .iter()
+                .find(|node| node.data().file().ends_with("a.py"))
+                .unwrap();
+            a.edges().clone()
+        };
+
+        assert!(edges_of_a().is_empty());
+
+        // a.py is unchanged, but `import b` is local now that b.py exists
+        fs::write(root.join("b.py"), "x = 1\n").unwrap();
+        assert_eq!(edges_of_a(), vec![root.join("b.py")]);
+    }
+
+    #[test]
+    fn test_other_version_cache_is_discarded() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_file = temp_dir.path().join("parse.json");
+        let file = temp_dir.path().join("main.rs");
+        fs::write(&file, "fn main() {}\n").unwrap();
+
+        let mut cache = ParseCache::load(&cache_file);
+        cache.parse_files(&HashMap::from([(file, Language::Rust)]), parse_file);
+        cache.save(&cache_file).unwrap();
+        assert_eq!(ParseCache::load(&cache_file).entries.len(), 1);
+
+        let text = fs::read_to_string(&cache_file).unwrap();
+        let outdated = text.replace(&format!("\"{CACHE_VERSION}\""), "\"0.0.0\"");
+        fs::write(&cache_file, outdated).unwrap();
+        assert!(ParseCache::load(&cache_file).entries.is_empty());
+
+        fs::write(&cache_file, "not json").unwrap();
+        assert!(ParseCache::load(&cache_file).entries.is_empty());
+    }
+}
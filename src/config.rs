@@ -0,0 +1,118 @@
+use crate::core::defs::Language;
+use crate::log::{self, LogLevel};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Load a language config file mapping language names to extra file extensions, e.g.
+/// `{ "python": ["pyi"], "typescript": [".mjs"] }`
+pub fn load_language_extensions(path: &Path) -> Result<HashMap<String, Vec<String>>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read language config {}: {e}", path.display()))?;
+    serde_json::from_str(&text)
+        .map_err(|e| format!("Failed to parse language config {}: {e}", path.display()))
+}
+
+/// Turn a loaded language config into an extension map for `Language::from_file_with_config`.
+/// Unknown language names are skipped, with a warning under `--verbose`.
+pub fn language_extension_map(
+    extensions: &HashMap<String, Vec<String>>,
+) -> HashMap<String, Language> {
+    let mut map = HashMap::new();
+    for (name, exts) in extensions {
+        let Some(language) = Language::from_name(name) else {
+            if log::enabled(LogLevel::Verbose) {
+                eprintln!("Warning: ignoring unknown language `{name}` in language config");
+            }
+            continue;
+        };
+        for ext in exts {
+            map.insert(ext.trim_start_matches('.').to_string(), language);
+        }
+    }
+    map
+}
+
+/// Parse an `--extension-priority` value such as `ts=tsx,ts` into a language and the
+/// extensions its resolver should try first
+pub fn parse_extension_priority(spec: &str) -> Result<(Language, Vec<String>), String> {
+    let (name, extensions) = spec.split_once('=').ok_or_else(|| {
+        format!("Invalid extension priority {spec:?}, expected LANG=EXT[,EXT...]")
+    })?;
+    let language =
+        Language::from_name(name.trim()).ok_or_else(|| format!("Unknown language: {name}"))?;
+    let extensions: Vec<String> = extensions
+        .split(',')
+        .map(|ext| ext.trim().trim_start_matches('.').to_string())
+        .filter(|ext| !ext.is_empty())
+        .collect();
+    if extensions.is_empty() {
+        return Err(format!(
+            "No extensions given in extension priority {spec:?}"
+        ));
+    }
+    Ok((language, extensions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_extra_extensions() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("languages.json");
+        fs::write(
+            &config_path,
+            r#"{ "python": [".pyi"], "typescript": ["mjs"], "cobol": ["cbl"] }"#,
+        )
+        .unwrap();
+
+        let extensions = load_language_extensions(&config_path).unwrap();
+        let map = language_extension_map(&extensions);
+
+        assert_eq!(
+            Language::from_file_with_config("stubs/os.pyi", &map),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            Language::from_file_with_config("index.mjs", &map),
+            Some(Language::TypeScript)
+        );
+        assert_eq!(Language::from_file_with_config("main.cbl", &map), None);
+        // Built-in extensions still work
+        assert_eq!(
+            Language::from_file_with_config("main.rs", &map),
+            Some(Language::Rust)
+        );
+    }
+
+    #[test]
+    fn test_parse_extension_priority() {
+        assert_eq!(
+            parse_extension_priority("ts=.tsx, ts").unwrap(),
+            (
+                Language::TypeScript,
+                vec!["tsx".to_string(), "ts".to_string()]
+            )
+        );
+        assert!(parse_extension_priority("c++").is_err());
+        assert!(parse_extension_priority("cobol=cbl").is_err());
+        assert!(parse_extension_priority("cpp=").is_err());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("languages.json");
+        fs::write(&config_path, r#"{ "python": "pyi" }"#).unwrap();
+
+        let result = load_language_extensions(&config_path);
+        assert!(
+            result
+                .unwrap_err()
+                .contains("Failed to parse language config")
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tiny_skia::Color;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -9,6 +10,9 @@ pub enum Language {
     Rust,
     TypeScript,
     Cpp,
+    Go,
+    Elixir,
+    Ruby,
 }
 
 impl Language {
@@ -16,10 +20,15 @@ impl Language {
     #[inline(always)]
     pub fn extensions(&self) -> &'static [&'static str] {
         match self {
-            Language::Python => &["py"],
+            // Notebooks are parsed from their code cells
+            Language::Python => &["py", "ipynb"],
             Language::Rust => &["rs"],
-            Language::TypeScript => &["ts"],
+            // `.mts` and `.cts` are Node's ES module and CommonJS flavors of TypeScript
+            Language::TypeScript => &["ts", "tsx", "jsx", "mts", "cts"],
             Language::Cpp => &["cpp", "cc", "cxx", "c++", "h", "hpp", "hxx", "h++"],
+            Language::Go => &["go"],
+            Language::Elixir => &["ex", "exs"],
+            Language::Ruby => &["rb"],
         }
     }
 
@@ -32,6 +41,9 @@ impl Language {
                 Language::Rust,
                 Language::TypeScript,
                 Language::Cpp,
+                Language::Go,
+                Language::Elixir,
+                Language::Ruby,
             ] {
                 for extension in lang.extensions() {
                     map.insert(*extension, *lang);
@@ -44,6 +56,32 @@ impl Language {
         EXTENSION_MAP.get(ext).copied()
     }
 
+    /// Like `from_file`, but extensions in `extension_map` take precedence over the built-in ones
+    pub fn from_file_with_config(
+        filename: &str,
+        extension_map: &HashMap<String, Language>,
+    ) -> Option<Self> {
+        let ext = filename.split('.').next_back().unwrap_or(filename);
+        extension_map
+            .get(ext)
+            .copied()
+            .or_else(|| Self::from_file(filename))
+    }
+
+    /// Parse a language from a user-provided name such as "rust", "ts", or "c++"
+    pub fn from_name(name: &str) -> Option<Language> {
+        match name.to_ascii_lowercase().as_str() {
+            "python" | "py" => Some(Language::Python),
+            "rust" | "rs" => Some(Language::Rust),
+            "typescript" | "ts" => Some(Language::TypeScript),
+            "cpp" | "c++" => Some(Language::Cpp),
+            "go" | "golang" => Some(Language::Go),
+            "elixir" | "ex" => Some(Language::Elixir),
+            "ruby" | "rb" => Some(Language::Ruby),
+            _ => None,
+        }
+    }
+
     #[allow(clippy::wrong_self_convention)]
     #[inline(always)]
     pub fn to_string(&self) -> &'static str {
@@ -52,6 +90,9 @@ impl Language {
             Language::Rust => "Rust",
             Language::TypeScript => "TypeScript",
             Language::Cpp => "C++",
+            Language::Go => "Go",
+            Language::Elixir => "Elixir",
+            Language::Ruby => "Ruby",
         }
     }
 
@@ -63,6 +104,9 @@ impl Language {
             Language::Rust => "#DEA584",
             Language::TypeScript => "#007ACC",
             Language::Cpp => "#00599C",
+            Language::Go => "#00ADD8",
+            Language::Elixir => "#6E4A7E",
+            Language::Ruby => "#CC342D",
         }
     }
 
@@ -78,19 +122,90 @@ impl Language {
                 Color::from_rgba(0.0, 122.0 / 255.0, 204.0 / 255.0, 1.0).unwrap()
             }
             Language::Cpp => Color::from_rgba(0.0, 89.0 / 255.0, 156.0 / 255.0, 1.0).unwrap(),
+            Language::Go => Color::from_rgba(0.0, 173.0 / 255.0, 216.0 / 255.0, 1.0).unwrap(),
+            Language::Elixir => {
+                Color::from_rgba(110.0 / 255.0, 74.0 / 255.0, 126.0 / 255.0, 1.0).unwrap()
+            }
+            Language::Ruby => {
+                Color::from_rgba(204.0 / 255.0, 52.0 / 255.0, 45.0 / 255.0, 1.0).unwrap()
+            }
         }
     }
 }
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+/// Languages are serialized by their display name (e.g. "C++")
+impl Serialize for Language {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Language {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Language::from_name(&name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown language: {name}")))
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Import {
     path: String,
     is_local: bool,
+    /// Only brings in types, e.g. TypeScript's `import type { Foo } from "./foo"`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    is_type_only: bool,
+    /// Names brought in from the module, e.g. `a` and `b` for Python's `from x import a, b`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    names: Vec<String>,
+    /// Passes the module on to importers of this file, e.g. TypeScript's `export * from "./a"`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    is_re_export: bool,
+    /// Only used by test code, e.g. inside a Rust `#[cfg(test)]` module
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    is_test: bool,
 }
 
 impl Import {
     pub fn new(path: String, is_local: bool) -> Self {
-        Import { path, is_local }
+        Import {
+            path,
+            is_local,
+            is_type_only: false,
+            names: Vec::new(),
+            is_re_export: false,
+            is_test: false,
+        }
+    }
+
+    /// Create an import that only brings in types
+    pub fn new_type_only(path: String, is_local: bool) -> Self {
+        Import {
+            path,
+            is_local,
+            is_type_only: true,
+            names: Vec::new(),
+            is_re_export: false,
+            is_test: false,
+        }
+    }
+
+    /// Record the names imported from the module
+    pub fn with_names(mut self, names: Vec<String>) -> Self {
+        self.names = names;
+        self
+    }
+
+    /// Mark the import as re-exporting the module to this file's importers
+    pub fn as_re_export(mut self) -> Self {
+        self.is_re_export = true;
+        self
+    }
+
+    /// Mark the import as only used by test code
+    pub fn as_test(mut self) -> Self {
+        self.is_test = true;
+        self
     }
 
     /// Get the import path
@@ -105,9 +220,33 @@ impl Import {
     pub fn is_local(&self) -> bool {
         self.is_local
     }
+
+    /// Check if this import only brings in types, which may be filtered out of the graph
+    #[inline(always)]
+    pub fn is_type_only(&self) -> bool {
+        self.is_type_only
+    }
+
+    /// Names imported from the module, if the import statement lists them
+    #[inline(always)]
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Check if importers of this file also depend on the imported module
+    #[inline(always)]
+    pub fn is_re_export(&self) -> bool {
+        self.is_re_export
+    }
+
+    /// Check if only test code uses this import
+    #[inline(always)]
+    pub fn is_test(&self) -> bool {
+        self.is_test
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileNode {
     file: PathBuf,
     loc: u32,
@@ -143,6 +282,12 @@ impl FileNode {
         }
     }
 
+    /// The same file contents, attributed to `file`
+    pub fn with_file(mut self, file: PathBuf) -> Self {
+        self.file = file;
+        self
+    }
+
     #[inline(always)]
     pub fn file(&self) -> &PathBuf {
         &self.file
@@ -177,19 +322,136 @@ impl FileNode {
     pub fn external_references(&self) -> &HashSet<String> {
         &self.external_references
     }
+
+    /// Share of this file's imports that are local to the project, or `None` if it
+    /// imports nothing. Low values point at files that mostly glue external libraries.
+    pub fn import_locality(&self) -> Option<f64> {
+        if self.imports.is_empty() {
+            return None;
+        }
+        let local = self.imports.iter().filter(|i| i.is_local()).count();
+        Some(local as f64 / self.imports.len() as f64)
+    }
+
+    /// Whether this file looks like generated code (see `is_generated_file`)
+    pub fn is_generated(&self) -> bool {
+        is_generated_file(&self.file)
+    }
+
+    /// Whether this file looks like a program or package entry point (see `is_entry_point_file`)
+    pub fn is_entry_point(&self) -> bool {
+        is_entry_point_file(&self.file)
+    }
+}
+
+/// Guess whether a file is where a program or package starts, such as `main.rs`,
+/// `lib.rs`, `__main__.py`, `main.go` or `index.ts`
+pub fn is_entry_point_file(path: &Path) -> bool {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    matches!(file_name, "lib.rs" | "__main__.py")
+        || (stem == "main" && Language::from_file(file_name).is_some())
+        || (stem == "index" && Language::from_file(file_name) == Some(Language::TypeScript))
+}
+
+/// Guess whether a file was produced by a code generator such as protoc or thrift,
+/// based on common output naming conventions
+pub fn is_generated_file(path: &Path) -> bool {
+    const GENERATED_SUFFIXES: &[&str] = &[
+        ".pb.rs",
+        ".pb.go",
+        ".pb.cc",
+        ".pb.h",
+        ".pb.ts",
+        "_pb.ts",
+        "_pb2.py",
+        "_pb2_grpc.py",
+        "_pb2.pyi",
+        "_grpc_pb.ts",
+    ];
+
+    let in_thrift_output = path.components().any(|component| {
+        component
+            .as_os_str()
+            .to_str()
+            .is_some_and(|name| name.starts_with("gen-"))
+    });
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    in_thrift_output
+        || GENERATED_SUFFIXES
+            .iter()
+            .any(|suffix| file_name.ends_with(suffix))
+}
+
+/// Guess whether a file only holds tests: anything under a `tests` directory, and files
+/// named like `parser_test.go`, `parser_test.py` or `test_parser.py`
+pub fn is_test_file(path: &Path) -> bool {
+    let in_tests_dir = path.parent().is_some_and(|dir| {
+        dir.components()
+            .any(|component| component.as_os_str() == "tests")
+    });
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let is_python = path.extension().is_some_and(|ext| ext == "py");
+    in_tests_dir || stem.ends_with("_test") || (is_python && stem.starts_with("test_"))
+}
+
+/// Count the lines in a file's contents the way an editor numbers them: an empty file has
+/// no lines, and a trailing newline ends the last line rather than starting a new one.
+/// `"a\nb"` and `"a\nb\n"` are both 2 lines, while `"a\nb\n\n"` is 3.
+pub fn count_loc(code: &str) -> u32 {
+    let newlines = code.matches('\n').count() as u32;
+    if code.is_empty() || code.ends_with('\n') {
+        newlines
+    } else {
+        newlines + 1
+    }
+}
+
+/// Shorten `label` to at most `max_len` characters, ending in an ellipsis if anything was cut
+pub fn truncate_label(label: &str, max_len: Option<usize>) -> String {
+    match max_len {
+        Some(max_len) if label.chars().count() > max_len => {
+            let kept: String = label.chars().take(max_len.saturating_sub(1)).collect();
+            format!("{kept}…")
+        }
+        _ => label.to_string(),
+    }
 }
 
 /// A node in the project graph, with edges to other nodes it references
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct GraphNode {
+    #[serde(flatten)]
     data: FileNode,
     /// Edges to other files (by file path)
     edges: Vec<PathBuf>,
+    /// The subset of `edges` that only test code depends on
+    #[serde(skip_serializing_if = "HashSet::is_empty")]
+    test_edges: HashSet<PathBuf>,
 }
 
 impl GraphNode {
-    pub fn new(data: FileNode, edges: Vec<PathBuf>) -> Self {
-        GraphNode { data, edges }
+    /// A node for `data` depending on `edges`; an edge back to the file itself, or a
+    /// repeated one, is dropped
+    pub fn new(data: FileNode, mut edges: Vec<PathBuf>) -> Self {
+        let mut seen = HashSet::new();
+        edges.retain(|edge| edge != &data.file && seen.insert(edge.clone()));
+        GraphNode {
+            data,
+            edges,
+            test_edges: HashSet::new(),
+        }
+    }
+
+    /// Mark the edges to `test_edges` as only used by test code
+    pub fn with_test_edges(mut self, test_edges: HashSet<PathBuf>) -> Self {
+        self.test_edges = test_edges;
+        self
+    }
+
+    /// Check if only test code depends on `target`
+    pub fn is_test_edge(&self, target: &Path) -> bool {
+        self.test_edges.contains(target)
     }
 
     #[inline(always)]
@@ -202,6 +464,12 @@ impl GraphNode {
         self.edges.as_ref()
     }
 
+    /// The label drawn on this node: its file stem, truncated to `max_len` characters
+    pub fn label(&self, max_len: Option<usize>) -> Option<String> {
+        let stem = self.data.file().file_stem()?.to_str()?;
+        Some(truncate_label(stem, max_len))
+    }
+
     /// Calculate the normalized size for this node based on min/max LOC and betweenness centrality
     /// Returns a value between min_size and max_size
     pub fn calculate_size(
@@ -230,3 +498,151 @@ impl GraphNode {
         }
     }
 }
+
+#[cfg(test)]
+impl GraphNode {
+    /// A node for `file`, with no imports, functions or containers, depending on `edges`
+    pub fn for_test<P: AsRef<Path>>(file: P, language: Language, loc: u32, edges: &[P]) -> Self {
+        let data = FileNode::new(
+            file.as_ref().to_path_buf(),
+            loc,
+            language,
+            HashSet::new(),
+            HashSet::new(),
+            HashSet::new(),
+            HashSet::new(),
+        );
+        let edges = edges
+            .iter()
+            .map(|edge| edge.as_ref().to_path_buf())
+            .collect();
+        GraphNode::new(data, edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notebook_detection() {
+        assert_eq!(
+            Language::from_file("analysis.ipynb"),
+            Some(Language::Python)
+        );
+    }
+
+    #[test]
+    fn test_cpp_detection() {
+        assert_eq!(Language::from_file("foo.cpp"), Some(Language::Cpp));
+        assert_eq!(Language::from_file("foo.hpp"), Some(Language::Cpp));
+        assert_eq!(Language::from_file("src/foo.h"), Some(Language::Cpp));
+        assert_eq!(Language::from_file("foo.cc"), Some(Language::Cpp));
+    }
+
+    #[test]
+    fn test_typescript_module_detection() {
+        assert_eq!(Language::from_file("foo.mts"), Some(Language::TypeScript));
+        assert_eq!(Language::from_file("foo.cts"), Some(Language::TypeScript));
+        // Only the last extension counts
+        assert_eq!(
+            Language::from_file("src/types.d.mts"),
+            Some(Language::TypeScript)
+        );
+        assert_eq!(Language::from_file("bundle.mts.map"), None);
+    }
+
+    #[test]
+    fn test_language_from_name() {
+        assert_eq!(Language::from_name("Rust"), Some(Language::Rust));
+        assert_eq!(Language::from_name("ts"), Some(Language::TypeScript));
+        assert_eq!(Language::from_name("C++"), Some(Language::Cpp));
+        assert_eq!(Language::from_name("golang"), Some(Language::Go));
+        assert_eq!(Language::from_name("cobol"), None);
+    }
+
+    #[test]
+    fn test_generated_files() {
+        assert!(is_generated_file(Path::new("src/service.pb.rs")));
+        assert!(is_generated_file(Path::new("api/service_pb2.py")));
+        assert!(is_generated_file(Path::new("api/service_pb2_grpc.py")));
+        assert!(is_generated_file(Path::new("proto/service.pb.go")));
+        assert!(is_generated_file(Path::new("gen-cpp/Service.h")));
+        assert!(!is_generated_file(Path::new("src/service.rs")));
+        assert!(!is_generated_file(Path::new("src/pb.rs")));
+        assert!(!is_generated_file(Path::new("src/generator.py")));
+    }
+
+    #[test]
+    fn test_test_files() {
+        assert!(is_test_file(Path::new("tests/analyze_project.rs")));
+        assert!(is_test_file(Path::new("crates/core/tests/common/mod.rs")));
+        assert!(is_test_file(Path::new("pkg/parser_test.go")));
+        assert!(is_test_file(Path::new("app/test_models.py")));
+        assert!(is_test_file(Path::new("app/models_test.py")));
+        assert!(!is_test_file(Path::new("src/tests.rs")));
+        assert!(!is_test_file(Path::new("src/test_utils.rs")));
+        assert!(!is_test_file(Path::new("app/testing.py")));
+    }
+
+    #[test]
+    fn test_entry_point_files() {
+        assert!(is_entry_point_file(Path::new("src/main.rs")));
+        assert!(is_entry_point_file(Path::new("src/lib.rs")));
+        assert!(is_entry_point_file(Path::new("cmd/server/main.go")));
+        assert!(is_entry_point_file(Path::new("app/__main__.py")));
+        assert!(is_entry_point_file(Path::new("web/src/index.tsx")));
+        assert!(!is_entry_point_file(Path::new("src/domain.rs")));
+        assert!(!is_entry_point_file(Path::new("docs/main.md")));
+    }
+
+    #[test]
+    fn test_import_locality() {
+        let imports = HashSet::from([
+            Import::new("crate::core".to_string(), true),
+            Import::new("crate::export".to_string(), true),
+            Import::new("serde".to_string(), false),
+            Import::new("std::fs".to_string(), false),
+        ]);
+        let node = |imports| {
+            FileNode::new(
+                PathBuf::from("src/lib.rs"),
+                10,
+                Language::Rust,
+                imports,
+                HashSet::new(),
+                HashSet::new(),
+                HashSet::new(),
+            )
+        };
+
+        assert_eq!(node(imports).import_locality(), Some(0.5));
+        assert_eq!(node(HashSet::new()).import_locality(), None);
+    }
+
+    #[test]
+    fn test_count_loc() {
+        assert_eq!(count_loc(""), 0);
+        assert_eq!(count_loc("a"), 1);
+        assert_eq!(count_loc("a\nb"), 2);
+        assert_eq!(count_loc("a\nb\n"), 2);
+        assert_eq!(count_loc("a\r\nb\r\n"), 2);
+        assert_eq!(count_loc("a\nb\n\n"), 3);
+        assert_eq!(count_loc("\n"), 1);
+    }
+
+    #[test]
+    fn test_truncate_label() {
+        assert_eq!(truncate_label("resolvers", None), "resolvers");
+        assert_eq!(truncate_label("resolvers", Some(9)), "resolvers");
+        assert_eq!(truncate_label("resolvers", Some(6)), "resol…");
+        assert_eq!(truncate_label("résolveurs", Some(3)), "ré…");
+        assert_eq!(truncate_label("resolvers", Some(0)), "…");
+    }
+
+    #[test]
+    fn test_cpp_display() {
+        assert_eq!(Language::Cpp.to_string(), "C++");
+        assert_ne!(Language::Cpp.color(), Language::TypeScript.color());
+    }
+}
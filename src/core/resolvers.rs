@@ -1,24 +1,175 @@
-use crate::core::defs::{FileNode, GraphNode, Language};
+use crate::core::defs::{FileNode, GraphNode, Language, is_test_file};
 use crate::core::resolvers::cpp::CppResolver;
+use crate::core::resolvers::elixir::ElixirResolver;
+use crate::core::resolvers::go::GoResolver;
 use crate::core::resolvers::python::PythonResolver;
+use crate::core::resolvers::ruby::RubyResolver;
 use crate::core::resolvers::rust::RustResolver;
 use crate::core::resolvers::typescript::TypeScriptResolver;
 use std::collections::{HashMap, HashSet};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 pub mod cpp;
+pub mod elixir;
+pub mod go;
 pub mod python;
+pub mod ruby;
 pub mod rust;
 pub mod typescript;
 
+/// Normalize a path to handle './' and '../' segments cleanly
+pub(crate) fn normalize_path(target_path: &Path) -> PathBuf {
+    let mut components = Vec::new();
+    for component in target_path.components() {
+        match component {
+            Component::ParentDir => {
+                if let Some(Component::Normal(_)) = components.last() {
+                    components.pop();
+                } else {
+                    components.push(component);
+                }
+            }
+            Component::CurDir => {}
+            _ => {
+                components.push(component);
+            }
+        }
+    }
+    components.iter().collect()
+}
+
+/// Outgoing edges of one file, without self-edges or duplicates, however a resolver
+/// spelled the target (e.g. `src/./lib.rs` or `src/a/../lib.rs`)
+struct EdgeSet {
+    from: PathBuf,
+    edges: Vec<PathBuf>,
+    seen: HashSet<PathBuf>,
+    /// Normalized targets that code outside of tests depends on
+    non_test: HashSet<PathBuf>,
+}
+
+impl EdgeSet {
+    fn new(from_file: &Path) -> Self {
+        Self {
+            from: normalize_path(from_file),
+            edges: Vec::new(),
+            seen: HashSet::new(),
+            non_test: HashSet::new(),
+        }
+    }
+
+    /// Add an edge to `target`, unless it points back at the file itself or is already present.
+    /// It stays a test edge as long as every import of `target` came from test code.
+    fn insert(&mut self, target: PathBuf, is_test: bool) {
+        let normalized = normalize_path(&target);
+        if normalized == self.from {
+            return;
+        }
+        if !is_test {
+            self.non_test.insert(normalized.clone());
+        }
+        if self.seen.insert(normalized) {
+            self.edges.push(target);
+        }
+    }
+
+    /// Edges that only test code depends on
+    fn test_edges(&self) -> HashSet<PathBuf> {
+        self.edges
+            .iter()
+            .filter(|edge| !self.non_test.contains(&normalize_path(edge)))
+            .cloned()
+            .collect()
+    }
+
+    fn is_test_edge(&self, target: &Path) -> bool {
+        !self.non_test.contains(&normalize_path(target))
+    }
+}
+
+/// How many re-export hops past an imported barrel file are followed by default
+pub const DEFAULT_REEXPORT_DEPTH: usize = 2;
+
+/// Add edges to the modules that `direct` targets re-export, then to the ones those
+/// re-export, up to `depth` hops. Each file is visited once, so barrels that
+/// re-export each other can't loop. A re-exported module is a test dependency
+/// when the barrel it came through is.
+fn follow_re_exports(
+    edges: &mut EdgeSet,
+    direct: &[PathBuf],
+    re_exports: &HashMap<PathBuf, Vec<PathBuf>>,
+    depth: usize,
+) {
+    let mut visited: HashSet<PathBuf> = direct.iter().map(|file| normalize_path(file)).collect();
+    visited.insert(edges.from.clone());
+    let mut frontier: Vec<(PathBuf, bool)> = direct
+        .iter()
+        .map(|file| (file.clone(), edges.is_test_edge(file)))
+        .collect();
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for (barrel, is_test) in &frontier {
+            for target in re_exports
+                .get(&normalize_path(barrel))
+                .into_iter()
+                .flatten()
+            {
+                edges.insert(target.clone(), *is_test);
+                if visited.insert(normalize_path(target)) {
+                    next.push((target.clone(), *is_test));
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+}
+
 /// Module resolution trait
-pub trait LanguageResolver {
+pub trait LanguageResolver: Send {
     /// Build module mapping for this language
     fn build_module_map(&mut self, files: &[PathBuf], project_root: &Path);
 
     /// Resolve an import path to a file path for this language
     fn resolve_import(&self, import_path: &str, from_file: &Path) -> Option<PathBuf>;
 
+    /// Resolve an import that brings in specific `names` from a module. By default only
+    /// the module itself is resolved; languages where a name can be a module of its own
+    /// (e.g. Python's `from package import submodule`) resolve those too.
+    fn resolve_import_names(
+        &self,
+        import_path: &str,
+        _names: &[String],
+        from_file: &Path,
+    ) -> Vec<PathBuf> {
+        self.resolve_import(import_path, from_file)
+            .into_iter()
+            .collect()
+    }
+
+    /// Whether a non-local import is mapped to project files, e.g. by a path alias
+    fn is_aliased_import(&self, _import_path: &str) -> bool {
+        false
+    }
+
+    /// Resolve a non-local import that [`Self::is_aliased_import`] accepted. Defaults to
+    /// [`Self::resolve_import_names`]; languages that search other places for non-local
+    /// imports (e.g. C++ angle-bracket includes) override it.
+    fn resolve_aliased_import_names(
+        &self,
+        import_path: &str,
+        names: &[String],
+        from_file: &Path,
+    ) -> Vec<PathBuf> {
+        self.resolve_import_names(import_path, names, from_file)
+    }
+
+    /// Try these extensions first, in order, when an extensionless import could match
+    /// several files (e.g. `foo.ts` and `foo.tsx`)
+    fn set_extension_priority(&mut self, _extensions: &[String]) {}
+
     /// Get additional edges from external references
     fn resolve_external_references(
         &self,
@@ -27,9 +178,36 @@ pub trait LanguageResolver {
     ) -> Vec<PathBuf>;
 }
 
+/// Reorder `extensions` so the ones listed in `priority` come first, in that order.
+/// Prioritized extensions the language doesn't already use are added as well.
+pub fn prioritize_extensions(extensions: &[String], priority: &[String]) -> Vec<String> {
+    let mut ordered: Vec<String> = Vec::new();
+    for ext in priority.iter().chain(extensions) {
+        let ext = ext.trim_start_matches('.');
+        if !ordered.iter().any(|existing| existing == ext) {
+            ordered.push(ext.to_string());
+        }
+    }
+    ordered
+}
+
 /// Multi-language graph builder
 pub struct GraphBuilder {
     resolvers: HashMap<Language, Box<dyn LanguageResolver>>,
+    /// Local imports that no file could be found for, by importing file
+    unresolved: HashMap<PathBuf, Vec<String>>,
+    /// Leave type-only imports out of the graph
+    skip_type_imports: bool,
+    /// How many re-export hops to follow past an imported barrel file
+    reexport_depth: usize,
+    /// Files each file re-exports, keyed by its normalized path
+    re_exports: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl Default for GraphBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl GraphBuilder {
@@ -39,7 +217,67 @@ impl GraphBuilder {
         resolvers.insert(Language::Rust, Box::new(RustResolver::new()));
         resolvers.insert(Language::TypeScript, Box::new(TypeScriptResolver::new()));
         resolvers.insert(Language::Cpp, Box::new(CppResolver::new()));
-        Self { resolvers }
+        resolvers.insert(Language::Go, Box::new(GoResolver::new()));
+        resolvers.insert(Language::Elixir, Box::new(ElixirResolver::new()));
+        resolvers.insert(Language::Ruby, Box::new(RubyResolver::new()));
+        Self {
+            resolvers,
+            unresolved: HashMap::new(),
+            skip_type_imports: false,
+            reexport_depth: DEFAULT_REEXPORT_DEPTH,
+            re_exports: HashMap::new(),
+        }
+    }
+
+    /// Override which extensions the resolver for `language` tries first
+    pub fn set_extension_priority(&mut self, language: Language, extensions: &[String]) {
+        if let Some(resolver) = self.resolvers.get_mut(&language) {
+            resolver.set_extension_priority(extensions);
+        }
+    }
+
+    /// Don't create edges for type-only imports, such as TypeScript's `import type`
+    pub fn set_skip_type_imports(&mut self, skip: bool) {
+        self.skip_type_imports = skip;
+    }
+
+    /// Also depend on the modules an imported file re-exports, up to `depth` hops away;
+    /// `0` only links files to what they import directly
+    pub fn set_reexport_depth(&mut self, depth: usize) {
+        self.reexport_depth = depth;
+    }
+
+    /// Local imports from the last `build_graph_edges` run that didn't resolve to any file,
+    /// keyed by the importing file and sorted by import path
+    pub fn unresolved_imports(&self) -> &HashMap<PathBuf, Vec<String>> {
+        &self.unresolved
+    }
+
+    /// Resolve a single import with the resolver for `language`.
+    /// Module maps are only populated after `build_module_maps` or `build_graph_edges` has run.
+    pub fn resolve_import(
+        &self,
+        language: Language,
+        import_path: &str,
+        from_file: &Path,
+    ) -> Option<PathBuf> {
+        self.resolvers
+            .get(&language)?
+            .resolve_import(import_path, from_file)
+    }
+
+    /// Build each language's module map from the paths of its files, which is all
+    /// `resolve_import` needs; `build_graph_edges` does this itself
+    pub fn build_module_maps(
+        &mut self,
+        files_by_language: &HashMap<Language, Vec<PathBuf>>,
+        project_root: &Path,
+    ) {
+        for (language, files) in files_by_language {
+            if let Some(resolver) = self.resolvers.get_mut(language) {
+                resolver.build_module_map(files, project_root);
+            }
+        }
     }
 
     /// Build graph edges for all languages
@@ -48,57 +286,495 @@ impl GraphBuilder {
         node_map: &HashMap<PathBuf, FileNode>,
         project_root: &Path,
     ) -> Vec<GraphNode> {
-        // Group files by language
-        let mut files_by_language: HashMap<Language, Vec<PathBuf>> = HashMap::new();
-        for (file_path, node) in node_map {
-            files_by_language
-                .entry(*node.language())
-                .or_default()
-                .push(file_path.clone());
+        self.build_module_maps(&files_by_language(node_map), project_root);
+
+        // Build edges for each node
+        self.unresolved.clear();
+        self.re_exports.clear();
+        let direct_edges: Vec<(&FileNode, EdgeSet)> = node_map
+            .iter()
+            .map(|(file_path, node)| (node, self.direct_edges(file_path, node, project_root)))
+            .collect();
+
+        direct_edges
+            .into_iter()
+            .map(|(node, edges)| self.graph_node(node, edges))
+            .collect()
+    }
+
+    /// Rebuild the edges of the `changed` files, which were parsed again, added to or
+    /// removed from `node_map` since `graph_nodes` was built, and of every file with an edge
+    /// to one of them. Other files keep their edges without resolving their imports again.
+    pub fn update_graph_edges(
+        &mut self,
+        node_map: &HashMap<PathBuf, FileNode>,
+        project_root: &Path,
+        graph_nodes: &mut Vec<GraphNode>,
+        changed: &HashSet<PathBuf>,
+    ) {
+        let known: HashSet<PathBuf> = graph_nodes
+            .iter()
+            .map(|node| node.data().file().clone())
+            .collect();
+        let added: Vec<&PathBuf> = changed
+            .iter()
+            .filter(|file| node_map.contains_key(*file) && !known.contains(*file))
+            .collect();
+        let removed = changed
+            .iter()
+            .any(|file| known.contains(file) && !node_map.contains_key(file));
+        let any_added = !added.is_empty();
+        // Module maps only depend on which files exist
+        if removed || any_added {
+            self.build_module_maps(&files_by_language(node_map), project_root);
         }
 
-        // Build module maps for each language
-        for (language, files) in &files_by_language {
-            if let Some(resolver) = self.resolvers.get_mut(language) {
-                resolver.build_module_map(files, project_root);
-            }
+        graph_nodes.retain(|node| node_map.contains_key(node.data().file()));
+        for file in changed.iter().filter(|file| !node_map.contains_key(*file)) {
+            self.unresolved.remove(file);
+            self.re_exports.remove(&normalize_path(file));
+        }
+        for file in added {
+            graph_nodes.push(GraphNode::new(node_map[file].clone(), Vec::new()));
         }
 
-        // Build edges for each node
-        let mut graph_nodes = Vec::new();
-        for (file_path, node) in node_map {
-            let mut edges = Vec::new();
-            let mut resolved_imports = HashSet::new();
-
-            // Use language-specific resolver
-            if let Some(resolver) = self.resolvers.get(node.language()) {
-                for import in node.imports() {
-                    if !import.is_local() {
-                        continue; // Skip non-local imports for now
-                    }
-                    if let Some(target_file) = resolver.resolve_import(import.path(), file_path)
-                        && target_file != *file_path
-                        && !resolved_imports.contains(&target_file)
-                    {
-                        edges.push(target_file.clone());
-                        resolved_imports.insert(target_file);
-                    }
-                }
+        // Besides the changed files and the files depending on them, a new file may be
+        // what an unresolved import was looking for
+        let normalized: HashSet<PathBuf> =
+            changed.iter().map(|file| normalize_path(file)).collect();
+        let affected: Vec<usize> = (0..graph_nodes.len())
+            .filter(|&i| {
+                let node = &graph_nodes[i];
+                let file = node.data().file();
+                changed.contains(file)
+                    || any_added && self.unresolved.contains_key(file)
+                    || node
+                        .edges()
+                        .iter()
+                        .any(|edge| normalized.contains(&normalize_path(edge)))
+            })
+            .collect();
+
+        // Every affected file's re-exports must be known before any re-exports are followed
+        let direct_edges: Vec<(usize, EdgeSet)> = affected
+            .into_iter()
+            .map(|i| {
+                let file = graph_nodes[i].data().file().clone();
+                (i, self.direct_edges(&file, &node_map[&file], project_root))
+            })
+            .collect();
+        for (i, edges) in direct_edges {
+            let file = graph_nodes[i].data().file();
+            graph_nodes[i] = self.graph_node(&node_map[file], edges);
+        }
+    }
+
+    /// Resolve the imports and external references of one file, recording its
+    /// unresolved imports and re-exports
+    fn direct_edges(&mut self, file_path: &Path, node: &FileNode, project_root: &Path) -> EdgeSet {
+        // Imports that climb above this directory are external, even if they land on a real file
+        let root_dir = if project_root.is_file() {
+            project_root.parent().unwrap_or(project_root)
+        } else {
+            project_root
+        };
+
+        let mut edges = EdgeSet::new(file_path);
+        self.unresolved.remove(file_path);
+        self.re_exports.remove(&edges.from);
+        // Everything a test file imports is a test dependency
+        let test_file = is_test_file(file_path.strip_prefix(root_dir).unwrap_or(file_path));
 
-                // Process external references
-                let ext_refs =
-                    resolver.resolve_external_references(node.external_references(), file_path);
-                for target_file in ext_refs {
-                    if target_file != *file_path && !resolved_imports.contains(&target_file) {
-                        edges.push(target_file.clone());
-                        resolved_imports.insert(target_file);
+        // Use language-specific resolver
+        let Some(resolver) = self.resolvers.get(node.language()) else {
+            return edges;
+        };
+        for import in node.imports() {
+            if !import.is_local() && !resolver.is_aliased_import(import.path()) {
+                continue; // Skip non-local imports for now
+            }
+            if self.skip_type_imports && import.is_type_only() {
+                continue;
+            }
+            let target_files = if import.is_local() {
+                resolver.resolve_import_names(import.path(), import.names(), file_path)
+            } else {
+                resolver.resolve_aliased_import_names(import.path(), import.names(), file_path)
+            };
+            if target_files.is_empty() {
+                self.unresolved
+                    .entry(file_path.to_path_buf())
+                    .or_default()
+                    .push(import.path().to_string());
+                continue;
+            }
+            for target_file in target_files {
+                if target_file.starts_with(root_dir) {
+                    if import.is_re_export() {
+                        self.re_exports
+                            .entry(edges.from.clone())
+                            .or_default()
+                            .push(target_file.clone());
                     }
+                    edges.insert(target_file, test_file || import.is_test());
                 }
             }
+        }
+        if let Some(imports) = self.unresolved.get_mut(file_path) {
+            imports.sort();
+        }
+
+        // Process external references
+        let ext_refs = resolver.resolve_external_references(node.external_references(), file_path);
+        for target_file in ext_refs {
+            edges.insert(target_file, test_file);
+        }
+        edges
+    }
+
+    /// The graph node for `node`, with its direct edges plus what they re-export
+    fn graph_node(&self, node: &FileNode, mut edges: EdgeSet) -> GraphNode {
+        let direct = edges.edges.clone();
+        follow_re_exports(&mut edges, &direct, &self.re_exports, self.reexport_depth);
+        let test_edges = edges.test_edges();
+        GraphNode::new(node.clone(), edges.edges).with_test_edges(test_edges)
+    }
+}
+
+/// Paths of the files in `node_map`, grouped by language
+fn files_by_language(node_map: &HashMap<PathBuf, FileNode>) -> HashMap<Language, Vec<PathBuf>> {
+    let mut files_by_language: HashMap<Language, Vec<PathBuf>> = HashMap::new();
+    for (file_path, node) in node_map {
+        files_by_language
+            .entry(*node.language())
+            .or_default()
+            .push(file_path.clone());
+    }
+    files_by_language
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::defs::Import;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_file_node(file: PathBuf, language: Language, imports: &[&str]) -> FileNode {
+        FileNode::new(
+            file,
+            1,
+            language,
+            imports
+                .iter()
+                .map(|path| Import::new(path.to_string(), true))
+                .collect(),
+            HashSet::new(),
+            HashSet::new(),
+            HashSet::new(),
+        )
+    }
+
+    #[test]
+    fn test_relative_import_escaping_root_is_external() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("project");
+        fs::create_dir_all(root.join("src/deep")).unwrap();
+        fs::write(temp_dir.path().join("outside.ts"), "").unwrap();
+        fs::write(temp_dir.path().join("outside.py"), "").unwrap();
+        fs::write(root.join("src/inside.ts"), "").unwrap();
 
-            graph_nodes.push(GraphNode::new(node.clone(), edges));
+        let ts_file = root.join("src/deep/main.ts");
+        let py_file = root.join("src/deep/main.py");
+        let mut node_map = HashMap::new();
+        node_map.insert(
+            ts_file.clone(),
+            create_file_node(
+                ts_file.clone(),
+                Language::TypeScript,
+                &["../../../outside", "../inside"],
+            ),
+        );
+        node_map.insert(
+            py_file.clone(),
+            create_file_node(py_file.clone(), Language::Python, &["....outside"]),
+        );
+
+        let graph_nodes = GraphBuilder::new().build_graph_edges(&node_map, &root);
+
+        let ts_node = graph_nodes
+            .iter()
+            .find(|n| n.data().file() == &ts_file)
+            .unwrap();
+        assert_eq!(ts_node.edges(), &vec![root.join("src/inside.ts")]);
+
+        let py_node = graph_nodes
+            .iter()
+            .find(|n| n.data().file() == &py_file)
+            .unwrap();
+        assert!(py_node.edges().is_empty());
+    }
+
+    #[test]
+    fn test_re_export_depth_with_mutual_barrels() {
+        // main -> one; `one` and `two` re-export each other, plus a chain two -> three -> c
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let re_exports = [
+            ("main.ts", vec![], vec!["./one"]),
+            ("one.ts", vec!["./two", "./a"], vec![]),
+            ("two.ts", vec!["./one", "./b", "./three"], vec![]),
+            ("three.ts", vec!["./c"], vec![]),
+            ("a.ts", vec![], vec![]),
+            ("b.ts", vec![], vec![]),
+            ("c.ts", vec![], vec![]),
+        ];
+        let mut node_map = HashMap::new();
+        for (file, exported, imported) in &re_exports {
+            let path = root.join(file);
+            fs::write(&path, "").unwrap();
+            let imports = exported
+                .iter()
+                .map(|target| Import::new(target.to_string(), true).as_re_export())
+                .chain(
+                    imported
+                        .iter()
+                        .map(|target| Import::new(target.to_string(), true)),
+                )
+                .collect();
+            let node = FileNode::new(
+                path.clone(),
+                1,
+                Language::TypeScript,
+                imports,
+                HashSet::new(),
+                HashSet::new(),
+                HashSet::new(),
+            );
+            node_map.insert(path, node);
+        }
+
+        let edges_of_main = |depth: usize| {
+            let mut builder = GraphBuilder::new();
+            builder.set_reexport_depth(depth);
+            let graph_nodes = builder.build_graph_edges(&node_map, root);
+            let main = graph_nodes
+                .iter()
+                .find(|n| n.data().file() == &root.join("main.ts"))
+                .unwrap();
+            let mut names: Vec<String> = main
+                .edges()
+                .iter()
+                .map(|edge| edge.file_name().unwrap().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(edges_of_main(0), vec!["one.ts"]);
+        assert_eq!(edges_of_main(1), vec!["a.ts", "one.ts", "two.ts"]);
+        assert_eq!(
+            edges_of_main(2),
+            vec!["a.ts", "b.ts", "one.ts", "three.ts", "two.ts"]
+        );
+        // Following the cycle forever still ends once every file was visited
+        let all = vec!["a.ts", "b.ts", "c.ts", "one.ts", "three.ts", "two.ts"];
+        assert_eq!(edges_of_main(3), all);
+        assert_eq!(edges_of_main(usize::MAX), all);
+    }
+
+    #[test]
+    fn test_prioritize_extensions() {
+        let extensions: Vec<String> = ["h", "hpp", "cpp"].iter().map(|e| e.to_string()).collect();
+        let priority = vec![".hpp".to_string(), "ipp".to_string()];
+        assert_eq!(
+            prioritize_extensions(&extensions, &priority),
+            vec!["hpp", "ipp", "h", "cpp"]
+        );
+        assert_eq!(prioritize_extensions(&extensions, &[]), extensions);
+    }
+
+    #[test]
+    fn test_skip_type_imports() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let main_file = root.join("main.ts");
+        fs::write(root.join("types.ts"), "").unwrap();
+        fs::write(root.join("util.ts"), "").unwrap();
+
+        let imports = HashSet::from([
+            Import::new_type_only("./types".to_string(), true),
+            Import::new("./util".to_string(), true),
+        ]);
+        let main_node = FileNode::new(
+            main_file.clone(),
+            1,
+            Language::TypeScript,
+            imports,
+            HashSet::new(),
+            HashSet::new(),
+            HashSet::new(),
+        );
+        let node_map = HashMap::from([(main_file.clone(), main_node)]);
+
+        let mut builder = GraphBuilder::new();
+        let graph_nodes = builder.build_graph_edges(&node_map, root);
+        assert_eq!(graph_nodes[0].edges().len(), 2);
+
+        builder.set_skip_type_imports(true);
+        let graph_nodes = builder.build_graph_edges(&node_map, root);
+        assert_eq!(graph_nodes[0].edges(), &vec![root.join("util.ts")]);
+    }
+
+    #[test]
+    fn test_unresolved_local_imports_are_reported() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let main_file = root.join("main.rs");
+        let util_file = root.join("util.rs");
+        fs::write(&main_file, "mod util;\nmod missing;\n").unwrap();
+        fs::write(&util_file, "").unwrap();
+
+        let mut node_map = HashMap::new();
+        node_map.insert(
+            main_file.clone(),
+            create_file_node(main_file.clone(), Language::Rust, &["util", "missing"]),
+        );
+        node_map.insert(
+            util_file.clone(),
+            create_file_node(util_file.clone(), Language::Rust, &[]),
+        );
+
+        let mut builder = GraphBuilder::new();
+        let graph_nodes = builder.build_graph_edges(&node_map, root);
+
+        let main_node = graph_nodes
+            .iter()
+            .find(|n| n.data().file() == &main_file)
+            .unwrap();
+        assert_eq!(main_node.edges(), &vec![util_file.clone()]);
+
+        let unresolved = builder.unresolved_imports();
+        assert_eq!(unresolved.len(), 1);
+        assert_eq!(unresolved[&main_file], vec!["missing".to_string()]);
+    }
+
+    #[test]
+    fn test_edges_from_test_code_are_tagged() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for file in ["util.rs", "fixtures.rs", "shared.rs", "models.py"] {
+            fs::write(root.join(file), "").unwrap();
+        }
+        let lib_file = root.join("lib.rs");
+        fs::write(
+            &lib_file,
+            "mod util;\nmod shared;\n\n#[cfg(test)]\nmod tests {\n    use crate::fixtures;\n    use crate::shared;\n}\n",
+        )
+        .unwrap();
+        let test_file = root.join("test_models.py");
+
+        let mut node_map = HashMap::from([(
+            test_file.clone(),
+            create_file_node(test_file.clone(), Language::Python, &["models"]),
+        )]);
+        node_map.insert(
+            lib_file.clone(),
+            crate::parsers::rust::parse_rust_file(&lib_file).unwrap(),
+        );
+        for file in ["util.rs", "fixtures.rs", "shared.rs"] {
+            let file = root.join(file);
+            node_map.insert(file.clone(), create_file_node(file, Language::Rust, &[]));
+        }
+
+        let graph_nodes = GraphBuilder::new().build_graph_edges(&node_map, root);
+        let node = |file: &Path| {
+            graph_nodes
+                .iter()
+                .find(|n| n.data().file() == file)
+                .unwrap()
+        };
+
+        let lib_node = node(&lib_file);
+        assert!(lib_node.edges().contains(&root.join("fixtures.rs")));
+        assert!(lib_node.is_test_edge(&root.join("fixtures.rs")));
+        assert!(!lib_node.is_test_edge(&root.join("util.rs")));
+        // Also used outside of the tests
+        assert!(!lib_node.is_test_edge(&root.join("shared.rs")));
+
+        let test_node = node(&test_file);
+        assert_eq!(test_node.edges(), &vec![root.join("models.py")]);
+        assert!(test_node.is_test_edge(&root.join("models.py")));
+    }
+
+    #[test]
+    fn test_edge_set_drops_self_and_duplicate_edges() {
+        let mut edges = EdgeSet::new(Path::new("/project/src/lib.rs"));
+        edges.insert(PathBuf::from("/project/src/./lib.rs"), false);
+        edges.insert(PathBuf::from("/project/src/core/../lib.rs"), false);
+        edges.insert(PathBuf::from("/project/src/util.rs"), false);
+        edges.insert(PathBuf::from("/project/src/./util.rs"), false);
+        assert_eq!(edges.edges, vec![PathBuf::from("/project/src/util.rs")]);
+    }
+
+    #[test]
+    fn test_module_referencing_itself_has_no_self_edge() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("util")).unwrap();
+        let lib_file = root.join("lib.rs");
+        let util_file = root.join("util/mod.rs");
+        let page_file = root.join("page.ts");
+        fs::write(&lib_file, "").unwrap();
+        fs::write(&util_file, "").unwrap();
+        fs::write(&page_file, "").unwrap();
+
+        let node_map = HashMap::from([
+            (
+                lib_file.clone(),
+                create_file_node(lib_file.clone(), Language::Rust, &["util"]),
+            ),
+            // `util/mod.rs` naming its own module, directly and through a re-export path
+            (
+                util_file.clone(),
+                create_file_node(
+                    util_file.clone(),
+                    Language::Rust,
+                    &["crate::util", "crate::util::helper", "super::util"],
+                ),
+            ),
+            (
+                page_file.clone(),
+                create_file_node(
+                    page_file.clone(),
+                    Language::TypeScript,
+                    &["./page", "../page"],
+                ),
+            ),
+        ]);
+
+        let graph_nodes = GraphBuilder::new().build_graph_edges(&node_map, root);
+        for node in &graph_nodes {
+            assert!(
+                !node.edges().contains(node.data().file()),
+                "{} depends on itself",
+                node.data().file().display()
+            );
+            let unique: HashSet<_> = node.edges().iter().collect();
+            assert_eq!(unique.len(), node.edges().len());
         }
 
-        graph_nodes
+        let lib_node = graph_nodes
+            .iter()
+            .find(|n| n.data().file() == &lib_file)
+            .unwrap();
+        assert_eq!(lib_node.edges(), &vec![util_file.clone()]);
+
+        // The invariant holds for nodes built by hand too
+        let node = GraphNode::new(
+            create_file_node(lib_file.clone(), Language::Rust, &[]),
+            vec![lib_file.clone(), util_file.clone(), util_file.clone()],
+        );
+        assert_eq!(node.edges(), &vec![util_file]);
     }
 }
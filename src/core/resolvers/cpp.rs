@@ -1,7 +1,63 @@
-use super::LanguageResolver;
+use super::{LanguageResolver, normalize_path, prioritize_extensions};
+use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Include directories passed to the compiler in a `compile_commands.json`, in the order
+/// first seen: `-iquote` directories (searched for quoted includes only) and `-I` /
+/// `-isystem` directories (searched for both kinds), each relative to its entry's directory
+fn compile_commands_include_dirs(text: &str) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let (mut quote_dirs, mut include_dirs) = (Vec::new(), Vec::new());
+    let Ok(serde_json::Value::Array(entries)) = serde_json::from_str(text) else {
+        return (quote_dirs, include_dirs);
+    };
+    for entry in &entries {
+        let directory = PathBuf::from(entry["directory"].as_str().unwrap_or(""));
+        let arguments: Vec<String> = match entry["arguments"].as_array() {
+            Some(arguments) => arguments
+                .iter()
+                .filter_map(|argument| argument.as_str().map(str::to_string))
+                .collect(),
+            None => entry["command"]
+                .as_str()
+                .unwrap_or("")
+                .split_whitespace()
+                .map(|argument| argument.trim_matches(['"', '\'']).to_string())
+                .collect(),
+        };
+
+        let mut arguments = arguments.iter();
+        while let Some(argument) = arguments.next() {
+            let Some((flag, value)) = ["-iquote", "-isystem", "-I"]
+                .into_iter()
+                .find_map(|flag| Some((flag, argument.strip_prefix(flag)?)))
+            else {
+                continue;
+            };
+            // The directory is either attached to the flag or the next argument
+            let value = if value.is_empty() {
+                match arguments.next() {
+                    Some(next) => next.as_str(),
+                    None => break,
+                }
+            } else {
+                value
+            };
+            let dirs = if flag == "-iquote" {
+                &mut quote_dirs
+            } else {
+                &mut include_dirs
+            };
+            let dir = normalize_path(&directory.join(value));
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+    (quote_dirs, include_dirs)
+}
+
 /// C++ include resolver with caching
 #[derive(Default)]
 pub struct CppResolver {
@@ -13,11 +69,29 @@ pub struct CppResolver {
     stdlib_headers: HashSet<String>,
     /// External library prefixes to exclude
     external_lib_prefixes: HashSet<String>,
+    /// Resolution results keyed by (including directory, include path, quoted)
+    include_cache: RefCell<HashMap<(PathBuf, String, bool), Option<PathBuf>>>,
+    /// Number of includes answered from the cache
+    cache_hits: Cell<usize>,
+    /// Number of includes that had to be searched on disk
+    cache_misses: Cell<usize>,
+    /// Extensions tried for includes written without one, in order
+    extensions: Vec<String>,
+    /// `-iquote` directories from `compile_commands.json`
+    quote_dirs: Vec<PathBuf>,
+    /// `-I` and `-isystem` directories from `compile_commands.json`
+    include_dirs: Vec<PathBuf>,
 }
 
 impl CppResolver {
     pub fn new() -> Self {
-        let mut resolver = Self::default();
+        let mut resolver = Self {
+            extensions: ["h", "hpp", "hxx", "h++", "cc", "cpp", "cxx", "c++"]
+                .iter()
+                .map(|ext| ext.to_string())
+                .collect(),
+            ..Self::default()
+        };
         resolver.init_stdlib_headers();
         resolver.init_external_lib_prefixes();
         resolver
@@ -135,6 +209,22 @@ impl CppResolver {
         }
     }
 
+    /// Get the number of cache hits and misses, in that order
+    pub fn cache_stats(&self) -> (usize, usize) {
+        (self.cache_hits.get(), self.cache_misses.get())
+    }
+
+    /// Get the fraction of lookups answered from the cache, or 0.0 if nothing was looked up
+    pub fn cache_hit_ratio(&self) -> f64 {
+        let (hits, misses) = self.cache_stats();
+        let total = hits + misses;
+        if total == 0 {
+            0.0
+        } else {
+            hits as f64 / total as f64
+        }
+    }
+
     /// Check if a header is a standard library header
     fn is_stdlib_header(&self, header_name: &str) -> bool {
         let normalized = header_name
@@ -233,9 +323,20 @@ impl CppResolver {
         }
     }
 
-    /// Try to find a file in common include directories
-    fn find_include_file(&self, include_path: &str, from_file: &Path) -> Option<PathBuf> {
+    /// Try to find a file in the include directories. Like a compiler, `quoted` includes
+    /// search the including file's directory first, then the `compile_commands.json`
+    /// directories, then common include directories; angle-bracket includes only search
+    /// the `compile_commands.json` directories.
+    fn find_include_file(
+        &self,
+        include_path: &str,
+        from_file: &Path,
+        quoted: bool,
+    ) -> Option<PathBuf> {
         let normalized = self.normalize_path(include_path);
+        if !quoted {
+            return self.search_include_dirs(&self.include_dirs, &normalized);
+        }
 
         // Build search directories, ordered by priority
         let mut search_dirs = Vec::new();
@@ -245,10 +346,19 @@ impl CppResolver {
             search_dirs.push(parent.to_path_buf());
         }
 
-        // 2. Project root
-        search_dirs.push(self.project_root.clone());
+        // 2. Directories the compiler is told about
+        for dir in self.quote_dirs.iter().chain(&self.include_dirs) {
+            if !search_dirs.contains(dir) {
+                search_dirs.push(dir.clone());
+            }
+        }
+
+        // 3. Project root
+        if !search_dirs.contains(&self.project_root) {
+            search_dirs.push(self.project_root.clone());
+        }
 
-        // 3. Common include directories
+        // 4. Common include directories
         let common_include_dirs = vec![
             "include",
             "include/public",
@@ -268,7 +378,7 @@ impl CppResolver {
             }
         }
 
-        // 4. Parent directories (for multi-level projects)
+        // 5. Parent directories (for multi-level projects)
         if let Some(parent) = from_file.parent() {
             let mut current_parent = parent.to_path_buf();
             let mut depth = 0;
@@ -291,18 +401,22 @@ impl CppResolver {
             }
         }
 
-        // Search for the include file in order of priority
+        self.search_include_dirs(&search_dirs, &normalized)
+    }
+
+    /// The first file matching the normalized include path in `search_dirs`, also trying
+    /// header extensions for includes written without one
+    fn search_include_dirs(&self, search_dirs: &[PathBuf], normalized: &str) -> Option<PathBuf> {
         for search_dir in search_dirs {
-            let candidate = search_dir.join(&normalized);
+            let candidate = search_dir.join(normalized);
             if candidate.exists() && candidate.is_file() {
                 return Some(candidate);
             }
 
             // Also check with different extensions for header files without extension
             if !normalized.contains('.') {
-                let extensions = vec![".h", ".hpp", ".hxx", ".h++", ".cc", ".cpp", ".cxx", ".c++"];
-                for ext in extensions {
-                    let with_ext = search_dir.join(format!("{}{}", &normalized, ext));
+                for ext in &self.extensions {
+                    let with_ext = search_dir.join(format!("{normalized}.{ext}"));
                     if with_ext.exists() && with_ext.is_file() {
                         return Some(with_ext);
                     }
@@ -312,11 +426,57 @@ impl CppResolver {
 
         None
     }
+
+    /// Resolve a quoted or angle-bracket include, caching the result
+    fn resolve_include(
+        &self,
+        import_path: &str,
+        from_file: &Path,
+        quoted: bool,
+    ) -> Option<PathBuf> {
+        // Check if this should be filtered (stdlib or external library)
+        if self.should_filter_include(import_path) {
+            return None; // Don't resolve system/external headers as they're external
+        }
+
+        // The search only depends on the including file's directory, so files
+        // in the same directory share cache entries; angle-bracket includes don't
+        // depend on it at all, so every file shares them
+        let from_dir = if quoted {
+            from_file.parent().unwrap_or(from_file).to_path_buf()
+        } else {
+            PathBuf::new()
+        };
+        let key = (from_dir, import_path.to_string(), quoted);
+        if let Some(cached) = self.include_cache.borrow().get(&key) {
+            self.cache_hits.set(self.cache_hits.get() + 1);
+            return cached.clone();
+        }
+
+        // Try to find the include file
+        self.cache_misses.set(self.cache_misses.get() + 1);
+        let resolved = self.find_include_file(import_path, from_file, quoted);
+        self.include_cache
+            .borrow_mut()
+            .insert(key, resolved.clone());
+        resolved
+    }
 }
 
 impl LanguageResolver for CppResolver {
+    fn set_extension_priority(&mut self, extensions: &[String]) {
+        self.extensions = prioritize_extensions(&self.extensions, extensions);
+        self.include_cache.borrow_mut().clear();
+    }
+
     fn build_module_map(&mut self, files: &[PathBuf], project_root: &Path) {
         self.project_root = project_root.to_path_buf();
+        self.include_cache.borrow_mut().clear();
+        self.include_to_file.clear();
+        (self.quote_dirs, self.include_dirs) =
+            fs::read_to_string(project_root.join("compile_commands.json"))
+                .map(|text| compile_commands_include_dirs(&text))
+                .unwrap_or_default();
 
         for file_path in files {
             // Map relative paths from project root to file paths
@@ -328,16 +488,26 @@ impl LanguageResolver for CppResolver {
     }
 
     fn resolve_import(&self, import_path: &str, from_file: &Path) -> Option<PathBuf> {
-        // Note: This is an immutable reference, so we can't update cache stats.
-        // In a real implementation with RefCell, we could track cache performance.
+        self.resolve_include(import_path, from_file, true)
+    }
 
-        // Check if this should be filtered (stdlib or external library)
-        if self.should_filter_include(import_path) {
-            return None; // Don't resolve system/external headers as they're external
-        }
+    /// Angle-bracket includes found in a `compile_commands.json` include directory
+    fn is_aliased_import(&self, import_path: &str) -> bool {
+        !self.include_dirs.is_empty()
+            && self
+                .resolve_include(import_path, Path::new(""), false)
+                .is_some()
+    }
 
-        // Try to find the include file
-        self.find_include_file(import_path, from_file)
+    fn resolve_aliased_import_names(
+        &self,
+        import_path: &str,
+        _names: &[String],
+        from_file: &Path,
+    ) -> Vec<PathBuf> {
+        self.resolve_include(import_path, from_file, false)
+            .into_iter()
+            .collect()
     }
 
     fn resolve_external_references(
@@ -441,7 +611,7 @@ mod tests {
         let mut resolver = CppResolver::new();
         resolver.build_module_map(std::slice::from_ref(&include_file), project_root);
 
-        let result = resolver.find_include_file("helper.h", &source_file);
+        let result = resolver.find_include_file("helper.h", &source_file, true);
         assert!(result.is_some());
         assert_eq!(result.unwrap(), include_file);
     }
@@ -468,7 +638,7 @@ mod tests {
         let mut resolver = CppResolver::new();
         resolver.build_module_map(std::slice::from_ref(&include_file), project_root);
 
-        let result = resolver.find_include_file("include/helper.h", &source_file);
+        let result = resolver.find_include_file("include/helper.h", &source_file, true);
         assert!(result.is_some());
     }
 
@@ -483,7 +653,7 @@ mod tests {
         let mut resolver = CppResolver::new();
         resolver.build_module_map(&[], project_root);
 
-        let result = resolver.find_include_file("nonexistent.h", &source_file);
+        let result = resolver.find_include_file("nonexistent.h", &source_file, true);
         assert!(result.is_none());
     }
 
@@ -509,7 +679,7 @@ mod tests {
         resolver.build_module_map(std::slice::from_ref(&include_file), project_root);
 
         // Request without extension - resolver should find it with .hpp
-        let result = resolver.find_include_file("include/helper", &source_file);
+        let result = resolver.find_include_file("include/helper", &source_file, true);
         assert!(result.is_some());
     }
 
@@ -576,4 +746,98 @@ mod tests {
         assert!(!resolver.should_filter_include("myheader.h"));
         assert!(!resolver.should_filter_include("utils/helper.hpp"));
     }
+
+    #[test]
+    fn test_include_cache_hit_after_miss() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let root = temp_dir.path();
+        fs::write(root.join("main.cpp"), "#include \"util.h\"").unwrap();
+        fs::write(root.join("util.h"), "").unwrap();
+
+        let mut resolver = CppResolver::new();
+        resolver.build_module_map(&[root.join("main.cpp"), root.join("util.h")], root);
+        assert_eq!(resolver.cache_hit_ratio(), 0.0);
+
+        let from_file = root.join("main.cpp");
+        let first = resolver.resolve_import("util.h", &from_file);
+        assert_eq!(resolver.cache_stats(), (0, 1));
+
+        let second = resolver.resolve_import("util.h", &from_file);
+        assert_eq!(resolver.cache_stats(), (1, 1));
+        assert_eq!(first, Some(root.join("util.h")));
+        assert_eq!(first, second);
+        assert_eq!(resolver.cache_hit_ratio(), 0.5);
+    }
+
+    #[test]
+    fn test_include_dirs_from_compile_commands() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("vendor/mylib/api/foo")).unwrap();
+        fs::create_dir_all(root.join("app/foo")).unwrap();
+        fs::write(root.join("vendor/mylib/api/foo/bar.h"), "").unwrap();
+        fs::write(root.join("app/foo/bar.h"), "").unwrap();
+        let from_file = root.join("app/main.cpp");
+        fs::write(&from_file, "").unwrap();
+        let compile_commands = serde_json::json!([
+            {
+                "directory": root.join("build"),
+                "command": "c++ -I../vendor/mylib/api -isystem /usr/include/x -c ../app/main.cpp",
+                "file": "../app/main.cpp"
+            },
+            {
+                "directory": root,
+                "arguments": ["c++", "-I", "vendor/mylib/api", "-iquote", "app", "-c", "app/util.cpp"],
+                "file": "app/util.cpp"
+            }
+        ]);
+        fs::write(
+            root.join("compile_commands.json"),
+            compile_commands.to_string(),
+        )
+        .unwrap();
+
+        let (quote_dirs, include_dirs) =
+            compile_commands_include_dirs(&compile_commands.to_string());
+        assert_eq!(quote_dirs, vec![root.join("app")]);
+        assert_eq!(
+            include_dirs,
+            vec![
+                root.join("vendor/mylib/api"),
+                PathBuf::from("/usr/include/x"),
+            ]
+        );
+
+        let mut resolver = CppResolver::new();
+        resolver.build_module_map(std::slice::from_ref(&from_file), root);
+
+        // Angle-bracket includes only search the compiler's include directories
+        let include_file = root.join("vendor/mylib/api/foo/bar.h");
+        assert!(resolver.is_aliased_import("foo/bar.h"));
+        assert_eq!(
+            resolver.resolve_aliased_import_names("foo/bar.h", &[], &from_file),
+            vec![include_file.clone()]
+        );
+        // The include directories were only searched once
+        assert_eq!(resolver.cache_stats(), (1, 1));
+        assert!(!resolver.is_aliased_import("foo/missing.h"));
+
+        // Quoted includes look next to the including file first
+        assert_eq!(
+            resolver.resolve_import("foo/bar.h", &from_file),
+            Some(root.join("app/foo/bar.h"))
+        );
+        fs::remove_file(root.join("app/foo/bar.h")).unwrap();
+        resolver.build_module_map(std::slice::from_ref(&from_file), root);
+        assert_eq!(
+            resolver.resolve_import("foo/bar.h", &from_file),
+            Some(include_file)
+        );
+    }
 }
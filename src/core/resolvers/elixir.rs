@@ -0,0 +1,137 @@
+use super::LanguageResolver;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Find the nearest directory at or above `start` containing a mix.exs
+pub fn find_mix_project(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join("mix.exs").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Convert a module name segment to its file name, like `Macro.underscore`:
+/// `FooBar` becomes `foo_bar` and `HTTPClient` becomes `http_client`
+fn underscore(segment: &str) -> String {
+    let chars: Vec<char> = segment.chars().collect();
+    let mut result = String::with_capacity(segment.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                result.push('_');
+            }
+        }
+        result.extend(c.to_lowercase());
+    }
+    result
+}
+
+/// Files that define `module` by the `lib/` convention, e.g. `MyApp.FooBar` lives
+/// in `lib/my_app/foo_bar.ex` under the mix project root
+pub fn module_file_candidates(module: &str, project_root: &Path) -> Vec<PathBuf> {
+    let mut relative = project_root.join("lib");
+    for segment in module.split('.') {
+        relative.push(underscore(segment));
+    }
+    ["ex", "exs"]
+        .iter()
+        .map(|ext| relative.with_extension(ext))
+        .collect()
+}
+
+#[derive(Default)]
+pub struct ElixirResolver {
+    files: HashSet<PathBuf>,
+}
+
+impl ElixirResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl LanguageResolver for ElixirResolver {
+    fn build_module_map(&mut self, files: &[PathBuf], _project_root: &Path) {
+        self.files = files.iter().cloned().collect();
+    }
+
+    /// Modules are looked up relative to the mix project of the importing file,
+    /// so each app of an umbrella project resolves against its own `lib/`
+    fn resolve_import(&self, import_path: &str, from_file: &Path) -> Option<PathBuf> {
+        let project_root = find_mix_project(from_file.parent()?)?;
+        module_file_candidates(import_path, &project_root)
+            .into_iter()
+            .find(|candidate| self.files.contains(candidate))
+    }
+
+    fn resolve_external_references(
+        &self,
+        _references: &HashSet<String>,
+        _from_file: &Path,
+    ) -> Vec<PathBuf> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_underscore() {
+        assert_eq!(underscore("MyApp"), "my_app");
+        assert_eq!(underscore("HTTPClient"), "http_client");
+        assert_eq!(underscore("Base64Encoder"), "base64_encoder");
+        assert_eq!(underscore("Repo"), "repo");
+    }
+
+    /// Creates a mock mix project:
+    /// /
+    /// ├── mix.exs
+    /// └── lib/
+    ///     ├── my_app.ex
+    ///     └── my_app/
+    ///         ├── http_client.ex
+    ///         └── server.ex
+    #[test]
+    fn test_elixir_resolver_lib_convention() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("lib/my_app")).unwrap();
+        fs::write(root.join("mix.exs"), "").unwrap();
+        let files = vec![
+            root.join("lib/my_app.ex"),
+            root.join("lib/my_app/http_client.ex"),
+            root.join("lib/my_app/server.ex"),
+        ];
+        for file in &files {
+            fs::write(file, "").unwrap();
+        }
+
+        let mut resolver = ElixirResolver::new();
+        resolver.build_module_map(&files, root);
+        let from_file = root.join("lib/my_app/server.ex");
+
+        assert_eq!(
+            resolver.resolve_import("MyApp.HTTPClient", &from_file),
+            Some(root.join("lib/my_app/http_client.ex"))
+        );
+        assert_eq!(
+            resolver.resolve_import("MyApp", &from_file),
+            Some(root.join("lib/my_app.ex"))
+        );
+        assert!(
+            resolver
+                .resolve_import("MyApp.Missing", &from_file)
+                .is_none()
+        );
+        assert!(resolver.resolve_import("GenServer", &from_file).is_none());
+    }
+}
@@ -0,0 +1,150 @@
+use super::LanguageResolver;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Find the nearest go.mod at or above `start`, returning its directory and module path
+pub fn find_go_module(start: &Path) -> Option<(PathBuf, String)> {
+    start.ancestors().find_map(|dir| {
+        let go_mod = fs::read_to_string(dir.join("go.mod")).ok()?;
+        let module_path = go_mod.lines().find_map(|line| {
+            line.trim()
+                .strip_prefix("module")
+                .filter(|rest| rest.starts_with(char::is_whitespace))
+                .map(|rest| rest.trim().trim_matches('"').to_string())
+        })?;
+        Some((dir.to_path_buf(), module_path))
+    })
+}
+
+#[derive(Default)]
+pub struct GoResolver {
+    /// Directory containing go.mod
+    module_root: PathBuf,
+    /// Module path declared in go.mod (e.g. "github.com/user/project")
+    module_path: Option<String>,
+    /// Maps package directories to the Go files they contain
+    package_files: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl GoResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map an import path inside the module to its package directory
+    fn package_dir(&self, import_path: &str) -> Option<PathBuf> {
+        let module_path = self.module_path.as_deref()?;
+        if import_path == module_path {
+            return Some(self.module_root.clone());
+        }
+        let relative = import_path.strip_prefix(module_path)?.strip_prefix('/')?;
+        Some(self.module_root.join(relative))
+    }
+}
+
+impl LanguageResolver for GoResolver {
+    fn build_module_map(&mut self, files: &[PathBuf], project_root: &Path) {
+        let start = if project_root.is_file() {
+            project_root.parent().unwrap_or(project_root)
+        } else {
+            project_root
+        };
+        let (module_root, module_path) = find_go_module(start).unzip();
+        self.module_root = module_root.unwrap_or_default();
+        self.module_path = module_path;
+
+        self.package_files.clear();
+        for file in files {
+            if let Some(dir) = file.parent() {
+                self.package_files
+                    .entry(dir.to_path_buf())
+                    .or_default()
+                    .push(file.clone());
+            }
+        }
+        for files in self.package_files.values_mut() {
+            files.sort();
+        }
+    }
+
+    /// Go imports name a package directory, so a single file stands in for it: the one
+    /// named after the directory if present, otherwise the first non-test file
+    fn resolve_import(&self, import_path: &str, _from_file: &Path) -> Option<PathBuf> {
+        let dir = self.package_dir(import_path)?;
+        let files = self.package_files.get(&dir)?;
+
+        let dir_name = dir.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        files
+            .iter()
+            .find(|file| file.file_stem().is_some_and(|stem| stem == dir_name))
+            .or_else(|| {
+                files.iter().find(|file| {
+                    !file
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.ends_with("_test.go"))
+                })
+            })
+            .or_else(|| files.first())
+            .cloned()
+    }
+
+    fn resolve_external_references(
+        &self,
+        _references: &HashSet<String>,
+        _from_file: &Path,
+    ) -> Vec<PathBuf> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Creates a mock Go module:
+    /// /
+    /// ├── go.mod           // module example.com/app
+    /// ├── main.go
+    /// └── internal/
+    ///     └── store/
+    ///         ├── db.go
+    ///         └── store.go
+    #[test]
+    fn test_go_resolver_package_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("internal/store")).unwrap();
+        fs::write(root.join("go.mod"), "module example.com/app\n\ngo 1.22\n").unwrap();
+        let files = vec![
+            root.join("main.go"),
+            root.join("internal/store/db.go"),
+            root.join("internal/store/store.go"),
+        ];
+        for file in &files {
+            fs::write(file, "package main\n").unwrap();
+        }
+
+        let mut resolver = GoResolver::new();
+        resolver.build_module_map(&files, root);
+        let from_file = root.join("main.go");
+
+        let resolved = resolver.resolve_import("example.com/app/internal/store", &from_file);
+        assert_eq!(resolved, Some(root.join("internal/store/store.go")));
+
+        // Packages outside the module, or missing from it, are not resolved
+        assert!(resolver.resolve_import("fmt", &from_file).is_none());
+        assert!(
+            resolver
+                .resolve_import("example.com/app/internal/missing", &from_file)
+                .is_none()
+        );
+        assert!(
+            resolver
+                .resolve_import("example.com/application", &from_file)
+                .is_none()
+        );
+    }
+}
@@ -1,11 +1,191 @@
 use super::LanguageResolver;
 use std::collections::HashSet;
+use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Strip a `#` comment that isn't inside a string from a TOML line
+fn strip_toml_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, ch) in line.char_indices() {
+        match (ch, quote) {
+            ('"' | '\'', None) => quote = Some(ch),
+            (ch, Some(open)) if ch == open => quote = None,
+            ('#', None) => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Every quoted string in a TOML value, e.g. the items of `["src", 'lib']`
+fn toml_strings(value: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut current: Option<(char, String)> = None;
+    for ch in value.chars() {
+        match &mut current {
+            Some((open, text)) if ch == *open => {
+                strings.push(std::mem::take(text));
+                current = None;
+            }
+            Some((_, text)) => text.push(ch),
+            None if ch == '"' || ch == '\'' => current = Some((ch, String::new())),
+            None => {}
+        }
+    }
+    strings
+}
+
+/// The key/value pairs of each inline table in a TOML value, e.g.
+/// `[{ include = "pkg", from = "src" }]`
+fn toml_inline_tables(value: &str) -> Vec<Vec<(String, String)>> {
+    value
+        .split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}'))
+        .map(|(table, _)| {
+            table
+                .split(',')
+                .filter_map(|pair| pair.split_once('='))
+                .filter_map(|(key, value)| {
+                    let key = key.trim().trim_matches(['"', '\'']).to_string();
+                    Some((key, toml_strings(value).into_iter().next()?))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The settings of a TOML document as `(table, key, raw value)`, with arrays spanning
+/// several lines joined. Only as much of TOML as package settings in `pyproject.toml` use.
+fn toml_entries(text: &str) -> Vec<(String, String, String)> {
+    let open = |value: &str| value.matches(['[', '{']).count() > value.matches([']', '}']).count();
+    let mut entries = Vec::new();
+    let mut table = String::new();
+    let mut pending: Option<(String, String)> = None;
+    for line in text.lines() {
+        let line = strip_toml_comment(line).trim();
+        if let Some((key, mut value)) = pending.take() {
+            value.push(' ');
+            value.push_str(line);
+            if open(&value) {
+                pending = Some((key, value));
+            } else {
+                entries.push((table.clone(), key, value));
+            }
+        } else if line.starts_with('[') {
+            table = line.trim_matches(['[', ']']).trim().to_string();
+        } else if let Some((key, value)) = line.split_once('=') {
+            let key = key.trim().trim_matches(['"', '\'']).to_string();
+            let value = value.trim().to_string();
+            if open(&value) {
+                pending = Some((key, value));
+            } else {
+                entries.push((table.clone(), key, value));
+            }
+        }
+    }
+    entries
+}
+
+/// Where the project's packages live according to its `pyproject.toml`, as pairs of an
+/// import name prefix and a directory; an empty prefix is a directory (like `src`)
+/// holding top-level packages. Reads setuptools, Poetry and Hatch package settings, and
+/// otherwise finds a `src/<project name>` package.
+fn pyproject_package_dirs(text: &str, root: &Path) -> Vec<(String, PathBuf)> {
+    let mut dirs = Vec::new();
+    let mut project_name = None;
+    for (table, key, value) in toml_entries(text) {
+        match (table.as_str(), key.as_str()) {
+            ("tool.setuptools.package-dir", prefix) => {
+                if let Some(dir) = toml_strings(&value).into_iter().next() {
+                    dirs.push((prefix.to_string(), root.join(dir)));
+                }
+            }
+            ("tool.setuptools", "package-dir") => {
+                for table in toml_inline_tables(&value) {
+                    dirs.extend(
+                        table
+                            .into_iter()
+                            .map(|(prefix, dir)| (prefix, root.join(dir))),
+                    );
+                }
+            }
+            ("tool.setuptools.packages.find", "where") => {
+                dirs.extend(
+                    toml_strings(&value)
+                        .into_iter()
+                        .map(|dir| (String::new(), root.join(dir))),
+                );
+            }
+            ("tool.poetry", "packages") => {
+                for package in toml_inline_tables(&value) {
+                    let get = |key: &str| package.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+                    if let Some(include) = get("include") {
+                        let from = get("from").map_or(root.to_path_buf(), |from| root.join(from));
+                        dirs.push((include.replace('/', "."), from.join(include)));
+                    }
+                }
+            }
+            ("tool.hatch.build.targets.wheel", "packages") => {
+                for path in toml_strings(&value) {
+                    let dir = root.join(&path);
+                    if let Some(name) = dir.file_name() {
+                        dirs.push((name.to_string_lossy().to_string(), dir.clone()));
+                    }
+                }
+            }
+            ("project", "name") => project_name = toml_strings(&value).into_iter().next(),
+            _ => {}
+        }
+    }
+
+    // A `src` layout named after the project needs no package settings
+    if dirs.is_empty()
+        && let Some(name) = project_name
+    {
+        let name = name.replace(['-', '.'], "_").to_lowercase();
+        let dir = root.join("src").join(&name);
+        if dir.is_dir() {
+            dirs.push((name, dir));
+        }
+    }
+    dirs
+}
+
+/// The module `module` (e.g. `my.module.name`) under `base`, as a `.py` file or a package's
+/// `__init__.py`. An empty `module` is the package at `base` itself.
+fn find_module(base: &Path, module: &str) -> Option<PathBuf> {
+    if module.is_empty() {
+        let init_path = base.join("__init__.py");
+        return init_path.is_file().then_some(init_path);
+    }
+
+    // Convert 'my.module.name' to an OS-specific path 'my/module/name'
+    let relative_path = PathBuf::from(module.replace('.', std::path::MAIN_SEPARATOR_STR));
+    let mut potential_path = base.join(relative_path);
+
+    // 1. Check if it's a '.py' file (e.g., /root/my/module/name.py)
+    potential_path.set_extension("py");
+    if potential_path.is_file() {
+        return Some(potential_path);
+    }
+
+    // 2. Check if it's a package (e.g., /root/my/module/name/__init__.py)
+    potential_path.set_extension(""); // Unset '.py' before joining
+    let init_path = potential_path.join("__init__.py");
+    if init_path.is_file() {
+        return Some(init_path);
+    }
+
+    None
+}
+
 #[derive(Default)]
 pub struct PythonResolver {
     /// Project root directory
     project_root: PathBuf,
+    /// Import name prefixes and the directories they map to, from `pyproject.toml`
+    package_dirs: Vec<(String, PathBuf)>,
 }
 
 impl PythonResolver {
@@ -13,26 +193,27 @@ impl PythonResolver {
         Self::default()
     }
 
-    /// Resolves an absolute import path (e.g., `my_app.utils`) from the project root
+    /// Resolves an absolute import path (e.g., `my_app.utils`) from the project root, or
+    /// through the package directories declared in `pyproject.toml`
     fn resolve_absolute(&self, import_path: &str) -> Option<PathBuf> {
-        // Convert 'my.module.name' to an OS-specific path 'my/module/name'
-        let relative_path = PathBuf::from(import_path.replace('.', std::path::MAIN_SEPARATOR_STR));
-        let mut potential_path = self.project_root.join(relative_path);
-
-        // 1. Check if it's a '.py' file (e.g., /root/my/module/name.py)
-        potential_path.set_extension("py");
-        if potential_path.is_file() {
-            return Some(potential_path);
-        }
-
-        // 2. Check if it's a package (e.g., /root/my/module/name/__init__.py)
-        potential_path.set_extension(""); // Unset '.py' before joining
-        let init_path = potential_path.join("__init__.py");
-        if init_path.is_file() {
-            return Some(init_path);
-        }
+        find_module(&self.project_root, import_path)
+            .or_else(|| self.resolve_package_import(import_path))
+    }
 
-        None
+    /// Resolves an import of a package that `pyproject.toml` places in another directory,
+    /// e.g. `mypkg.models` to `src/mypkg/models.py`
+    fn resolve_package_import(&self, import_path: &str) -> Option<PathBuf> {
+        self.package_dirs.iter().find_map(|(prefix, dir)| {
+            if prefix.is_empty() {
+                return find_module(dir, import_path);
+            }
+            let rest = import_path.strip_prefix(prefix.as_str())?;
+            if rest.is_empty() {
+                find_module(dir, "")
+            } else {
+                find_module(dir, rest.strip_prefix('.')?)
+            }
+        })
     }
 
     /// Resolves a relative import path (e.g., `.utils` or `..api.routes`) from the file's location
@@ -84,6 +265,9 @@ impl PythonResolver {
 impl LanguageResolver for PythonResolver {
     fn build_module_map(&mut self, _files: &[PathBuf], project_root: &Path) {
         self.project_root = project_root.to_path_buf();
+        self.package_dirs = fs::read_to_string(project_root.join("pyproject.toml"))
+            .map(|text| pyproject_package_dirs(&text, project_root))
+            .unwrap_or_default();
     }
 
     fn resolve_import(&self, import_path: &str, from_file: &Path) -> Option<PathBuf> {
@@ -94,6 +278,40 @@ impl LanguageResolver for PythonResolver {
         }
     }
 
+    /// `from package import a, b` resolves each name that is a submodule (`package/a.py`
+    /// or `package/a/__init__.py`) to its own file. The package itself is kept for any
+    /// name that isn't a module, such as a function defined in its `__init__.py`.
+    fn resolve_import_names(
+        &self,
+        import_path: &str,
+        names: &[String],
+        from_file: &Path,
+    ) -> Vec<PathBuf> {
+        let mut resolved = Vec::new();
+        let mut needs_package = names.is_empty();
+        for name in names {
+            let submodule = if import_path.is_empty() || import_path.ends_with('.') {
+                format!("{import_path}{name}")
+            } else {
+                format!("{import_path}.{name}")
+            };
+            match self.resolve_import(&submodule, from_file) {
+                Some(file) => resolved.push(file),
+                None => needs_package = true,
+            }
+        }
+        if needs_package && let Some(package) = self.resolve_import(import_path, from_file) {
+            resolved.push(package);
+        }
+        resolved
+    }
+
+    /// Imports of packages declared in `pyproject.toml` look external next to the
+    /// importing file, e.g. from `tests/` into `src/`
+    fn is_aliased_import(&self, import_path: &str) -> bool {
+        self.resolve_package_import(import_path).is_some()
+    }
+
     fn resolve_external_references(
         &self,
         _references: &HashSet<String>,
@@ -168,6 +386,99 @@ mod tests {
         assert_eq!(resolved, root.join("utils.py"));
     }
 
+    #[test]
+    fn test_resolve_from_import_submodule() {
+        let temp_dir = TempDir::new().unwrap();
+        setup_test_project(&temp_dir);
+        let root = temp_dir.path();
+
+        let mut resolver = PythonResolver::new();
+        resolver.build_module_map(&[], root);
+        let from_file = root.join("main.py");
+
+        // `from api import routes` points at the submodule, not the package
+        let resolved = resolver.resolve_import_names("api", &["routes".to_string()], &from_file);
+        assert_eq!(resolved, vec![root.join("api/routes.py")]);
+
+        // Names that aren't modules fall back to the package's __init__.py
+        let resolved = resolver.resolve_import_names(
+            "api",
+            &["routes".to_string(), "create_app".to_string()],
+            &from_file,
+        );
+        assert_eq!(
+            resolved,
+            vec![root.join("api/routes.py"), root.join("api/__init__.py")]
+        );
+    }
+
+    #[test]
+    fn test_resolve_through_pyproject_packages() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/mypkg")).unwrap();
+        fs::create_dir(root.join("tests")).unwrap();
+        File::create(root.join("src/mypkg/__init__.py")).unwrap();
+        File::create(root.join("src/mypkg/models.py")).unwrap();
+        File::create(root.join("tests/test_models.py")).unwrap();
+        fs::write(
+            root.join("pyproject.toml"),
+            "[project]\nname = \"my-pkg\"\n\n[tool.setuptools.package-dir]\n\"\" = \"src\"  # src layout\n",
+        )
+        .unwrap();
+
+        let mut resolver = PythonResolver::new();
+        resolver.build_module_map(&[], root);
+        let from_file = root.join("tests/test_models.py");
+
+        assert_eq!(
+            resolver.resolve_import("mypkg", &from_file),
+            Some(root.join("src/mypkg/__init__.py"))
+        );
+        assert_eq!(
+            resolver.resolve_import("mypkg.models", &from_file),
+            Some(root.join("src/mypkg/models.py"))
+        );
+        assert!(resolver.is_aliased_import("mypkg.models"));
+        assert!(!resolver.is_aliased_import("requests"));
+    }
+
+    #[test]
+    fn test_pyproject_package_dirs() {
+        let root = Path::new("/project");
+        let poetry = r#"
+[tool.poetry]
+name = "app"
+packages = [
+    { include = "app", from = "src" },
+    { include = "tools" },
+]
+"#;
+        assert_eq!(
+            pyproject_package_dirs(poetry, root),
+            vec![
+                ("app".to_string(), root.join("src/app")),
+                ("tools".to_string(), root.join("tools")),
+            ]
+        );
+
+        let setuptools = "[tool.setuptools]\npackage-dir = {\"mypkg\" = \"lib/mypkg\"}\n\n[tool.setuptools.packages.find]\nwhere = [\"src\", 'vendor']\n";
+        assert_eq!(
+            pyproject_package_dirs(setuptools, root),
+            vec![
+                ("mypkg".to_string(), root.join("lib/mypkg")),
+                (String::new(), root.join("src")),
+                (String::new(), root.join("vendor")),
+            ]
+        );
+
+        let hatch = "[tool.hatch.build.targets.wheel]\npackages = [\"src/widgets\"]\n";
+        assert_eq!(
+            pyproject_package_dirs(hatch, root),
+            vec![("widgets".to_string(), root.join("src/widgets"))]
+        );
+    }
+
     #[test]
     fn test_import_non_existent_module() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,94 @@
+use super::{LanguageResolver, normalize_path};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Default)]
+pub struct RubyResolver {
+    files: HashSet<PathBuf>,
+}
+
+impl RubyResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl LanguageResolver for RubyResolver {
+    fn build_module_map(&mut self, files: &[PathBuf], _project_root: &Path) {
+        self.files = files.iter().map(|file| normalize_path(file)).collect();
+    }
+
+    /// Only `require_relative` paths are local; they are relative to the requiring
+    /// file's directory, with the `.rb` extension usually left off
+    fn resolve_import(&self, import_path: &str, from_file: &Path) -> Option<PathBuf> {
+        let target = normalize_path(&from_file.parent()?.join(import_path));
+        let with_extension = if target.extension().is_some_and(|ext| ext == "rb") {
+            target
+        } else {
+            let mut file_name = target.file_name()?.to_os_string();
+            file_name.push(".rb");
+            target.with_file_name(file_name)
+        };
+        self.files
+            .contains(&with_extension)
+            .then_some(with_extension)
+    }
+
+    fn resolve_external_references(
+        &self,
+        _references: &HashSet<String>,
+        _from_file: &Path,
+    ) -> Vec<PathBuf> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Creates a mock Ruby project:
+    /// /
+    /// ├── app.rb
+    /// ├── helpers.rb
+    /// ├── models/
+    /// │   └── user.rb
+    /// └── lib/
+    ///     └── tasks.rb
+    #[test]
+    fn test_ruby_resolver_require_relative() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("models")).unwrap();
+        fs::create_dir_all(root.join("lib")).unwrap();
+        let files = vec![
+            root.join("app.rb"),
+            root.join("helpers.rb"),
+            root.join("models/user.rb"),
+            root.join("lib/tasks.rb"),
+        ];
+        for file in &files {
+            fs::write(file, "").unwrap();
+        }
+
+        let mut resolver = RubyResolver::new();
+        resolver.build_module_map(&files, root);
+        let from_file = root.join("app.rb");
+
+        assert_eq!(
+            resolver.resolve_import("./models/user", &from_file),
+            Some(root.join("models/user.rb"))
+        );
+        assert_eq!(
+            resolver.resolve_import("helpers.rb", &from_file),
+            Some(root.join("helpers.rb"))
+        );
+        assert_eq!(
+            resolver.resolve_import("../helpers", &root.join("lib/tasks.rb")),
+            Some(root.join("helpers.rb"))
+        );
+        assert!(resolver.resolve_import("./missing", &from_file).is_none());
+    }
+}
@@ -97,11 +97,30 @@ impl RustResolver {
 
         None
     }
+
+    /// Find the file of `module_path`, or of the closest enclosing module when the path
+    /// ends in an item (`crate::defs::FileNode` -> `crate::defs`). The crate root itself
+    /// is never the fallback, so imports of unknown paths don't all land on `lib.rs`.
+    fn module_file(&self, module_path: &str) -> Option<PathBuf> {
+        let mut path = module_path;
+        loop {
+            if let Some(file) = self.module_to_file.get(path) {
+                return Some(file.clone());
+            }
+            let (parent, _) = path.rsplit_once("::")?;
+            if !parent.contains("::") {
+                return None;
+            }
+            path = parent;
+        }
+    }
 }
 
 impl LanguageResolver for RustResolver {
     fn build_module_map(&mut self, files: &[PathBuf], project_root: &Path) {
         self.project_root = project_root.to_path_buf();
+        self.module_to_file.clear();
+        self.file_to_module.clear();
 
         for file_path in files {
             if let Some(module_path) = self.file_path_to_module_path(file_path) {
@@ -123,7 +142,7 @@ impl LanguageResolver for RustResolver {
 
         if import_path.starts_with("crate::") {
             // Absolute crate import
-            self.module_to_file.get(import_path).cloned()
+            self.module_file(import_path)
         } else if import_path.starts_with("super::") {
             // Super import - go up one module level
             if let Some(current_module) = self.file_to_module.get(from_file) {
@@ -144,7 +163,7 @@ impl LanguageResolver for RustResolver {
                 new_parts.extend(remaining_path.split("::"));
                 let resolved_path = new_parts.join("::");
 
-                self.module_to_file.get(&resolved_path).cloned()
+                self.module_file(&resolved_path)
             } else {
                 None
             }
@@ -154,6 +173,13 @@ impl LanguageResolver for RustResolver {
                 let self_import = import_path.strip_prefix("self::").unwrap();
                 let current_parts: Vec<&str> = current_module.split("::").collect();
 
+                // A mod.rs file is the module itself, so its children sit directly below it
+                if from_file.file_name().is_some_and(|name| name == "mod.rs") {
+                    let mut new_parts = current_parts;
+                    new_parts.extend(self_import.split("::"));
+                    return self.module_file(&new_parts.join("::"));
+                }
+
                 if current_parts.len() <= 1 {
                     return None;
                 }
@@ -161,7 +187,7 @@ impl LanguageResolver for RustResolver {
                 let mut new_parts = current_parts[..current_parts.len() - 1].to_vec();
                 new_parts.extend(self_import.split("::"));
                 let resolved_path = new_parts.join("::");
-                self.module_to_file.get(&resolved_path).cloned()
+                self.module_file(&resolved_path)
             } else {
                 None
             }
@@ -177,7 +203,7 @@ impl LanguageResolver for RustResolver {
                 let mut new_parts = current_parts[..current_parts.len() - 1].to_vec();
                 new_parts.extend(import_path.split("::"));
                 let resolved_path = new_parts.join("::");
-                self.module_to_file.get(&resolved_path).cloned()
+                self.module_file(&resolved_path)
             } else {
                 None
             }
@@ -259,6 +285,32 @@ mod tests {
         assert_eq!(resolved, Some(root.join("api/routes.rs")));
     }
 
+    #[test]
+    fn test_rust_resolver_re_exported_item() {
+        let temp_dir = TempDir::new().unwrap();
+        setup_test_project(&temp_dir);
+        let root = temp_dir.path();
+        let files = vec![
+            root.join("lib.rs"),
+            root.join("utils.rs"),
+            root.join("api/mod.rs"),
+            root.join("api/routes.rs"),
+        ];
+
+        let mut resolver = RustResolver::new();
+        resolver.build_module_map(&files, root);
+
+        // `pub use crate::api::routes::Router;` names an item, which resolves to its module
+        let resolved = resolver.resolve_import("crate::api::routes::Router", &root.join("lib.rs"));
+        assert_eq!(resolved, Some(root.join("api/routes.rs")));
+
+        // Items directly in the crate root don't fall back to it
+        assert_eq!(
+            resolver.resolve_import("crate::Config", &root.join("utils.rs")),
+            None
+        );
+    }
+
     #[test]
     fn test_rust_resolver_super_and_mod_paths() {
         let temp_dir = TempDir::new().unwrap();
@@ -284,6 +336,54 @@ mod tests {
         assert_eq!(resolved, Some(root.join("api/routes.rs")));
     }
 
+    /// Creates a 2015-edition style project where modules live in `foo/mod.rs`:
+    /// /
+    /// └── src/
+    ///     ├── lib.rs
+    ///     └── foo/
+    ///         ├── mod.rs
+    ///         └── bar.rs
+    #[test]
+    fn test_rust_resolver_mod_rs_layout() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/foo")).unwrap();
+        fs::write(root.join("src/lib.rs"), "mod foo;").unwrap();
+        fs::write(root.join("src/foo/mod.rs"), "pub mod bar;").unwrap();
+        File::create(root.join("src/foo/bar.rs")).unwrap();
+        let files = vec![
+            root.join("src/lib.rs"),
+            root.join("src/foo/mod.rs"),
+            root.join("src/foo/bar.rs"),
+        ];
+
+        let mut resolver = RustResolver::new();
+        resolver.build_module_map(&files, root);
+
+        assert_eq!(
+            resolver.file_path_to_module_path(&root.join("src/foo/mod.rs")),
+            Some("crate::foo".to_string())
+        );
+        assert!(!resolver.module_to_file.contains_key("crate::foo::mod"));
+
+        let lib = root.join("src/lib.rs");
+        assert_eq!(
+            resolver.resolve_import("foo", &lib),
+            Some(root.join("src/foo/mod.rs"))
+        );
+        assert_eq!(
+            resolver.resolve_import("crate::foo", &lib),
+            Some(root.join("src/foo/mod.rs"))
+        );
+
+        // `self::` inside mod.rs refers to the `foo` module itself
+        let foo_mod = root.join("src/foo/mod.rs");
+        assert_eq!(
+            resolver.resolve_import("self::bar", &foo_mod),
+            Some(root.join("src/foo/bar.rs"))
+        );
+    }
+
     #[test]
     fn test_rust_resolver_non_existent() {
         let temp_dir = TempDir::new().unwrap();
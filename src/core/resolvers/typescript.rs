@@ -1,71 +1,235 @@
-use super::LanguageResolver;
+use super::{LanguageResolver, normalize_path, prioritize_extensions};
 use crate::core::defs::Language;
+use serde_json::Value;
 use std::collections::HashSet;
-use std::path::{Component, Path, PathBuf};
+use std::fs;
+use std::path::{Path, PathBuf};
 
-pub struct TypeScriptResolver;
+/// A `compilerOptions.paths` entry, e.g. `"@app/*": ["src/app/*"]`
+struct PathAlias {
+    /// Specifier pattern, with at most one `*` wildcard
+    pattern: String,
+    /// Replacement patterns, relative to `base_dir`
+    targets: Vec<String>,
+}
+
+impl PathAlias {
+    /// Returns the text matched by the wildcard (empty for exact patterns)
+    fn match_specifier<'a>(&self, specifier: &'a str) -> Option<&'a str> {
+        match self.pattern.split_once('*') {
+            Some((prefix, suffix)) => specifier.strip_prefix(prefix)?.strip_suffix(suffix),
+            None => (specifier == self.pattern).then_some(""),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TypeScriptResolver {
+    /// Directory that alias targets are relative to (`baseUrl`, or the tsconfig.json directory)
+    base_dir: PathBuf,
+    /// Aliases from the nearest tsconfig.json, most specific first
+    path_aliases: Vec<PathAlias>,
+    /// Extensions tried when resolving a module, in order
+    extensions: Vec<String>,
+}
 
 impl TypeScriptResolver {
     pub fn new() -> Self {
-        Self
+        Self {
+            extensions: Language::TypeScript
+                .extensions()
+                .iter()
+                .map(|ext| ext.to_string())
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Load `baseUrl` and `paths` from the nearest tsconfig.json at or above `project_root`
+    fn load_tsconfig(&mut self, project_root: &Path) {
+        self.path_aliases.clear();
+
+        let start = if project_root.is_file() {
+            project_root.parent()
+        } else {
+            Some(project_root)
+        };
+        let Some(tsconfig_path) = start
+            .into_iter()
+            .flat_map(Path::ancestors)
+            .map(|dir| dir.join("tsconfig.json"))
+            .find(|path| path.is_file())
+        else {
+            return;
+        };
+        let Some(config) = fs::read_to_string(&tsconfig_path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<Value>(&strip_json_comments(&text)).ok())
+        else {
+            return;
+        };
+
+        let config_dir = tsconfig_path.parent().unwrap_or(Path::new(""));
+        let options = &config["compilerOptions"];
+        self.base_dir = match options["baseUrl"].as_str() {
+            Some(base_url) => config_dir.join(base_url),
+            None => config_dir.to_path_buf(),
+        };
+
+        if let Some(paths) = options["paths"].as_object() {
+            for (pattern, targets) in paths {
+                let targets = targets
+                    .as_array()
+                    .map(|targets| {
+                        targets
+                            .iter()
+                            .filter_map(|t| t.as_str().map(str::to_string))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                self.path_aliases.push(PathAlias {
+                    pattern: pattern.clone(),
+                    targets,
+                });
+            }
+        }
+
+        // TypeScript picks the pattern with the longest prefix before the wildcard
+        self.path_aliases.sort_by_key(|alias| {
+            std::cmp::Reverse(alias.pattern.split('*').next().unwrap_or("").len())
+        });
+    }
+
+    /// Rewrite an aliased specifier to a real file using the tsconfig `paths`
+    fn resolve_aliased_import(&self, import_path: &str) -> Option<PathBuf> {
+        self.path_aliases.iter().find_map(|alias| {
+            let matched = alias.match_specifier(import_path)?;
+            alias.targets.iter().find_map(|target| {
+                let target_path = self.base_dir.join(target.replacen('*', matched, 1));
+                resolve_module_file(&normalize_path(&target_path), &self.extensions)
+            })
+        })
     }
 
     /// Resolves a relative import path into a full PathBuf
     fn resolve_relative_import(&self, import_path_str: &str, from_file: &Path) -> Option<PathBuf> {
         let from_dir = from_file.parent()?;
-        let target_path = from_dir.join(import_path_str);
-
-        // normalize the path to handle './' and '../' segments cleanly
-        let mut components = Vec::new();
-        for component in target_path.components() {
-            match component {
-                Component::ParentDir => {
-                    if let Some(Component::Normal(_)) = components.last() {
-                        components.pop();
-                    } else {
-                        components.push(component);
-                    }
-                }
-                Component::CurDir => {}
-                _ => {
-                    components.push(component);
-                }
+        resolve_module_file(
+            &normalize_path(&from_dir.join(import_path_str)),
+            &self.extensions,
+        )
+    }
+}
+
+/// Find the file a module path refers to: the path itself if it already has one of the
+/// `extensions`, then each extension appended (so `./foo.service` finds
+/// `foo.service.ts`), then each one in place of its last extension (so ESM's `./foo.js`
+/// finds `foo.ts`), and finally an index file in a directory of that name
+fn resolve_module_file(normalized_path: &Path, extensions: &[String]) -> Option<PathBuf> {
+    let has_known_extension = normalized_path
+        .extension()
+        .is_some_and(|ext| extensions.iter().any(|known| ext == known.as_str()));
+    if has_known_extension && normalized_path.is_file() {
+        return Some(normalized_path.to_path_buf());
+    }
+
+    if let Some(file_name) = normalized_path.file_name() {
+        for ext in extensions {
+            let mut with_ext = file_name.to_os_string();
+            with_ext.push(format!(".{ext}"));
+            let path_with_ext = normalized_path.with_file_name(with_ext);
+            if path_with_ext.is_file() {
+                return Some(path_with_ext);
             }
         }
-        let normalized_path: PathBuf = components.iter().collect();
+    }
 
-        for ext in Language::TypeScript.extensions() {
+    if normalized_path.extension().is_some() {
+        for ext in extensions {
             let path_with_ext = normalized_path.with_extension(ext);
             if path_with_ext.is_file() {
                 return Some(path_with_ext);
             }
         }
+    }
 
-        // check for index file in directory (e.g., ./foo/index.ts)
-        if normalized_path.is_dir() {
-            for ext in Language::TypeScript.extensions() {
-                let index_path = normalized_path.join(format!("index.{ext}"));
-                if index_path.is_file() {
-                    return Some(index_path);
-                }
+    // check for index file in directory (e.g., ./foo/index.ts)
+    if normalized_path.is_dir() {
+        for ext in extensions {
+            let index_path = normalized_path.join(format!("index.{ext}"));
+            if index_path.is_file() {
+                return Some(index_path);
+            }
+        }
+    }
+
+    None
+}
+
+/// Remove `//` and `/* */` comments, which tsconfig.json allows but JSON does not
+fn strip_json_comments(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => output.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
             }
+            continue;
         }
 
-        None
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|&next| next != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            _ => output.push(c),
+        }
     }
+
+    output
 }
 
 impl LanguageResolver for TypeScriptResolver {
-    fn build_module_map(&mut self, _files: &[PathBuf], _project_root: &Path) {}
+    fn build_module_map(&mut self, _files: &[PathBuf], project_root: &Path) {
+        self.load_tsconfig(project_root);
+    }
 
     fn resolve_import(&self, import_path: &str, from_file: &Path) -> Option<PathBuf> {
         if is_local_import(import_path) {
             self.resolve_relative_import(import_path, from_file)
         } else {
-            None
+            // Bare specifiers are packages unless tsconfig.json maps them to project files
+            self.resolve_aliased_import(import_path)
         }
     }
 
+    fn set_extension_priority(&mut self, extensions: &[String]) {
+        self.extensions = prioritize_extensions(&self.extensions, extensions);
+    }
+
+    fn is_aliased_import(&self, import_path: &str) -> bool {
+        self.path_aliases
+            .iter()
+            .any(|alias| alias.match_specifier(import_path).is_some())
+    }
+
     fn resolve_external_references(
         &self,
         _references: &HashSet<String>,
@@ -91,7 +255,10 @@ mod tests {
     /// ├── main.ts
     /// ├── components/
     /// │   ├── button.ts
-    /// │   └── index.ts // we do not currently support TSX
+    /// │   └── index.ts
+    /// ├── widgets/
+    /// │   ├── Card.tsx
+    /// │   └── index.tsx
     /// └── utils.ts
     fn setup_test_project(dir: &TempDir) {
         let root = dir.path();
@@ -101,6 +268,10 @@ mod tests {
         File::create(root.join("utils.ts")).unwrap();
         File::create(root.join("components/button.ts")).unwrap();
         File::create(root.join("components/index.ts")).unwrap();
+
+        fs::create_dir_all(root.join("widgets")).unwrap();
+        File::create(root.join("widgets/Card.tsx")).unwrap();
+        File::create(root.join("widgets/index.tsx")).unwrap();
     }
 
     #[test]
@@ -136,6 +307,97 @@ mod tests {
         // Import a .ts file directly
         let resolved = resolver.resolve_import("./components/button", &from_file);
         assert_eq!(resolved, Some(root.join("components/button.ts")));
+
+        // TSX files and directories with an index.tsx
+        let resolved = resolver.resolve_import("./widgets/Card", &from_file);
+        assert_eq!(resolved, Some(root.join("widgets/Card.tsx")));
+        let resolved = resolver.resolve_import("./widgets", &from_file);
+        assert_eq!(resolved, Some(root.join("widgets/index.tsx")));
+    }
+
+    #[test]
+    fn test_ts_resolver_tsconfig_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("packages/lib")).unwrap();
+        fs::create_dir_all(root.join("apps/web")).unwrap();
+        File::create(root.join("packages/lib/util.ts")).unwrap();
+        File::create(root.join("apps/web/main.ts")).unwrap();
+        fs::write(
+            root.join("tsconfig.json"),
+            r#"{
+                // comments are allowed in tsconfig.json
+                "compilerOptions": {
+                    "baseUrl": ".",
+                    "paths": { "@lib/*": ["packages/lib/*"] }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let mut resolver = TypeScriptResolver::new();
+        resolver.build_module_map(&[], root);
+        let from_file = root.join("apps/web/main.ts");
+
+        let resolved = resolver.resolve_import("@lib/util", &from_file);
+        assert_eq!(resolved, Some(root.join("packages/lib/util.ts")));
+        assert!(resolver.is_aliased_import("@lib/util"));
+
+        // Non-aliased bare specifiers are still packages
+        assert!(resolver.resolve_import("react", &from_file).is_none());
+        assert!(!resolver.is_aliased_import("react"));
+    }
+
+    #[test]
+    fn test_ts_resolver_module_extensions() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for file in ["main.mts", "bar.mts", "legacy.cts", "user.service.ts"] {
+            File::create(root.join(file)).unwrap();
+        }
+        let from_file = root.join("main.mts");
+        let resolver = TypeScriptResolver::new();
+
+        assert_eq!(
+            resolver.resolve_import("./bar", &from_file),
+            Some(root.join("bar.mts"))
+        );
+        assert_eq!(
+            resolver.resolve_import("./legacy", &from_file),
+            Some(root.join("legacy.cts"))
+        );
+        // ESM imports name the compiled file
+        assert_eq!(
+            resolver.resolve_import("./bar.mjs", &from_file),
+            Some(root.join("bar.mts"))
+        );
+        // A dot in the file name is not mistaken for an extension
+        assert_eq!(
+            resolver.resolve_import("./user.service", &from_file),
+            Some(root.join("user.service.ts"))
+        );
+        assert_eq!(
+            resolver.resolve_import("./bar.mts", &from_file),
+            Some(root.join("bar.mts"))
+        );
+    }
+
+    #[test]
+    fn test_ts_resolver_extension_priority() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        File::create(root.join("main.ts")).unwrap();
+        File::create(root.join("foo.ts")).unwrap();
+        File::create(root.join("foo.tsx")).unwrap();
+        let from_file = root.join("main.ts");
+
+        let mut resolver = TypeScriptResolver::new();
+        let resolved = resolver.resolve_import("./foo", &from_file);
+        assert_eq!(resolved, Some(root.join("foo.ts")));
+
+        resolver.set_extension_priority(&[".tsx".to_string()]);
+        let resolved = resolver.resolve_import("./foo", &from_file);
+        assert_eq!(resolved, Some(root.join("foo.tsx")));
     }
 
     #[test]
@@ -0,0 +1,199 @@
+use crate::core::defs::GraphNode;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Files and dependencies that differ between two graphs of the same project. Paths
+/// are rooted at the newer graph's project root, including those of removed files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphDiff {
+    pub added_files: BTreeSet<PathBuf>,
+    pub removed_files: BTreeSet<PathBuf>,
+    /// `(from, to)` dependencies only in the newer graph
+    pub added_edges: BTreeSet<(PathBuf, PathBuf)>,
+    /// `(from, to)` dependencies only in the older graph
+    pub removed_edges: BTreeSet<(PathBuf, PathBuf)>,
+}
+
+/// How a file or dependency changed between the two graphs of a [`GraphDiff`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffState {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+impl GraphDiff {
+    pub fn file_state(&self, file: &Path) -> DiffState {
+        if self.added_files.contains(file) {
+            DiffState::Added
+        } else if self.removed_files.contains(file) {
+            DiffState::Removed
+        } else {
+            DiffState::Unchanged
+        }
+    }
+
+    pub fn edge_state(&self, from: &Path, to: &Path) -> DiffState {
+        let edge = (from.to_path_buf(), to.to_path_buf());
+        if self.added_edges.contains(&edge) {
+            DiffState::Added
+        } else if self.removed_edges.contains(&edge) {
+            DiffState::Removed
+        } else {
+            DiffState::Unchanged
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_files.is_empty()
+            && self.removed_files.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+/// Move `path` from under `from_root` to the same place under `to_root`
+fn reroot(path: &Path, from_root: &Path, to_root: &Path) -> PathBuf {
+    match path.strip_prefix(from_root) {
+        Ok(relative) => to_root.join(relative),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Files and edges of a graph, with every path moved under `root`
+fn files_and_edges(
+    graph_nodes: &[GraphNode],
+    graph_root: &Path,
+    root: &Path,
+) -> (BTreeSet<PathBuf>, BTreeSet<(PathBuf, PathBuf)>) {
+    let files: BTreeSet<PathBuf> = graph_nodes
+        .iter()
+        .map(|node| reroot(node.data().file(), graph_root, root))
+        .collect();
+    let edges = graph_nodes
+        .iter()
+        .flat_map(|node| {
+            let from = reroot(node.data().file(), graph_root, root);
+            node.edges()
+                .iter()
+                .map(move |to| (from.clone(), reroot(to, graph_root, root)))
+        })
+        .filter(|(_, to)| files.contains(to))
+        .collect();
+    (files, edges)
+}
+
+/// Compare the graph of an older checkout of a project, rooted at `old_root`, with
+/// the current one rooted at `new_root`, matching files by their path in the project
+pub fn graph_diff(
+    old_nodes: &[GraphNode],
+    old_root: &Path,
+    new_nodes: &[GraphNode],
+    new_root: &Path,
+) -> GraphDiff {
+    let (old_files, old_edges) = files_and_edges(old_nodes, old_root, new_root);
+    let (new_files, new_edges) = files_and_edges(new_nodes, new_root, new_root);
+    GraphDiff {
+        added_files: new_files.difference(&old_files).cloned().collect(),
+        removed_files: old_files.difference(&new_files).cloned().collect(),
+        added_edges: new_edges.difference(&old_edges).cloned().collect(),
+        removed_edges: old_edges.difference(&new_edges).cloned().collect(),
+    }
+}
+
+/// The current graph plus whatever `diff` says was removed from the old one, so both
+/// states can be drawn at once. Removed files are moved under `new_root`, and removed
+/// dependencies are added back to the files they came from.
+pub fn overlay_nodes(
+    old_nodes: &[GraphNode],
+    old_root: &Path,
+    new_nodes: &[GraphNode],
+    new_root: &Path,
+    diff: &GraphDiff,
+) -> Vec<GraphNode> {
+    let mut removed_edges: HashMap<&PathBuf, Vec<PathBuf>> = HashMap::new();
+    for (from, to) in &diff.removed_edges {
+        removed_edges.entry(from).or_default().push(to.clone());
+    }
+    let with_removed_edges = |file: &PathBuf, mut edges: Vec<PathBuf>| {
+        edges.extend(removed_edges.get(file).into_iter().flatten().cloned());
+        edges
+    };
+
+    let mut overlay: Vec<GraphNode> = new_nodes
+        .iter()
+        .map(|node| {
+            let edges = with_removed_edges(node.data().file(), node.edges().clone());
+            GraphNode::new(node.data().clone(), edges)
+        })
+        .collect();
+    for node in old_nodes {
+        let file = reroot(node.data().file(), old_root, new_root);
+        if diff.removed_files.contains(&file) {
+            let edges = with_removed_edges(&file, Vec::new());
+            overlay.push(GraphNode::new(node.data().clone().with_file(file), edges));
+        }
+    }
+    overlay
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::defs::Language;
+
+    fn create_node(root: &str, file: &str, edges: &[&str]) -> GraphNode {
+        let root = Path::new(root);
+        let edges: Vec<PathBuf> = edges.iter().map(|edge| root.join(edge)).collect();
+        GraphNode::for_test(root.join(file), Language::Rust, 10, &edges)
+    }
+
+    #[test]
+    fn test_graph_diff_and_overlay() {
+        let old_nodes = vec![
+            create_node("/old", "main.rs", &["util.rs", "legacy.rs"]),
+            create_node("/old", "util.rs", &[]),
+            create_node("/old", "legacy.rs", &["util.rs"]),
+        ];
+        let new_nodes = vec![
+            create_node("/new", "main.rs", &["util.rs", "config.rs"]),
+            create_node("/new", "util.rs", &[]),
+            create_node("/new", "config.rs", &["util.rs"]),
+        ];
+        let (old_root, new_root) = (Path::new("/old"), Path::new("/new"));
+        let at = |file: &str| new_root.join(file);
+
+        let diff = graph_diff(&old_nodes, old_root, &new_nodes, new_root);
+        assert_eq!(diff.added_files, BTreeSet::from([at("config.rs")]));
+        assert_eq!(diff.removed_files, BTreeSet::from([at("legacy.rs")]));
+        assert_eq!(
+            diff.added_edges,
+            BTreeSet::from([
+                (at("main.rs"), at("config.rs")),
+                (at("config.rs"), at("util.rs")),
+            ])
+        );
+        assert_eq!(
+            diff.removed_edges,
+            BTreeSet::from([
+                (at("main.rs"), at("legacy.rs")),
+                (at("legacy.rs"), at("util.rs")),
+            ])
+        );
+        assert!(graph_diff(&new_nodes, new_root, &new_nodes, new_root).is_empty());
+        assert_eq!(diff.file_state(&at("main.rs")), DiffState::Unchanged);
+        assert_eq!(
+            diff.edge_state(&at("legacy.rs"), &at("util.rs")),
+            DiffState::Removed
+        );
+
+        let overlay = overlay_nodes(&old_nodes, old_root, &new_nodes, new_root, &diff);
+        assert_eq!(overlay.len(), 4);
+        assert_eq!(
+            overlay[0].edges(),
+            &vec![at("util.rs"), at("config.rs"), at("legacy.rs")]
+        );
+        assert_eq!(overlay[3].data().file(), &at("legacy.rs"));
+        assert_eq!(overlay[3].edges(), &vec![at("util.rs")]);
+    }
+}
@@ -1,34 +1,439 @@
+use crate::analysis::build_dependency_graph;
+use crate::analysis::{build_edge_list, find_bidirectional_pairs, out_degrees, total_degrees};
 use crate::core::defs::{GraphNode, Language};
+use crate::diff::{DiffState, GraphDiff};
+use crate::layout::directories::{DirectoryTree, TREE_PADDING};
+use crate::layout::overlap::{OverlapConfig, remove_overlaps};
+use crate::layout::{self, LayoutType};
 use font_kit::family_name::FamilyName;
 use font_kit::source::SystemSource;
 use fontdue::{Font, FontSettings};
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::Write;
-use std::path::Path;
-use svg::Document;
-use svg::node::element::{Circle, Line, Marker, Text, Title, path::Data};
-use tiny_skia::{Color, FillRule, Paint, PathBuilder, Pixmap, Rect, Shader, Stroke, Transform};
+use std::path::{Path, PathBuf};
+use svg::node::element::{Circle, Line, Marker, Rectangle, Text, Title, path::Data};
+use svg::{Document, Node};
+use tiny_skia::{
+    Color, FillRule, Paint, PathBuilder, Pixmap, PixmapPaint, Rect, Shader, Stroke, StrokeDash,
+    Transform,
+};
+
+mod csv;
+mod dot;
+mod gexf;
+mod html;
+mod json;
+mod mermaid;
+
+pub use csv::{csv_output_paths, export_graph_as_csv};
+pub use dot::export_graph_as_dot;
+pub use gexf::export_graph_as_gexf;
+pub use html::export_graph_as_html;
+pub use json::export_graph_as_json;
+pub use mermaid::export_graph_as_mermaid;
 
 const CANVAS_WIDTH: f32 = 1200.0;
 const CANVAS_HEIGHT: f32 = 900.0;
 const MIN_NODE_RADIUS: f32 = 20.0;
 const MAX_NODE_RADIUS: f32 = 40.0;
 const MARGIN: f32 = 50.0;
+/// How far an edge's control point bends away from the straight line, relative to its length
+const EDGE_CURVATURE: f32 = 0.15;
+/// Height of the title and subtitle lines added above the canvas, and the gap below them
+const TITLE_HEIGHT: f32 = 40.0;
+const SUBTITLE_HEIGHT: f32 = 24.0;
+const HEADER_PADDING: f32 = 10.0;
+
+/// Options controlling how graphs are rendered by the exporters
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Draw edges as curves instead of straight lines (SVG only)
+    pub curved_edges: bool,
+    /// Omit languages with fewer files than this from the legend
+    pub legend_min_count: usize,
+    /// Truncate node labels to this many characters
+    pub max_label_len: Option<usize>,
+    /// Draw each node's out-degree in a badge next to it
+    pub show_degree: bool,
+    /// Use HTML-like table labels with per-file metrics (DOT only)
+    pub rich_labels: bool,
+    /// Files with uncommitted changes, drawn with a dashed border
+    pub modified_files: HashSet<PathBuf>,
+    /// How SVG/PNG/HTML exports place nodes that have no explicit position
+    pub layout: LayoutType,
+    /// Draw edges touching a node with more edges than this without arrowheads
+    pub max_arrow_degree: Option<usize>,
+    /// Color the files and dependencies that changed since another revision (SVG only)
+    pub diff: Option<GraphDiff>,
+    /// Draw dependencies that only test code has in [`TEST_EDGE_COLOR`]
+    pub highlight_test_edges: bool,
+    /// Heading drawn above the graph (SVG/PNG only)
+    pub title: Option<String>,
+    /// Smaller line drawn under the title, e.g. a date or commit (SVG/PNG only)
+    pub subtitle: Option<String>,
+    /// Multiply the image size, and everything drawn on it, by this (PNG only; default 1)
+    pub scale: Option<f32>,
+    /// Push overlapping nodes apart after placing them (SVG/PNG/HTML only)
+    pub no_overlap: bool,
+    /// Draw the directory tree as nested boxes behind the graph (SVG only)
+    pub show_tree: bool,
+}
+
+impl ExportOptions {
+    /// How much taller than the canvas SVG/PNG exports are, to fit the title and subtitle
+    pub fn header_height(&self) -> f32 {
+        let title = self.title.as_ref().map_or(0.0, |_| TITLE_HEIGHT);
+        let subtitle = self.subtitle.as_ref().map_or(0.0, |_| SUBTITLE_HEIGHT);
+        if title + subtitle > 0.0 {
+            title + subtitle + HEADER_PADDING
+        } else {
+            0.0
+        }
+    }
+
+    /// The title and subtitle, each with its font size and the y coordinate of its center,
+    /// measured from the top of the header
+    fn header_lines(&self) -> Vec<(&str, f32, f32)> {
+        let mut lines = Vec::new();
+        let mut top = HEADER_PADDING;
+        if let Some(title) = &self.title {
+            lines.push((title.as_str(), 24.0, top + TITLE_HEIGHT / 2.0));
+            top += TITLE_HEIGHT;
+        }
+        if let Some(subtitle) = &self.subtitle {
+            lines.push((subtitle.as_str(), 14.0, top + SUBTITLE_HEIGHT / 2.0));
+        }
+        lines
+    }
+}
+
+/// Color of the dependencies only test code has, when they are highlighted
+pub const TEST_EDGE_COLOR: &str = "#9467BD";
+
+/// Whether the edge drawn from node `i` to node `j` only comes from test code. A mutual
+/// dependency, drawn as one edge, has to come from test code in both directions.
+pub fn is_test_edge(graph_nodes: &[GraphNode], i: usize, j: usize, bidirectional: bool) -> bool {
+    let (from, to) = (&graph_nodes[i], &graph_nodes[j]);
+    from.is_test_edge(to.data().file()) && (!bidirectional || to.is_test_edge(from.data().file()))
+}
+
+/// CSS class, stroke color and opacity of a changed file or dependency in a diff overlay.
+/// Added ones are green; removed ones are red and ghosted.
+fn diff_style(state: DiffState) -> Option<(&'static str, &'static str, f32)> {
+    match state {
+        DiffState::Unchanged => None,
+        DiffState::Added => Some(("added", "#2CA02C", 1.0)),
+        DiffState::Removed => Some(("removed", "#D62728", 0.35)),
+    }
+}
+
+/// Apply the diff overlay style, if any, to an SVG node or edge
+fn apply_diff_style<T: Node>(element: &mut T, state: DiffState) {
+    if let Some((class, color, opacity)) = diff_style(state) {
+        element.assign("class", class);
+        element.assign("stroke", color);
+        element.assign("opacity", opacity);
+    }
+}
+
+/// Whether the edge between nodes `i` and `j` gets arrowheads, given each node's total
+/// degree. Edges into or out of a hub above `max_degree` are drawn as plain lines.
+pub fn shows_arrowheads(degrees: &[usize], i: usize, j: usize, max_degree: Option<usize>) -> bool {
+    max_degree.is_none_or(|max| degrees[i] <= max && degrees[j] <= max)
+}
+
+/// What is known about a file that affects how its node's border is drawn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NodeState {
+    /// The file has uncommitted changes in git
+    pub modified: bool,
+    /// The file looks like generated code
+    pub generated: bool,
+    /// The file looks like a program or package entry point
+    pub entry_point: bool,
+}
+
+impl NodeState {
+    /// State of `node`, given the set of files git reports as modified
+    pub fn of(node: &GraphNode, modified_files: &HashSet<PathBuf>) -> Self {
+        Self {
+            modified: modified_files.contains(node.data().file()),
+            generated: node.data().is_generated(),
+            entry_point: node.data().is_entry_point(),
+        }
+    }
+}
+
+/// Line pattern of a node's border
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BorderPattern {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+/// How a node's border is drawn, shared by the GUI and the SVG/PNG exporters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NodeStyle {
+    pub pattern: BorderPattern,
+    /// Draw the border at double width
+    pub thick: bool,
+}
+
+impl NodeStyle {
+    /// Modified files are dashed and generated ones dotted; a modified generated file
+    /// is dashed, since the edit is the more useful cue. Entry points are thick
+    /// regardless of pattern.
+    pub fn for_state(state: NodeState) -> Self {
+        let pattern = if state.modified {
+            BorderPattern::Dashed
+        } else if state.generated {
+            BorderPattern::Dotted
+        } else {
+            BorderPattern::Solid
+        };
+        Self {
+            pattern,
+            thick: state.entry_point,
+        }
+    }
+
+    /// Border width, given the width of a plain border
+    pub fn stroke_width(&self, base: f32) -> f32 {
+        if self.thick { base * 2.0 } else { base }
+    }
+
+    /// Dash and gap lengths for the pattern, given the border width
+    pub fn dash_array(&self, width: f32) -> Option<[f32; 2]> {
+        match self.pattern {
+            BorderPattern::Solid => None,
+            BorderPattern::Dashed => Some([width * 3.0, width * 2.0]),
+            BorderPattern::Dotted => Some([width, width * 1.5]),
+        }
+    }
+}
+
+/// Font used for PNG labels when no usable system font is installed (Hack, MIT/Bitstream Vera licensed)
+const FALLBACK_FONT: &[u8] = include_bytes!("../assets/fonts/Hack-Regular.ttf");
+
+/// Radius of the out-degree badge drawn with `show_degree`
+const DEGREE_BADGE_RADIUS: f32 = 8.0;
+
+/// Place a node's degree badge on its upper-right edge
+fn degree_badge_position(x: f32, y: f32, node_radius: f32) -> (f32, f32) {
+    let offset = node_radius * std::f32::consts::FRAC_1_SQRT_2;
+    (x + offset, y - offset)
+}
+
+/// Node positions keyed by file, in canvas coordinates
+pub type NodePositions<'a> = HashMap<&'a PathBuf, (f32, f32)>;
+
+/// Place every node with `layout_type`, in canvas coordinates. The circular layout
+/// is drawn straight onto the canvas; the others go through the `layout` module, like
+/// in the GUI, and are then scaled and centered to fit.
+pub fn auto_positions(graph_nodes: &[GraphNode], layout_type: LayoutType) -> NodePositions<'_> {
+    if layout_type == LayoutType::Circular {
+        let radius = (CANVAS_HEIGHT - 2.0 * MARGIN).min(CANVAS_WIDTH - 2.0 * MARGIN) * 0.4;
+        let center_x = CANVAS_WIDTH / 2.0;
+        let center_y = CANVAS_HEIGHT / 2.0;
+        let n = graph_nodes.len();
+        // Angles follow file paths rather than the node order, which comes from hash map
+        // iteration, so the same project isn't drawn rotated differently on every run
+        let mut files: Vec<&PathBuf> = graph_nodes.iter().map(|node| node.data().file()).collect();
+        files.sort();
+        return files
+            .into_iter()
+            .enumerate()
+            .map(|(i, file)| {
+                let angle = (i as f32) * (2.0 * std::f32::consts::PI / n as f32);
+                (
+                    file,
+                    (
+                        center_x + radius * angle.cos(),
+                        center_y + radius * angle.sin(),
+                    ),
+                )
+            })
+            .collect();
+    }
+
+    // Node `i` of the dependency graph is `graph_nodes[i]`
+    let graph = build_dependency_graph(graph_nodes);
+    let raw = layout::create_layout_for(layout_type, graph_nodes).layout(&graph);
+    let positions: Vec<_> = graph
+        .node_indices()
+        .filter_map(|idx| {
+            raw.get(&idx)
+                .map(|&position| (graph_nodes[idx.index()].data().file(), position))
+        })
+        .collect();
+    fit_to_canvas(&positions)
+}
+
+/// Position every node, using `explicit` where it has an entry and the `options.layout`
+/// auto-layout otherwise, then push overlapping nodes apart if `options.no_overlap`.
+/// Also returns each file's index in `graph_nodes`.
+fn layout_positions<'a>(
+    graph_nodes: &'a [GraphNode],
+    explicit: Option<&NodePositions>,
+    options: &ExportOptions,
+) -> (NodePositions<'a>, HashMap<&'a PathBuf, usize>) {
+    let mut positions = HashMap::new();
+    let mut indices = HashMap::new();
+    // Only lay the graph out when some node actually needs it
+    let needs_layout = graph_nodes
+        .iter()
+        .any(|node| explicit.is_none_or(|explicit| !explicit.contains_key(node.data().file())));
+    let auto = if needs_layout {
+        auto_positions(graph_nodes, options.layout)
+    } else {
+        HashMap::new()
+    };
+
+    for (i, node) in graph_nodes.iter().enumerate() {
+        let file = node.data().file();
+        let position = explicit
+            .and_then(|explicit| explicit.get(file).copied())
+            .unwrap_or_else(|| auto[file]);
+        positions.insert(file, position);
+        indices.insert(file, i);
+    }
+
+    if options.no_overlap {
+        let mut separated: Vec<_> = graph_nodes
+            .iter()
+            .map(|node| positions[node.data().file()])
+            .collect();
+        remove_overlaps(
+            &mut separated,
+            &node_radii(graph_nodes),
+            &OverlapConfig::default(),
+        );
+        for (node, position) in graph_nodes.iter().zip(separated) {
+            positions.insert(node.data().file(), position);
+        }
+    }
+    (positions, indices)
+}
+
+/// Radius each node is drawn with in SVG/PNG exports, scaled by its LOC
+fn node_radii(graph_nodes: &[GraphNode]) -> Vec<f32> {
+    let locs = graph_nodes.iter().map(|node| node.data().loc());
+    let min_loc = locs.clone().min().unwrap_or(0);
+    let max_loc = locs.max().unwrap_or(0);
+    graph_nodes
+        .iter()
+        .map(|node| node.calculate_size(min_loc, max_loc, MIN_NODE_RADIUS, MAX_NODE_RADIUS, None))
+        .collect()
+}
+
+/// Scale and translate arbitrary (e.g. GUI world) coordinates so they fill the
+/// export canvas inside its margins, preserving the aspect ratio
+pub fn fit_to_canvas<'a>(positions: &[(&'a PathBuf, (f32, f32))]) -> NodePositions<'a> {
+    let (mut min_x, mut min_y) = (f32::INFINITY, f32::INFINITY);
+    let (mut max_x, mut max_y) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for (_, (x, y)) in positions {
+        min_x = min_x.min(*x);
+        min_y = min_y.min(*y);
+        max_x = max_x.max(*x);
+        max_y = max_y.max(*y);
+    }
+
+    // Leave room for the largest node on top of the usual margin
+    let inset = MARGIN + MAX_NODE_RADIUS;
+    let available_width = CANVAS_WIDTH - 2.0 * inset;
+    let available_height = CANVAS_HEIGHT - 2.0 * inset;
+    let (width, height) = (max_x - min_x, max_y - min_y);
+    let scale = match (width > 0.0, height > 0.0) {
+        (true, true) => (available_width / width).min(available_height / height),
+        (true, false) => available_width / width,
+        (false, true) => available_height / height,
+        (false, false) => 1.0,
+    };
+    let offset_x = (CANVAS_WIDTH - width * scale) / 2.0;
+    let offset_y = (CANVAS_HEIGHT - height * scale) / 2.0;
+
+    positions
+        .iter()
+        .map(|(file, (x, y))| {
+            (
+                *file,
+                (
+                    offset_x + (x - min_x) * scale,
+                    offset_y + (y - min_y) * scale,
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Calculate the control point of a quadratic Bézier edge between two points.
+/// The curve always bends to the left of the direction of travel, so the two
+/// edges of a bidirectional pair are mirrored instead of drawn on top of each other.
+fn curve_control_point(start: (f32, f32), end: (f32, f32)) -> (f32, f32) {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let mid_x = (start.0 + end.0) / 2.0;
+    let mid_y = (start.1 + end.1) / 2.0;
+    (mid_x + dy * EDGE_CURVATURE, mid_y - dx * EDGE_CURVATURE)
+}
+
+/// Languages to show in the legend: those detected in the project that appear in
+/// at least `min_count` graph nodes, sorted by name so output is stable across runs
+fn legend_languages(
+    graph_nodes: &[GraphNode],
+    detected_languages: &HashSet<Language>,
+    min_count: usize,
+) -> Vec<Language> {
+    let mut counts: HashMap<Language, usize> = HashMap::new();
+    for node in graph_nodes {
+        *counts.entry(*node.data().language()).or_insert(0) += 1;
+    }
+    let mut languages: Vec<Language> = detected_languages
+        .iter()
+        .filter(|lang| {
+            counts
+                .get(lang)
+                .is_some_and(|&count| count >= min_count.max(1))
+        })
+        .copied()
+        .collect();
+    languages.sort_by_key(|lang| lang.to_string());
+    languages
+}
+
+/// Turn a file stem into an identifier that DOT and Mermaid accept unquoted. The node
+/// index is appended so files with the same stem in different directories stay distinct.
+fn node_id(stem: &str, index: usize) -> String {
+    let sanitized: String = stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("n{index}_{sanitized}")
+}
 
+/// Escape text for use in HTML (or GraphViz HTML-like label) content and attributes
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Export the graph as an SVG image. Nodes without an entry in `positions` (or all
+/// of them, when it is `None`) are placed on a circle.
 pub fn export_graph_as_svg(
     graph_nodes: &[GraphNode],
     output_path: &Path,
     detected_languages: HashSet<Language>,
+    options: &ExportOptions,
+    positions: Option<&NodePositions>,
 ) -> Result<(), String> {
-    if graph_nodes.is_empty() {
-        return Ok(());
-    }
-
-    // Calculate layout (similar to GUI layout)
-    let radius = (CANVAS_HEIGHT - 2.0 * MARGIN).min(CANVAS_WIDTH - 2.0 * MARGIN) * 0.4;
-    let center_x = CANVAS_WIDTH / 2.0;
-    let center_y = CANVAS_HEIGHT / 2.0;
+    // Draw in path order, so the same project always writes the same file
+    let mut sorted = graph_nodes.to_vec();
+    sorted.sort_by(|a, b| a.data().file().cmp(b.data().file()));
+    let graph_nodes = sorted.as_slice();
     let n = graph_nodes.len();
 
     // Calculate min/max LOC for node size normalization
@@ -44,29 +449,49 @@ pub fn export_graph_as_svg(
         .unwrap_or(0);
 
     // Calculate node positions
-    let mut positions = HashMap::new();
-    for (i, node) in graph_nodes.iter().enumerate() {
-        let angle = (i as f32) * (2.0 * std::f32::consts::PI / n as f32);
-        let x = center_x + radius * angle.cos();
-        let y = center_y + radius * angle.sin();
-        positions.insert(node.data().file(), (x, y));
-    }
+    let (positions, indices) = layout_positions(graph_nodes, positions, options);
+    let edge_list = build_edge_list(graph_nodes);
+    let bidirectional_pairs = find_bidirectional_pairs(&edge_list);
+    let degrees = out_degrees(n, &edge_list);
+    let edge_degrees = total_degrees(n, &edge_list);
 
-    // Create SVG document
+    // Create SVG document; the header sits above the canvas, at negative y, so the graph
+    // keeps its coordinates
+    let header_height = options.header_height();
     let mut document = Document::new()
         .set("width", CANVAS_WIDTH)
-        .set("height", CANVAS_HEIGHT)
-        .set("viewBox", (0, 0, CANVAS_WIDTH as i32, CANVAS_HEIGHT as i32))
+        .set("height", CANVAS_HEIGHT + header_height)
+        .set(
+            "viewBox",
+            (
+                0.0,
+                -header_height,
+                CANVAS_WIDTH,
+                CANVAS_HEIGHT + header_height,
+            ),
+        )
         .set("style", "background-color: white");
+    for (i, (line, size, y)) in options.header_lines().into_iter().enumerate() {
+        let text = Text::new(line)
+            .set("x", CANVAS_WIDTH / 2.0)
+            .set("y", y - header_height)
+            .set("text-anchor", "middle")
+            .set("dominant-baseline", "middle")
+            .set("font-family", "Arial")
+            .set("font-size", size)
+            .set("font-weight", if i == 0 { "bold" } else { "normal" })
+            .set("class", if i == 0 { "title" } else { "subtitle" });
+        document = document.add(text);
+    }
 
-    // Add arrow marker definition
+    // Add arrow marker definition, reversed when used as a start marker
     let marker = Marker::new()
         .set("id", "arrowhead")
         .set("markerWidth", 10)
         .set("markerHeight", 7)
         .set("refX", 10)
         .set("refY", 3.5)
-        .set("orient", "auto");
+        .set("orient", "auto-start-reverse");
 
     let path = Data::new()
         .move_to((0, 0))
@@ -78,41 +503,126 @@ pub fn export_graph_as_svg(
         .set("d", path)
         .set("fill", "lightblue");
 
-    document = document.add(marker.add(arrow));
+    document = document.add(marker.clone().add(arrow.clone()));
+    if options.highlight_test_edges {
+        let test_marker = marker
+            .set("id", "test-arrowhead")
+            .add(arrow.set("fill", TEST_EDGE_COLOR));
+        document = document.add(test_marker);
+    }
+
+    // The directory tree goes underneath everything else
+    if options.show_tree {
+        let files: Vec<&Path> = graph_nodes
+            .iter()
+            .map(|n| n.data().file().as_path())
+            .collect();
+        let centers: Vec<(f32, f32)> = graph_nodes
+            .iter()
+            .map(|n| positions[n.data().file()])
+            .collect();
+        let tree = DirectoryTree::from_files(&files);
+        for directory in tree.boxes(&centers, &node_radii(graph_nodes), TREE_PADDING) {
+            let rect = Rectangle::new()
+                .set("x", directory.min.0)
+                .set("y", directory.min.1)
+                .set("width", directory.max.0 - directory.min.0)
+                .set("height", directory.max.1 - directory.min.1)
+                .set("rx", 6)
+                .set("fill", "gray")
+                .set("fill-opacity", 0.06)
+                .set("stroke", "gray")
+                .set("stroke-opacity", 0.3)
+                .set("class", "directory")
+                .add(Title::new(directory.path.display().to_string()));
+            document = document.add(rect);
+        }
+    }
 
     // Add edges first (so they appear under nodes)
-    for node in graph_nodes {
+    for (i, node) in graph_nodes.iter().enumerate() {
         let (start_x, start_y) = positions.get(node.data().file()).unwrap();
 
         for edge in node.edges() {
-            if let Some((end_x, end_y)) = positions.get(edge) {
+            if let (Some((end_x, end_y)), Some(&j)) = (positions.get(edge), indices.get(edge)) {
+                // Mutual dependencies are drawn once, with an arrowhead on both ends
+                let is_bidirectional = bidirectional_pairs.contains(&(i.min(j), i.max(j)));
+                if is_bidirectional && i > j {
+                    continue;
+                }
+                let arrowheads = shows_arrowheads(&edge_degrees, i, j, options.max_arrow_degree);
+                let test_edge = options.highlight_test_edges
+                    && is_test_edge(graph_nodes, i, j, is_bidirectional);
+                let (color, arrowhead) = if test_edge {
+                    (TEST_EDGE_COLOR, "url(#test-arrowhead)")
+                } else {
+                    ("lightblue", "url(#arrowhead)")
+                };
+                let marker_end = if arrowheads { arrowhead } else { "none" };
+                let marker_start = if arrowheads && is_bidirectional {
+                    marker_end
+                } else {
+                    "none"
+                };
+                let diff_state = options.diff.as_ref().map_or(DiffState::Unchanged, |diff| {
+                    diff.edge_state(node.data().file(), edge)
+                });
+
                 // Add the edge with the arrow marker
-                let edge = Line::new()
-                    .set("x1", *start_x)
-                    .set("y1", *start_y)
-                    .set("x2", *end_x)
-                    .set("y2", *end_y)
-                    .set("stroke", "lightblue")
-                    .set("stroke-width", 2)
-                    .set("marker-end", "url(#arrowhead)");
-                document = document.add(edge);
+                if options.curved_edges {
+                    let (control_x, control_y) =
+                        curve_control_point((*start_x, *start_y), (*end_x, *end_y));
+                    let data = Data::new()
+                        .move_to((*start_x, *start_y))
+                        .quadratic_curve_to((control_x, control_y, *end_x, *end_y));
+                    let mut edge = svg::node::element::Path::new()
+                        .set("d", data)
+                        .set("fill", "none")
+                        .set("stroke", color)
+                        .set("stroke-width", 2)
+                        .set("marker-start", marker_start)
+                        .set("marker-end", marker_end);
+                    apply_diff_style(&mut edge, diff_state);
+                    document = document.add(edge);
+                } else {
+                    let mut edge = Line::new()
+                        .set("x1", *start_x)
+                        .set("y1", *start_y)
+                        .set("x2", *end_x)
+                        .set("y2", *end_y)
+                        .set("stroke", color)
+                        .set("stroke-width", 2)
+                        .set("marker-start", marker_start)
+                        .set("marker-end", marker_end);
+                    apply_diff_style(&mut edge, diff_state);
+                    document = document.add(edge);
+                }
             }
         }
     }
 
     // Add nodes with labels
-    for node in graph_nodes {
+    for (i, node) in graph_nodes.iter().enumerate() {
         let (x, y) = positions.get(node.data().file()).unwrap();
         let radius = node.calculate_size(min_loc, max_loc, MIN_NODE_RADIUS, MAX_NODE_RADIUS, None);
 
-        // Node circle
+        // Node circle, its border styled by the file's state
+        let style = NodeStyle::for_state(NodeState::of(node, &options.modified_files));
+        let stroke_width = style.stroke_width(2.0);
         let circle = Circle::new()
             .set("cx", *x)
             .set("cy", *y)
             .set("r", radius)
             .set("fill", node.data().language().color())
             .set("stroke", "black")
-            .set("stroke-width", 2);
+            .set("stroke-width", stroke_width);
+        let mut circle = match style.dash_array(stroke_width) {
+            Some([dash, gap]) => circle.set("stroke-dasharray", format!("{dash} {gap}")),
+            None => circle,
+        };
+        if let Some(diff) = &options.diff {
+            apply_diff_style(&mut circle, diff.file_state(node.data().file()));
+        }
 
         // Add title for hover tooltip
         let title = Title::new(node.data().file().file_name().unwrap().to_str().unwrap());
@@ -120,10 +630,8 @@ pub fn export_graph_as_svg(
         document = document.add(circle_with_title);
 
         // Node label
-        if let Some(name) = node.data().file().file_stem()
-            && let Some(name_str) = name.to_str()
-        {
-            let label = Text::new(name_str)
+        if let Some(name) = node.label(options.max_label_len) {
+            let label = Text::new(name)
                 .set("x", *x)
                 .set("y", *y)
                 .set("text-anchor", "middle")
@@ -133,6 +641,28 @@ pub fn export_graph_as_svg(
                 .set("fill", "black");
             document = document.add(label);
         }
+
+        // Out-degree badge on the node's upper right
+        if options.show_degree {
+            let (badge_x, badge_y) = degree_badge_position(*x, *y, radius);
+            let badge = Circle::new()
+                .set("cx", badge_x)
+                .set("cy", badge_y)
+                .set("r", DEGREE_BADGE_RADIUS)
+                .set("fill", "white")
+                .set("stroke", "black")
+                .set("stroke-width", 1);
+            let count = Text::new(degrees[i].to_string())
+                .set("class", "degree-badge")
+                .set("x", badge_x)
+                .set("y", badge_y)
+                .set("text-anchor", "middle")
+                .set("dominant-baseline", "middle")
+                .set("font-family", "Arial")
+                .set("font-size", 10)
+                .set("fill", "black");
+            document = document.add(badge).add(count);
+        }
     }
 
     // Add legend
@@ -140,7 +670,10 @@ pub fn export_graph_as_svg(
     let legend_x = MARGIN;
     let legend_spacing = 25.0;
 
-    for (i, lang) in detected_languages.iter().enumerate() {
+    for (i, lang) in legend_languages(graph_nodes, &detected_languages, options.legend_min_count)
+        .iter()
+        .enumerate()
+    {
         let y = legend_y + (i as f32 * legend_spacing);
 
         // Legend dot
@@ -171,21 +704,81 @@ pub fn export_graph_as_svg(
     Ok(())
 }
 
+/// Draw a filled arrowhead pointing at `end`, pulled back from the node center
+fn fill_arrowhead(
+    pixmap: &mut Pixmap,
+    paint: &Paint,
+    start: (f32, f32),
+    end: (f32, f32),
+    transform: Transform,
+) {
+    let (sx, sy) = start;
+    let (ex, ey) = end;
+
+    // Calculate arrow direction and points
+    let dx = ex - sx;
+    let dy = ey - sy;
+    let length = (dx * dx + dy * dy).sqrt();
+    if length <= 0.0 {
+        return;
+    }
+
+    let arrow_size = 10.0;
+    let arrow_angle = 0.5f32; // ~30 degrees in radians
+
+    // Normalize direction vector
+    let dir_x = dx / length;
+    let dir_y = dy / length;
+
+    // Calculate arrow tip position (pulled back from end point)
+    let tip_x = ex - dir_x * 20.0;
+    let tip_y = ey - dir_y * 20.0;
+
+    // Calculate arrow wing points
+    let left_x = tip_x + arrow_size * (-dir_x * arrow_angle.cos() + dir_y * arrow_angle.sin());
+    let left_y = tip_y + arrow_size * (-dir_x * arrow_angle.sin() - dir_y * arrow_angle.cos());
+    let right_x = tip_x + arrow_size * (-dir_x * arrow_angle.cos() - dir_y * arrow_angle.sin());
+    let right_y = tip_y + arrow_size * (dir_x * arrow_angle.sin() - dir_y * arrow_angle.cos());
+
+    // Draw arrowhead
+    let mut arrow_pb = PathBuilder::new();
+    arrow_pb.move_to(ex, ey);
+    arrow_pb.line_to(left_x, left_y);
+    arrow_pb.line_to(right_x, right_y);
+    arrow_pb.close();
+
+    if let Some(arrow_path) = arrow_pb.finish() {
+        pixmap.fill_path(&arrow_path, paint, FillRule::Winding, transform, None);
+    }
+}
+
+/// Export the graph as a PNG image, laid out like [`export_graph_as_svg`]
 pub fn export_graph_as_png(
     graph_nodes: &[GraphNode],
     output_path: &Path,
     detected_languages: HashSet<Language>,
+    options: &ExportOptions,
+    positions: Option<&NodePositions>,
 ) -> Result<(), String> {
-    if graph_nodes.is_empty() {
-        return Ok(());
-    }
-
-    let font = load_font()?;
+    render_png(
+        graph_nodes,
+        output_path,
+        detected_languages,
+        options,
+        positions,
+        &load_font(),
+    )
+}
 
-    // Layout math (unchanged from SVG version)
-    let radius = (CANVAS_HEIGHT - 2.0 * MARGIN).min(CANVAS_WIDTH - 2.0 * MARGIN) * 0.4;
-    let center_x = CANVAS_WIDTH / 2.0;
-    let center_y = CANVAS_HEIGHT / 2.0;
+/// Draw the graph to a PNG file using the given font for labels and legend
+fn render_png(
+    graph_nodes: &[GraphNode],
+    output_path: &Path,
+    detected_languages: HashSet<Language>,
+    options: &ExportOptions,
+    positions: Option<&NodePositions>,
+    font: &Font,
+) -> Result<(), String> {
     let n = graph_nodes.len();
 
     let min_loc = graph_nodes
@@ -199,23 +792,30 @@ pub fn export_graph_as_png(
         .max()
         .unwrap_or(0);
 
-    let mut positions = std::collections::HashMap::new();
-    for (i, node) in graph_nodes.iter().enumerate() {
-        let angle = (i as f32) * (2.0 * std::f32::consts::PI / n as f32);
-        let x = center_x + radius * angle.cos();
-        let y = center_y + radius * angle.sin();
-        positions.insert(node.data().file(), (x, y));
-    }
+    let (positions, indices) = layout_positions(graph_nodes, positions, options);
+    let edge_list = build_edge_list(graph_nodes);
+    let bidirectional_pairs = find_bidirectional_pairs(&edge_list);
+    let degrees = out_degrees(n, &edge_list);
+    let edge_degrees = total_degrees(n, &edge_list);
+
+    // Everything is drawn in canvas coordinates and scaled as it is rasterized
+    let scale = options.scale.unwrap_or(1.0);
+    let transform = Transform::from_scale(scale, scale);
 
     // Create pixmap (white background)
-    let mut pixmap =
-        Pixmap::new(CANVAS_WIDTH as u32, CANVAS_HEIGHT as u32).ok_or("Failed to create pixmap")?;
+    let mut pixmap = Pixmap::new(
+        (CANVAS_WIDTH * scale).round() as u32,
+        (CANVAS_HEIGHT * scale).round() as u32,
+    )
+    .ok_or("Failed to create pixmap")?;
     pixmap.fill(Color::WHITE);
 
     // Stroke paint for edges
     let mut edge_paint = Paint::default();
     edge_paint.set_color(Color::from_rgba8(173, 216, 230, 255));
     edge_paint.anti_alias = true;
+    let mut test_edge_paint = edge_paint.clone();
+    test_edge_paint.set_color(Color::from_rgba8(148, 103, 189, 255));
 
     let stroke = Stroke {
         width: 2.0,
@@ -223,58 +823,35 @@ pub fn export_graph_as_png(
     };
 
     // Draw edges
-    for node in graph_nodes {
+    for (i, node) in graph_nodes.iter().enumerate() {
         let (sx, sy) = positions[node.data().file()];
         for edge in node.edges() {
-            if let Some(&(ex, ey)) = positions.get(edge) {
+            if let (Some(&(ex, ey)), Some(&j)) = (positions.get(edge), indices.get(edge)) {
+                // Mutual dependencies are drawn once, with an arrowhead on both ends
+                let is_bidirectional = bidirectional_pairs.contains(&(i.min(j), i.max(j)));
+                if is_bidirectional && i > j {
+                    continue;
+                }
+
+                let paint = if options.highlight_test_edges
+                    && is_test_edge(graph_nodes, i, j, is_bidirectional)
+                {
+                    &test_edge_paint
+                } else {
+                    &edge_paint
+                };
+
                 // Draw the main line
                 let mut pb = PathBuilder::new();
                 pb.move_to(sx, sy);
                 pb.line_to(ex, ey);
                 let path = pb.finish().unwrap();
-                pixmap.stroke_path(&path, &edge_paint, &stroke, Transform::identity(), None);
-
-                // Calculate arrow direction and points
-                let dx = ex - sx;
-                let dy = ey - sy;
-                let length = (dx * dx + dy * dy).sqrt();
-                if length > 0.0 {
-                    let arrow_size = 10.0;
-                    let arrow_angle = 0.5f32; // ~30 degrees in radians
-
-                    // Normalize direction vector
-                    let dir_x = dx / length;
-                    let dir_y = dy / length;
-
-                    // Calculate arrow tip position (pulled back from end point)
-                    let tip_x = ex - dir_x * 20.0;
-                    let tip_y = ey - dir_y * 20.0;
-
-                    // Calculate arrow wing points
-                    let left_x = tip_x
-                        + arrow_size * (-dir_x * arrow_angle.cos() + dir_y * arrow_angle.sin());
-                    let left_y = tip_y
-                        + arrow_size * (-dir_x * arrow_angle.sin() - dir_y * arrow_angle.cos());
-                    let right_x = tip_x
-                        + arrow_size * (-dir_x * arrow_angle.cos() - dir_y * arrow_angle.sin());
-                    let right_y = tip_y
-                        + arrow_size * (dir_x * arrow_angle.sin() - dir_y * arrow_angle.cos());
-
-                    // Draw arrowhead
-                    let mut arrow_pb = PathBuilder::new();
-                    arrow_pb.move_to(ex, ey);
-                    arrow_pb.line_to(left_x, left_y);
-                    arrow_pb.line_to(right_x, right_y);
-                    arrow_pb.close();
-
-                    if let Some(arrow_path) = arrow_pb.finish() {
-                        pixmap.fill_path(
-                            &arrow_path,
-                            &edge_paint,
-                            FillRule::Winding,
-                            Transform::identity(),
-                            None,
-                        );
+                pixmap.stroke_path(&path, paint, &stroke, transform, None);
+
+                if shows_arrowheads(&edge_degrees, i, j, options.max_arrow_degree) {
+                    fill_arrowhead(&mut pixmap, paint, (sx, sy), (ex, ey), transform);
+                    if is_bidirectional {
+                        fill_arrowhead(&mut pixmap, paint, (ex, ey), (sx, sy), transform);
                     }
                 }
             }
@@ -282,7 +859,7 @@ pub fn export_graph_as_png(
     }
 
     // Draw nodes
-    for node in graph_nodes {
+    for (i, node) in graph_nodes.iter().enumerate() {
         let (x, y) = positions[node.data().file()];
         let node_radius =
             node.calculate_size(min_loc, max_loc, MIN_NODE_RADIUS, MAX_NODE_RADIUS, None);
@@ -297,27 +874,69 @@ pub fn export_graph_as_png(
             &circle_path,
             &fill_paint,
             FillRule::Winding,
-            Transform::identity(),
+            transform,
             None,
         );
 
-        // Circle stroke
+        // Circle stroke, styled by the file's state
         let mut stroke_paint = Paint::default();
         stroke_paint.set_color(Color::BLACK);
+        let style = NodeStyle::for_state(NodeState::of(node, &options.modified_files));
+        let width = style.stroke_width(2.0);
+        let dash = style
+            .dash_array(width)
+            .and_then(|pattern| StrokeDash::new(pattern.to_vec(), 0.0));
         pixmap.stroke_path(
             &circle_path,
             &stroke_paint,
             &Stroke {
-                width: 2.0,
+                width,
+                dash,
                 ..Default::default()
             },
-            Transform::identity(),
+            transform,
             None,
         );
 
         // Node label
-        if let Some(name) = node.data().file().file_stem().and_then(|s| s.to_str()) {
-            draw_text(&font, &mut pixmap, name, x, y, 12.0, false);
+        if let Some(name) = node.label(options.max_label_len) {
+            draw_text(font, &mut pixmap, &name, (x, y), 12.0, false, scale);
+        }
+
+        // Out-degree badge on the node's upper right
+        if options.show_degree {
+            let (badge_x, badge_y) = degree_badge_position(x, y, node_radius);
+            let badge_path =
+                PathBuilder::from_circle(badge_x, badge_y, DEGREE_BADGE_RADIUS).unwrap();
+            let mut badge_paint = Paint::default();
+            badge_paint.set_color(Color::WHITE);
+            badge_paint.anti_alias = true;
+            pixmap.fill_path(
+                &badge_path,
+                &badge_paint,
+                FillRule::Winding,
+                transform,
+                None,
+            );
+            pixmap.stroke_path(
+                &badge_path,
+                &stroke_paint,
+                &Stroke {
+                    width: 1.0,
+                    ..Default::default()
+                },
+                transform,
+                None,
+            );
+            draw_text(
+                font,
+                &mut pixmap,
+                &degrees[i].to_string(),
+                (badge_x, badge_y),
+                10.0,
+                false,
+                scale,
+            );
         }
     }
 
@@ -325,20 +944,17 @@ pub fn export_graph_as_png(
     let legend_y = MARGIN;
     let legend_spacing = 25.0;
 
-    for (i, lang) in detected_languages.iter().enumerate() {
+    for (i, lang) in legend_languages(graph_nodes, &detected_languages, options.legend_min_count)
+        .iter()
+        .enumerate()
+    {
         let y = legend_y + (i as f32 * legend_spacing);
 
         // Legend dot
         let dot_path = PathBuilder::from_circle(legend_x, y, 6.0).unwrap();
         let mut dot_paint = Paint::default();
         dot_paint.set_color(lang.color_rgba());
-        pixmap.fill_path(
-            &dot_path,
-            &dot_paint,
-            FillRule::Winding,
-            Transform::identity(),
-            None,
-        );
+        pixmap.fill_path(&dot_path, &dot_paint, FillRule::Winding, transform, None);
 
         // Dot border
         let mut border_paint = Paint::default();
@@ -350,36 +966,71 @@ pub fn export_graph_as_png(
                 width: 1.0,
                 ..Default::default()
             },
-            Transform::identity(),
+            transform,
             None,
         );
 
         // Legend label
         draw_text(
-            &font,
+            font,
             &mut pixmap,
             lang.to_string(),
-            legend_x + 15.0,
-            y,
+            (legend_x + 15.0, y),
             12.0,
             true,
+            scale,
         );
     }
 
+    // Put the header above the finished graph
+    let header_height = options.header_height();
+    if header_height > 0.0 {
+        let mut page = Pixmap::new(
+            pixmap.width(),
+            pixmap.height() + (header_height * scale).round() as u32,
+        )
+        .ok_or("Failed to create pixmap")?;
+        page.fill(Color::WHITE);
+        page.draw_pixmap(
+            0,
+            (header_height * scale).round() as i32,
+            pixmap.as_ref(),
+            &PixmapPaint::default(),
+            Transform::identity(),
+            None,
+        );
+        for (line, size, y) in options.header_lines() {
+            draw_text(
+                font,
+                &mut page,
+                line,
+                (CANVAS_WIDTH / 2.0, y),
+                size,
+                false,
+                scale,
+            );
+        }
+        pixmap = page;
+    }
+
     // Save PNG
     pixmap.save_png(output_path).map_err(|e| e.to_string())?;
     Ok(())
 }
 
+/// Draw `text` centered on `position`, or starting at it for legends. Coordinates and
+/// `size` are in canvas units, and glyphs are rasterized at `scale` times that size
 fn draw_text(
     font: &Font,
     pixmap: &mut Pixmap,
     text: &str,
-    x: f32,
-    y: f32,
+    position: (f32, f32),
     size: f32,
     legends: bool,
+    scale: f32,
 ) {
+    let (x, y) = (position.0 * scale, position.1 * scale);
+    let size = size * scale;
     let total_width: f32 = text
         .chars()
         .map(|ch| font.metrics(ch, size).advance_width)
@@ -397,8 +1048,8 @@ fn draw_text(
 
     // No centering needed for legends
     if legends {
-        cursor_x = x - 5.0;
-        baseline_y = y + 5.0;
+        cursor_x = x - 5.0 * scale;
+        baseline_y = y + 5.0 * scale;
     }
 
     for ch in text.chars() {
@@ -429,32 +1080,664 @@ fn draw_text(
     }
 }
 
-/// Attempt to load Arial font, falling back to system font
-fn load_font() -> Result<Font, String> {
+/// Load Arial or the best available sans-serif font from the system
+fn load_system_font() -> Result<Font, String> {
     let source = SystemSource::new();
 
-    let font_data = match source.select_by_postscript_name("Arial") {
-        Ok(handle) => handle
-            .load()
-            .map_err(|e| format!("Failed to load font: {}", e))?
-            .copy_font_data()
-            .unwrap()
-            .to_vec(),
-        Err(_) => {
-            let handle = source
-                .select_best_match(
-                    &[FamilyName::SansSerif],
-                    &font_kit::properties::Properties::new(),
-                )
-                .map_err(|e| format!("Failed to load font: {}", e))?;
-            handle
-                .load()
-                .map_err(|e| format!("Failed to load font: {}", e))?
-                .copy_font_data()
-                .unwrap()
-                .to_vec()
-        }
+    let handle = match source.select_by_postscript_name("Arial") {
+        Ok(handle) => handle,
+        Err(_) => source
+            .select_best_match(
+                &[FamilyName::SansSerif],
+                &font_kit::properties::Properties::new(),
+            )
+            .map_err(|e| format!("Failed to load font: {}", e))?,
     };
+    let font_data = handle
+        .load()
+        .map_err(|e| format!("Failed to load font: {}", e))?
+        .copy_font_data()
+        .ok_or("Failed to read font data")?
+        .to_vec();
 
     Ok(Font::from_bytes(font_data, FontSettings::default())?)
 }
+
+/// Load the embedded fallback font
+fn load_fallback_font() -> Font {
+    Font::from_bytes(FALLBACK_FONT, FontSettings::default()).expect("Embedded font is valid")
+}
+
+/// Load a system font, falling back to the embedded font when none can be used
+fn load_font() -> Font {
+    load_system_font().unwrap_or_else(|_| load_fallback_font())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_svg_curved_edges() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.svg");
+        let graph_nodes = vec![
+            GraphNode::for_test("a.rs", Language::Rust, 10, &["b.rs"]),
+            GraphNode::for_test("b.rs", Language::Rust, 10, &["c.rs"]),
+            GraphNode::for_test("c.rs", Language::Rust, 10, &[]),
+        ];
+        let options = ExportOptions {
+            curved_edges: true,
+            ..Default::default()
+        };
+
+        export_graph_as_svg(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust]),
+            &options,
+            None,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(!content.contains("<line"));
+        // One path for the arrow marker plus one per edge
+        assert_eq!(content.matches("<path").count(), 3);
+        assert!(content.contains(" Q"));
+    }
+
+    #[test]
+    fn test_circular_export_is_reproducible() {
+        let temp_dir = TempDir::new().unwrap();
+        let graph_nodes = vec![
+            GraphNode::for_test(
+                "src/main.rs",
+                Language::Rust,
+                10,
+                &["src/util.rs", "src/cli.rs"],
+            ),
+            GraphNode::for_test("src/util.rs", Language::Rust, 10, &[]),
+            GraphNode::for_test("src/cli.rs", Language::Rust, 10, &["src/util.rs"]),
+            GraphNode::for_test("app.py", Language::Python, 10, &[]),
+        ];
+        let mut shuffled = graph_nodes.clone();
+        shuffled.rotate_left(1);
+        shuffled.swap(0, 2);
+
+        // The angles don't depend on the order the nodes come in
+        assert_eq!(
+            auto_positions(&graph_nodes, LayoutType::Circular),
+            auto_positions(&shuffled, LayoutType::Circular)
+        );
+
+        let export = |graph_nodes: &[GraphNode], name: &str| {
+            let output = temp_dir.path().join(name);
+            export_graph_as_svg(
+                graph_nodes,
+                &output,
+                HashSet::from([Language::Rust, Language::Python]),
+                &ExportOptions::default(),
+                None,
+            )
+            .unwrap();
+            fs::read(output).unwrap()
+        };
+        assert_eq!(
+            export(&graph_nodes, "first.svg"),
+            export(&shuffled, "second.svg")
+        );
+    }
+
+    #[test]
+    fn test_svg_bidirectional_edge_drawn_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.svg");
+        let graph_nodes = vec![
+            GraphNode::for_test("a.rs", Language::Rust, 10, &["b.rs"]),
+            GraphNode::for_test("b.rs", Language::Rust, 10, &["a.rs"]),
+        ];
+
+        export_graph_as_svg(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust]),
+            &ExportOptions::default(),
+            None,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        assert_eq!(content.matches("<line").count(), 1);
+        assert!(content.contains(r#"marker-start="url(#arrowhead)""#));
+    }
+
+    #[test]
+    fn test_svg_hub_edges_without_arrowheads() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.svg");
+        // `hub.rs` has three incoming edges; `a.rs -> b.rs` touches no hub
+        let graph_nodes = vec![
+            GraphNode::for_test("a.rs", Language::Rust, 10, &["hub.rs", "b.rs"]),
+            GraphNode::for_test("b.rs", Language::Rust, 10, &["hub.rs"]),
+            GraphNode::for_test("c.rs", Language::Rust, 10, &["hub.rs"]),
+            GraphNode::for_test("hub.rs", Language::Rust, 10, &[]),
+        ];
+        let edge_list = build_edge_list(&graph_nodes);
+        let degrees = total_degrees(graph_nodes.len(), &edge_list);
+        assert!(!shows_arrowheads(&degrees, 2, 3, Some(2)));
+        assert!(shows_arrowheads(&degrees, 2, 3, Some(3)));
+        assert!(shows_arrowheads(&degrees, 2, 3, None));
+
+        let options = ExportOptions {
+            max_arrow_degree: Some(2),
+            ..Default::default()
+        };
+        export_graph_as_svg(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust]),
+            &options,
+            None,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        let document = roxmltree::Document::parse(&content).unwrap();
+        let markers: Vec<&str> = document
+            .descendants()
+            .filter(|n| n.has_tag_name("line"))
+            .map(|n| n.attribute("marker-end").unwrap())
+            .collect();
+        // Only the edge between the two small nodes keeps its arrowhead
+        assert_eq!(markers, vec!["none", "url(#arrowhead)", "none", "none"]);
+    }
+
+    #[test]
+    fn test_svg_highlights_test_edges() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.svg");
+        let graph_nodes = vec![
+            GraphNode::for_test("lib.rs", Language::Rust, 10, &["util.rs", "fixtures.rs"])
+                .with_test_edges(HashSet::from([PathBuf::from("fixtures.rs")])),
+            GraphNode::for_test("util.rs", Language::Rust, 10, &[]),
+            GraphNode::for_test("fixtures.rs", Language::Rust, 10, &[]),
+        ];
+        assert!(is_test_edge(&graph_nodes, 0, 2, false));
+        assert!(!is_test_edge(&graph_nodes, 0, 1, false));
+
+        let stroke_colors = |highlight_test_edges| {
+            let options = ExportOptions {
+                highlight_test_edges,
+                ..Default::default()
+            };
+            export_graph_as_svg(
+                &graph_nodes,
+                &output,
+                HashSet::from([Language::Rust]),
+                &options,
+                None,
+            )
+            .unwrap();
+            let content = fs::read_to_string(&output).unwrap();
+            let document = roxmltree::Document::parse(&content).unwrap();
+            document
+                .descendants()
+                .filter(|n| n.has_tag_name("line"))
+                .map(|n| n.attribute("stroke").unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(stroke_colors(false), vec!["lightblue", "lightblue"]);
+        assert_eq!(stroke_colors(true), vec!["lightblue", TEST_EDGE_COLOR]);
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(content.contains("marker-end=\"url(#test-arrowhead)\""));
+    }
+
+    #[test]
+    fn test_svg_diff_overlay() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.svg");
+        let graph_nodes = vec![
+            GraphNode::for_test("main.rs", Language::Rust, 10, &["util.rs", "old.rs"]),
+            GraphNode::for_test("util.rs", Language::Rust, 10, &[]),
+            GraphNode::for_test("old.rs", Language::Rust, 10, &[]),
+        ];
+        let options = ExportOptions {
+            diff: Some(GraphDiff {
+                removed_files: [PathBuf::from("old.rs")].into(),
+                added_edges: [(PathBuf::from("main.rs"), PathBuf::from("util.rs"))].into(),
+                removed_edges: [(PathBuf::from("main.rs"), PathBuf::from("old.rs"))].into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        export_graph_as_svg(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust]),
+            &options,
+            None,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        let document = roxmltree::Document::parse(&content).unwrap();
+        let edges: Vec<(Option<&str>, &str)> = document
+            .descendants()
+            .filter(|n| n.has_tag_name("line"))
+            .map(|n| (n.attribute("class"), n.attribute("stroke").unwrap()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![(Some("added"), "#2CA02C"), (Some("removed"), "#D62728")]
+        );
+
+        let removed: Vec<_> = document
+            .descendants()
+            .filter(|n| n.has_tag_name("circle") && n.attribute("class") == Some("removed"))
+            .collect();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].attribute("opacity"), Some("0.35"));
+    }
+
+    #[test]
+    fn test_svg_sugiyama_layout() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.svg");
+        let graph_nodes = vec![
+            GraphNode::for_test("main.rs", Language::Rust, 10, &["app.rs", "cli.rs"]),
+            GraphNode::for_test("app.rs", Language::Rust, 10, &["core.rs"]),
+            GraphNode::for_test("cli.rs", Language::Rust, 10, &["core.rs"]),
+            GraphNode::for_test("core.rs", Language::Rust, 10, &["util.rs"]),
+            GraphNode::for_test("util.rs", Language::Rust, 10, &[]),
+        ];
+        let options = ExportOptions {
+            layout: LayoutType::Sugiyama,
+            ..Default::default()
+        };
+
+        export_graph_as_svg(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust]),
+            &options,
+            None,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        let document = roxmltree::Document::parse(&content).unwrap();
+        let centers: Vec<(f32, f32)> = document
+            .descendants()
+            .filter(|n| n.has_tag_name("circle"))
+            .take(graph_nodes.len())
+            .map(|n| {
+                let coordinate = |name| n.attribute(name).unwrap().parse::<f32>().unwrap();
+                (coordinate("cx"), coordinate("cy"))
+            })
+            .collect();
+
+        // Layers, not a circle: distances from the canvas center differ
+        let distances: Vec<f32> = centers
+            .iter()
+            .map(|(x, y)| {
+                ((x - CANVAS_WIDTH / 2.0).powi(2) + (y - CANVAS_HEIGHT / 2.0).powi(2)).sqrt()
+            })
+            .collect();
+        let (min, max) = distances
+            .iter()
+            .fold((f32::INFINITY, 0.0_f32), |(lo, hi), &d| {
+                (lo.min(d), hi.max(d))
+            });
+        assert!(max - min > 1.0, "nodes are equidistant from the center");
+
+        // Everything stays on the canvas
+        for (x, y) in centers {
+            assert!((MARGIN..=CANVAS_WIDTH - MARGIN).contains(&x));
+            assert!((MARGIN..=CANVAS_HEIGHT - MARGIN).contains(&y));
+        }
+    }
+
+    #[test]
+    fn test_svg_legend_only_lists_present_languages() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.svg");
+        let graph_nodes = vec![
+            GraphNode::for_test("main.rs", Language::Rust, 10, &["lib.rs"]),
+            GraphNode::for_test("lib.rs", Language::Rust, 10, &[]),
+        ];
+
+        export_graph_as_svg(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust]),
+            &ExportOptions::default(),
+            None,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(content.contains("Rust"));
+        assert!(!content.contains("Python"));
+    }
+
+    #[test]
+    fn test_legend_languages_sorted_and_filtered() {
+        let graph_nodes = vec![
+            GraphNode::for_test("a.ts", Language::TypeScript, 10, &[]),
+            GraphNode::for_test("b.cpp", Language::Cpp, 10, &[]),
+            GraphNode::for_test("c.rs", Language::Rust, 10, &[]),
+        ];
+        let detected = HashSet::from([
+            Language::Rust,
+            Language::TypeScript,
+            Language::Cpp,
+            Language::Python,
+        ]);
+
+        let languages = legend_languages(&graph_nodes, &detected, 0);
+        let names: Vec<&str> = languages.iter().map(|lang| lang.to_string()).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+        assert_eq!(languages.len(), 3);
+        assert!(!languages.contains(&Language::Python));
+    }
+
+    #[test]
+    fn test_png_export_with_fallback_font() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.png");
+        let graph_nodes = vec![
+            GraphNode::for_test("main.rs", Language::Rust, 10, &["lib.rs"]),
+            GraphNode::for_test("lib.rs", Language::Rust, 10, &[]),
+        ];
+
+        render_png(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust]),
+            &ExportOptions::default(),
+            None,
+            &load_fallback_font(),
+        )
+        .unwrap();
+
+        let bytes = fs::read(&output).unwrap();
+        assert!(bytes.starts_with(b"\x89PNG"));
+
+        // A header makes the image taller
+        let options = ExportOptions {
+            title: Some("seiri".to_string()),
+            ..Default::default()
+        };
+        render_png(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust]),
+            &options,
+            None,
+            &load_fallback_font(),
+        )
+        .unwrap();
+        let pixmap = Pixmap::load_png(&output).unwrap();
+        assert_eq!(
+            pixmap.height(),
+            (CANVAS_HEIGHT + options.header_height()) as u32
+        );
+    }
+
+    #[test]
+    fn test_png_export_scale() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.png");
+        let graph_nodes = vec![
+            GraphNode::for_test("main.rs", Language::Rust, 10, &["lib.rs"]),
+            GraphNode::for_test("lib.rs", Language::Rust, 10, &[]),
+        ];
+        let options = ExportOptions {
+            scale: Some(2.0),
+            ..Default::default()
+        };
+
+        render_png(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust]),
+            &options,
+            None,
+            &load_fallback_font(),
+        )
+        .unwrap();
+        let pixmap = Pixmap::load_png(&output).unwrap();
+        assert_eq!((pixmap.width(), pixmap.height()), (2400, 1800));
+    }
+
+    #[test]
+    fn test_svg_title_and_subtitle() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.svg");
+        let graph_nodes = vec![
+            GraphNode::for_test("main.rs", Language::Rust, 10, &["lib.rs"]),
+            GraphNode::for_test("lib.rs", Language::Rust, 10, &[]),
+        ];
+        let options = ExportOptions {
+            title: Some("seiri".to_string()),
+            subtitle: Some("2026-10-16 · abc1234 · 2 files".to_string()),
+            ..Default::default()
+        };
+        let header_height = TITLE_HEIGHT + SUBTITLE_HEIGHT + HEADER_PADDING;
+        assert_eq!(options.header_height(), header_height);
+        assert_eq!(ExportOptions::default().header_height(), 0.0);
+
+        export_graph_as_svg(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust]),
+            &options,
+            None,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        let document = roxmltree::Document::parse(&content).unwrap();
+        let svg = document.root_element();
+        assert_eq!(
+            svg.attribute("height").unwrap().parse::<f32>().unwrap(),
+            CANVAS_HEIGHT + header_height
+        );
+        assert_eq!(
+            svg.attribute("viewBox").unwrap(),
+            format!(
+                "0 -{header_height} {CANVAS_WIDTH} {}",
+                CANVAS_HEIGHT + header_height
+            )
+        );
+
+        let text_of = |class: &str| {
+            let node = document
+                .descendants()
+                .find(|n| n.attribute("class") == Some(class))
+                .unwrap();
+            let y: f32 = node.attribute("y").unwrap().parse().unwrap();
+            (node.text().unwrap().trim().to_string(), y)
+        };
+        let (title, title_y) = text_of("title");
+        let (subtitle, subtitle_y) = text_of("subtitle");
+        assert_eq!(title, "seiri");
+        assert_eq!(subtitle, "2026-10-16 · abc1234 · 2 files");
+        // Both lines sit above the canvas, where the graph starts
+        assert!(title_y < subtitle_y && subtitle_y < 0.0);
+    }
+
+    #[test]
+    fn test_legend_min_count_omits_rare_languages() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.svg");
+        let graph_nodes = vec![
+            GraphNode::for_test("main.rs", Language::Rust, 10, &["lib.rs"]),
+            GraphNode::for_test("lib.rs", Language::Rust, 10, &[]),
+            GraphNode::for_test("generated.ts", Language::TypeScript, 10, &[]),
+        ];
+        let detected = HashSet::from([Language::Rust, Language::TypeScript]);
+
+        assert_eq!(
+            legend_languages(&graph_nodes, &detected, 2),
+            vec![Language::Rust]
+        );
+
+        let options = ExportOptions {
+            legend_min_count: 2,
+            ..Default::default()
+        };
+        export_graph_as_svg(&graph_nodes, &output, detected, &options, None).unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(!content.contains("TypeScript"));
+        // The file itself is still colored by its language
+        assert!(content.contains(Language::TypeScript.color()));
+    }
+
+    #[test]
+    fn test_svg_degree_badges_match_edge_counts() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.svg");
+        let graph_nodes = vec![
+            GraphNode::for_test("main.rs", Language::Rust, 10, &["lib.rs", "util.rs"]),
+            GraphNode::for_test("lib.rs", Language::Rust, 10, &["util.rs"]),
+            GraphNode::for_test("util.rs", Language::Rust, 10, &[]),
+        ];
+
+        let options = ExportOptions {
+            show_degree: true,
+            ..Default::default()
+        };
+        export_graph_as_svg(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust]),
+            &options,
+            None,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        let document = roxmltree::Document::parse(&content).unwrap();
+        let badges: Vec<&str> = document
+            .descendants()
+            .filter(|n| n.attribute("class") == Some("degree-badge"))
+            .filter_map(|n| n.text())
+            .map(str::trim)
+            .collect();
+        // Nodes are drawn in path order: lib.rs, main.rs, util.rs
+        assert_eq!(badges, vec!["1", "2", "0"]);
+    }
+
+    #[test]
+    fn test_svg_uses_explicit_positions() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.svg");
+        let graph_nodes = vec![
+            GraphNode::for_test("main.rs", Language::Rust, 10, &["lib.rs"]),
+            GraphNode::for_test("lib.rs", Language::Rust, 10, &[]),
+            GraphNode::for_test("util.rs", Language::Rust, 10, &[]),
+        ];
+        let main = PathBuf::from("main.rs");
+        let lib = PathBuf::from("lib.rs");
+        // util.rs has no explicit position and falls back to the circular layout
+        let positions = HashMap::from([(&main, (100.0, 200.0)), (&lib, (300.0, 400.0))]);
+
+        export_graph_as_svg(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust]),
+            &ExportOptions::default(),
+            Some(&positions),
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        let document = roxmltree::Document::parse(&content).unwrap();
+        let centers: Vec<(f32, f32)> = document
+            .descendants()
+            .filter(|n| n.has_tag_name("circle"))
+            // Node circles come first, followed by the legend dots
+            .take(graph_nodes.len())
+            .map(|n| {
+                let coordinate = |name| n.attribute(name).unwrap().parse::<f32>().unwrap();
+                (coordinate("cx"), coordinate("cy"))
+            })
+            .collect();
+        let (fallback, _) = layout_positions(&graph_nodes, None, &ExportOptions::default());
+        assert_eq!(
+            centers,
+            // Nodes are drawn in path order: lib.rs, main.rs, util.rs
+            vec![
+                (300.0, 400.0),
+                (100.0, 200.0),
+                fallback[&PathBuf::from("util.rs")]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fit_to_canvas_keeps_nodes_inside_margins() {
+        let a = PathBuf::from("a.rs");
+        let b = PathBuf::from("b.rs");
+        let fitted = fit_to_canvas(&[(&a, (-500.0, -10.0)), (&b, (500.0, 10.0))]);
+
+        // The wide, flat layout is limited by the canvas width and centered vertically
+        let inset = MARGIN + MAX_NODE_RADIUS;
+        assert!((fitted[&a].0 - inset).abs() < 1e-3);
+        assert!((fitted[&b].0 - (CANVAS_WIDTH - inset)).abs() < 1e-3);
+        assert!((fitted[&a].1 + fitted[&b].1 - CANVAS_HEIGHT).abs() < 1e-3);
+        assert!(fitted[&a].1 < fitted[&b].1);
+    }
+
+    #[test]
+    fn test_node_style_for_state() {
+        let style = |modified, generated, entry_point| {
+            NodeStyle::for_state(NodeState {
+                modified,
+                generated,
+                entry_point,
+            })
+        };
+
+        assert_eq!(style(false, false, false), NodeStyle::default());
+        assert_eq!(style(true, false, false).pattern, BorderPattern::Dashed);
+        assert_eq!(style(false, true, false).pattern, BorderPattern::Dotted);
+        // An edit to a generated file is the more useful cue
+        assert_eq!(style(true, true, false).pattern, BorderPattern::Dashed);
+
+        let entry = style(false, false, true);
+        assert_eq!(entry.pattern, BorderPattern::Solid);
+        assert!(entry.thick);
+        assert_eq!(entry.stroke_width(2.0), 4.0);
+        assert!(style(true, false, true).thick);
+
+        assert_eq!(NodeStyle::default().dash_array(2.0), None);
+        assert_eq!(style(true, false, false).dash_array(2.0), Some([6.0, 4.0]));
+        assert_eq!(style(false, true, false).dash_array(2.0), Some([2.0, 3.0]));
+    }
+
+    #[test]
+    fn test_curve_control_point_mirrors_reverse_edge() {
+        let forward = curve_control_point((0.0, 0.0), (100.0, 0.0));
+        let backward = curve_control_point((100.0, 0.0), (0.0, 0.0));
+        assert_eq!(forward.0, backward.0);
+        assert_eq!(forward.1, -backward.1);
+    }
+
+    #[test]
+    fn test_node_id_is_valid_identifier() {
+        let id = node_id("weird name/with-dots.rs", 3);
+        assert_eq!(id, "n3_weird_name_with_dots_rs");
+        assert!(id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+    }
+}
@@ -0,0 +1,154 @@
+use crate::analysis::{
+    GraphAnalysis, build_dependency_graph, build_edge_list, out_degrees, total_degrees,
+};
+use crate::core::defs::GraphNode;
+use petgraph::graph::NodeIndex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The node and edge tables written for `output_path`: `graph.csv` becomes
+/// `graph.nodes.csv` and `graph.edges.csv` next to it
+pub fn csv_output_paths(output_path: &Path) -> (PathBuf, PathBuf) {
+    let stem = output_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    (
+        output_path.with_file_name(format!("{stem}.nodes.csv")),
+        output_path.with_file_name(format!("{stem}.edges.csv")),
+    )
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One row per file: its path, language, LOC, fan-in, fan-out and betweenness
+fn build_nodes_csv(graph_nodes: &[GraphNode], edge_list: &[(usize, usize)]) -> String {
+    let analysis = GraphAnalysis::analyze_graph(&build_dependency_graph(graph_nodes));
+    let out_degree = out_degrees(graph_nodes.len(), edge_list);
+    let degree = total_degrees(graph_nodes.len(), edge_list);
+
+    let mut csv = String::from("path,language,loc,in_degree,out_degree,betweenness\n");
+    for (i, node) in graph_nodes.iter().enumerate() {
+        let betweenness = analysis
+            .get_betweenness_centrality(NodeIndex::new(i))
+            .unwrap_or(0.0);
+        csv.push_str(&format!(
+            "{},{},{},{},{},{betweenness:.6}\n",
+            csv_field(&node.data().file().to_string_lossy()),
+            csv_field(node.data().language().to_string()),
+            node.data().loc(),
+            degree[i] - out_degree[i],
+            out_degree[i],
+        ));
+    }
+    csv
+}
+
+/// One row per dependency, from the importing file to the file it depends on
+fn build_edges_csv(graph_nodes: &[GraphNode], edge_list: &[(usize, usize)]) -> String {
+    let mut csv = String::from("source_path,target_path\n");
+    for &(from, to) in edge_list {
+        csv.push_str(&format!(
+            "{},{}\n",
+            csv_field(&graph_nodes[from].data().file().to_string_lossy()),
+            csv_field(&graph_nodes[to].data().file().to_string_lossy()),
+        ));
+    }
+    csv
+}
+
+/// Export the graph as two CSV tables, see [`csv_output_paths`]
+pub fn export_graph_as_csv(graph_nodes: &[GraphNode], output_path: &Path) -> Result<(), String> {
+    let edge_list = build_edge_list(graph_nodes);
+    let (nodes_path, edges_path) = csv_output_paths(output_path);
+    fs::write(&nodes_path, build_nodes_csv(graph_nodes, &edge_list)).map_err(|e| e.to_string())?;
+    fs::write(&edges_path, build_edges_csv(graph_nodes, &edge_list)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::defs::Language;
+    use tempfile::TempDir;
+
+    /// Split a CSV document into rows of fields, honoring quoted fields
+    fn parse_csv(content: &str) -> Vec<Vec<String>> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut chars = content.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, quoted) {
+                ('"', true) if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                ('"', _) => quoted = !quoted,
+                (',', false) => row.push(std::mem::take(&mut field)),
+                ('\n', false) => {
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                }
+                _ => field.push(c),
+            }
+        }
+        rows
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.csv");
+        let graph_nodes = vec![
+            GraphNode::for_test(
+                "src/main.rs",
+                Language::Rust,
+                10,
+                &["src/a, \"b\".rs", "src/util.rs"],
+            ),
+            GraphNode::for_test("src/a, \"b\".rs", Language::Rust, 10, &["src/util.rs"]),
+            GraphNode::for_test("src/util.rs", Language::Rust, 10, &[]),
+            GraphNode::for_test("src/isolated.rs", Language::Rust, 10, &[]),
+        ];
+
+        export_graph_as_csv(&graph_nodes, &output).unwrap();
+        assert!(!output.exists());
+
+        let nodes =
+            parse_csv(&fs::read_to_string(temp_dir.path().join("graph.nodes.csv")).unwrap());
+        assert_eq!(
+            nodes[0],
+            [
+                "path",
+                "language",
+                "loc",
+                "in_degree",
+                "out_degree",
+                "betweenness"
+            ]
+        );
+        assert_eq!(nodes.len() - 1, graph_nodes.len());
+        assert_eq!(
+            nodes[2],
+            ["src/a, \"b\".rs", "Rust", "10", "1", "1", "0.000000"]
+        );
+        assert_eq!(nodes[3][3..5], ["2", "0"]);
+
+        let edges =
+            parse_csv(&fs::read_to_string(temp_dir.path().join("graph.edges.csv")).unwrap());
+        assert_eq!(edges[0], ["source_path", "target_path"]);
+        assert_eq!(edges.len() - 1, build_edge_list(&graph_nodes).len());
+        assert!(edges.contains(&vec![
+            "src/a, \"b\".rs".to_string(),
+            "src/util.rs".to_string()
+        ]));
+    }
+}
@@ -0,0 +1,157 @@
+use super::{ExportOptions, escape_html, legend_languages, node_id};
+use crate::core::defs::{GraphNode, Language};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Escape a string for use inside a quoted DOT attribute
+fn escape_label(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// An HTML-like label showing the file name, LOC, and function/container counts
+fn rich_label(node: &GraphNode) -> String {
+    let data = node.data();
+    let name = data
+        .file()
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!(
+        "<<TABLE BORDER=\"0\" CELLBORDER=\"1\" CELLSPACING=\"0\" BGCOLOR=\"{}\">\
+         <TR><TD><B>{}</B></TD></TR>\
+         <TR><TD>LOC: {}</TD></TR>\
+         <TR><TD>Functions: {}</TD></TR>\
+         <TR><TD>Containers: {}</TD></TR>\
+         </TABLE>>",
+        data.language().color(),
+        escape_html(&name),
+        data.loc(),
+        data.functions().len(),
+        data.containers().len()
+    )
+}
+
+/// Render the graph as a GraphViz `digraph`
+fn build_dot(
+    graph_nodes: &[GraphNode],
+    detected_languages: &HashSet<Language>,
+    rich_labels: bool,
+) -> String {
+    let mut dot = String::from("digraph seiri {\n");
+    dot.push_str("    node [shape=circle, style=filled, fontname=\"Arial\"];\n");
+
+    // Legend, as comments so it doesn't add nodes to the graph
+    for lang in legend_languages(graph_nodes, detected_languages, 0) {
+        dot.push_str(&format!("    // {}: {}\n", lang.to_string(), lang.color()));
+    }
+
+    let mut ids: HashMap<&PathBuf, String> = HashMap::new();
+    for (i, node) in graph_nodes.iter().enumerate() {
+        let file = node.data().file();
+        let stem = file
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let id = node_id(&stem, i);
+        if rich_labels {
+            dot.push_str(&format!(
+                "    {id} [shape=plaintext, style=\"\", label={}];\n",
+                rich_label(node)
+            ));
+        } else {
+            dot.push_str(&format!(
+                "    {id} [label=\"{}\", fillcolor=\"{}\"];\n",
+                escape_label(&stem),
+                node.data().language().color()
+            ));
+        }
+        ids.insert(file, id);
+    }
+
+    for node in graph_nodes {
+        let from = &ids[node.data().file()];
+        for edge in node.edges() {
+            if let Some(to) = ids.get(edge) {
+                dot.push_str(&format!("    {from} -> {to};\n"));
+            }
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+pub fn export_graph_as_dot(
+    graph_nodes: &[GraphNode],
+    output_path: &Path,
+    detected_languages: HashSet<Language>,
+    options: &ExportOptions,
+) -> Result<(), String> {
+    let mut file = File::create(output_path).map_err(|e| e.to_string())?;
+    let dot = build_dot(graph_nodes, &detected_languages, options.rich_labels);
+    file.write_all(dot.as_bytes()).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::defs::FileNode;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_dot_nodes_and_edges() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.dot");
+        let graph_nodes = vec![
+            GraphNode::for_test("src/my main.rs", Language::Rust, 10, &["src/util-lib.rs"]),
+            GraphNode::for_test("src/util-lib.rs", Language::Rust, 10, &[]),
+            GraphNode::for_test("src/isolated.rs", Language::Rust, 10, &[]),
+        ];
+
+        export_graph_as_dot(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust]),
+            &ExportOptions::default(),
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(content.starts_with("digraph seiri {"));
+        assert!(content.contains("n0_my_main [label=\"my main\", fillcolor=\"#DEA584\"];"));
+        assert!(content.contains("n1_util_lib [label=\"util-lib\", fillcolor=\"#DEA584\"];"));
+        assert!(content.contains("n2_isolated [label=\"isolated\""));
+        assert!(content.contains("n0_my_main -> n1_util_lib;"));
+        assert_eq!(content.matches("->").count(), 1);
+    }
+
+    #[test]
+    fn test_dot_rich_labels() {
+        let data = FileNode::new(
+            PathBuf::from("src/a<b>&c.rs"),
+            42,
+            Language::Rust,
+            HashSet::new(),
+            HashSet::from(["run".to_string(), "stop".to_string()]),
+            HashSet::from(["Engine".to_string()]),
+            HashSet::new(),
+        );
+        let graph_nodes = vec![GraphNode::new(data, vec![])];
+
+        let plain = build_dot(&graph_nodes, &HashSet::from([Language::Rust]), false);
+        assert!(!plain.contains("<TABLE"));
+
+        let rich = build_dot(&graph_nodes, &HashSet::from([Language::Rust]), true);
+        assert!(rich.contains("label=<<TABLE"));
+        assert!(rich.contains("</TABLE>>];"));
+        assert!(rich.contains("<B>a&lt;b&gt;&amp;c.rs</B>"));
+        assert!(rich.contains("<TD>LOC: 42</TD>"));
+        assert!(rich.contains("<TD>Functions: 2</TD>"));
+        assert!(rich.contains("<TD>Containers: 1</TD>"));
+        assert!(rich.contains("BGCOLOR=\"#DEA584\""));
+    }
+}
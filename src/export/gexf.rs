@@ -0,0 +1,154 @@
+use crate::analysis::{GraphAnalysis, build_dependency_graph, build_edge_list};
+use crate::core::defs::GraphNode;
+use petgraph::graph::NodeIndex;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// Escape the characters that are not allowed inside XML attribute values
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Render the graph as a static GEXF 1.3 document
+fn build_gexf(graph_nodes: &[GraphNode]) -> String {
+    let analysis = GraphAnalysis::analyze_graph(&build_dependency_graph(graph_nodes));
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(
+        "<gexf xmlns=\"http://gexf.net/1.3\" \
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+         xsi:schemaLocation=\"http://gexf.net/1.3 http://gexf.net/1.3/gexf.xsd\" \
+         version=\"1.3\">\n",
+    );
+    xml.push_str("  <meta>\n    <creator>seiri</creator>\n  </meta>\n");
+    xml.push_str("  <graph mode=\"static\" defaultedgetype=\"directed\">\n");
+
+    // Attribute declarations, referenced by id from each node
+    xml.push_str("    <attributes class=\"node\">\n");
+    xml.push_str("      <attribute id=\"0\" title=\"language\" type=\"string\"/>\n");
+    xml.push_str("      <attribute id=\"1\" title=\"loc\" type=\"integer\"/>\n");
+    xml.push_str("      <attribute id=\"2\" title=\"betweenness\" type=\"double\"/>\n");
+    xml.push_str("      <attribute id=\"3\" title=\"scc\" type=\"integer\"/>\n");
+    xml.push_str("    </attributes>\n");
+
+    xml.push_str("    <nodes>\n");
+    for (i, node) in graph_nodes.iter().enumerate() {
+        let file = node.data().file();
+        let label = file
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_else(|| file.to_string_lossy());
+        let index = NodeIndex::new(i);
+        let betweenness = analysis.get_betweenness_centrality(index).unwrap_or(0.0);
+        let scc = analysis.node_to_scc.get(&index).copied().unwrap_or(0);
+
+        xml.push_str(&format!(
+            "      <node id=\"{i}\" label=\"{}\">\n",
+            escape_xml(&label)
+        ));
+        xml.push_str("        <attvalues>\n");
+        xml.push_str(&format!(
+            "          <attvalue for=\"0\" value=\"{}\"/>\n",
+            node.data().language().to_string()
+        ));
+        xml.push_str(&format!(
+            "          <attvalue for=\"1\" value=\"{}\"/>\n",
+            node.data().loc()
+        ));
+        xml.push_str(&format!(
+            "          <attvalue for=\"2\" value=\"{betweenness}\"/>\n"
+        ));
+        xml.push_str(&format!(
+            "          <attvalue for=\"3\" value=\"{scc}\"/>\n"
+        ));
+        xml.push_str("        </attvalues>\n");
+        xml.push_str("      </node>\n");
+    }
+    xml.push_str("    </nodes>\n");
+
+    xml.push_str("    <edges>\n");
+    for (id, (from, to)) in build_edge_list(graph_nodes).into_iter().enumerate() {
+        xml.push_str(&format!(
+            "      <edge id=\"{id}\" source=\"{from}\" target=\"{to}\"/>\n"
+        ));
+    }
+    xml.push_str("    </edges>\n");
+
+    xml.push_str("  </graph>\n");
+    xml.push_str("</gexf>\n");
+    xml
+}
+
+pub fn export_graph_as_gexf(graph_nodes: &[GraphNode], output_path: &Path) -> Result<(), String> {
+    let mut file = File::create(output_path).map_err(|e| e.to_string())?;
+    file.write_all(build_gexf(graph_nodes).as_bytes())
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::defs::Language;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_gexf_attributes() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.gexf");
+        // a -> b -> c, so b is the only node on a shortest path between others
+        let graph_nodes = vec![
+            GraphNode::for_test("a.rs", Language::Rust, 10, &["b.rs"]),
+            GraphNode::for_test("b.rs", Language::Rust, 42, &["c.rs"]),
+            GraphNode::for_test("c.rs", Language::Rust, 7, &[]),
+        ];
+
+        export_graph_as_gexf(&graph_nodes, &output).unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        let doc = roxmltree::Document::parse(&content).unwrap();
+        let root = doc.root_element();
+        assert_eq!(root.tag_name().name(), "gexf");
+        assert_eq!(root.tag_name().namespace(), Some("http://gexf.net/1.3"));
+
+        let titles: Vec<&str> = doc
+            .descendants()
+            .filter(|n| n.has_tag_name("attribute"))
+            .filter_map(|n| n.attribute("title"))
+            .collect();
+        assert_eq!(titles, vec!["language", "loc", "betweenness", "scc"]);
+
+        let node_b = doc
+            .descendants()
+            .find(|n| n.has_tag_name("node") && n.attribute("label") == Some("b.rs"))
+            .unwrap();
+        let value_for = |id: &str| {
+            node_b
+                .descendants()
+                .find(|n| n.has_tag_name("attvalue") && n.attribute("for") == Some(id))
+                .and_then(|n| n.attribute("value"))
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(value_for("0"), "Rust");
+        assert_eq!(value_for("1"), "42");
+        assert!(value_for("2").parse::<f64>().unwrap() > 0.0);
+        assert!(value_for("3").parse::<usize>().is_ok());
+
+        let edges = doc.descendants().filter(|n| n.has_tag_name("edge")).count();
+        assert_eq!(edges, 2);
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("a&b<c>\"d'"), "a&amp;b&lt;c&gt;&quot;d&apos;");
+    }
+}
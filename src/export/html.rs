@@ -0,0 +1,283 @@
+use super::{
+    CANVAS_HEIGHT, CANVAS_WIDTH, ExportOptions, MAX_NODE_RADIUS, MIN_NODE_RADIUS, escape_html,
+    layout_positions, legend_languages,
+};
+use crate::analysis::{build_edge_list, out_degrees, total_degrees};
+use crate::core::defs::{GraphNode, Language};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use svg::Document;
+use svg::node::element::{Circle, Group, Line, Marker, Path as SvgPath, Text, Title, path::Data};
+
+const STYLE: &str = r#"
+body { margin: 0; font-family: Arial, sans-serif; background: white; }
+#legend { position: fixed; top: 12px; left: 12px; padding: 8px 12px; background: rgba(255, 255, 255, 0.9); border: 1px solid #ccc; border-radius: 4px; font-size: 12px; }
+#legend .swatch { display: inline-block; width: 12px; height: 12px; margin-right: 6px; border: 1px solid black; border-radius: 50%; vertical-align: middle; }
+svg { display: block; width: 100vw; height: 100vh; cursor: grab; }
+svg.focused circle:not(.highlight), svg.focused line:not(.highlight), svg.focused text { opacity: 0.15; }
+line.highlight { stroke: #ff9632; }
+circle.highlight { stroke-width: 3; }
+"#;
+
+/// Pans on drag, zooms around the cursor on scroll, and highlights a clicked node
+/// along with its direct dependencies and dependents
+const SCRIPT: &str = r#"
+(function () {
+  const svg = document.querySelector('svg');
+  const viewport = document.getElementById('viewport');
+  let scale = 1, x = 0, y = 0, drag = null, moved = false;
+  const apply = () => viewport.setAttribute('transform', `translate(${x} ${y}) scale(${scale})`);
+  const point = (e) => {
+    const p = svg.createSVGPoint();
+    p.x = e.clientX;
+    p.y = e.clientY;
+    return p.matrixTransform(svg.getScreenCTM().inverse());
+  };
+  svg.addEventListener('wheel', (e) => {
+    e.preventDefault();
+    const p = point(e);
+    const factor = e.deltaY < 0 ? 1.1 : 1 / 1.1;
+    x = p.x - (p.x - x) * factor;
+    y = p.y - (p.y - y) * factor;
+    scale *= factor;
+    apply();
+  }, { passive: false });
+  svg.addEventListener('mousedown', (e) => {
+    const p = point(e);
+    drag = { x: p.x - x, y: p.y - y };
+    moved = false;
+  });
+  window.addEventListener('mousemove', (e) => {
+    if (!drag) return;
+    const p = point(e);
+    x = p.x - drag.x;
+    y = p.y - drag.y;
+    moved = true;
+    apply();
+  });
+  window.addEventListener('mouseup', () => { drag = null; });
+  svg.addEventListener('click', (e) => {
+    if (moved) return;
+    svg.querySelectorAll('.highlight').forEach((el) => el.classList.remove('highlight'));
+    const node = e.target.closest('circle[data-index]');
+    svg.classList.toggle('focused', node !== null);
+    if (!node) return;
+    const i = node.dataset.index;
+    node.classList.add('highlight');
+    svg.querySelectorAll(`line[data-from="${i}"], line[data-to="${i}"]`).forEach((edge) => {
+      edge.classList.add('highlight');
+      const other = edge.dataset.from === i ? edge.dataset.to : edge.dataset.from;
+      svg.querySelector(`circle[data-index="${other}"]`).classList.add('highlight');
+    });
+  });
+})();
+"#;
+
+/// Inline SVG of the graph: edges and nodes inside a `#viewport` group the script
+/// transforms, each node with a tooltip of its path, LOC and dependency counts.
+/// Nodes are placed with `options.layout`, like in SVG/PNG exports.
+fn build_svg(graph_nodes: &[GraphNode], options: &ExportOptions) -> Document {
+    let (positions, index_of) = layout_positions(graph_nodes, None, options);
+    let edge_list = build_edge_list(graph_nodes);
+    let dependencies = out_degrees(graph_nodes.len(), &edge_list);
+    let degrees = total_degrees(graph_nodes.len(), &edge_list);
+    let min_loc = graph_nodes
+        .iter()
+        .map(|n| n.data().loc())
+        .min()
+        .unwrap_or(0);
+    let max_loc = graph_nodes
+        .iter()
+        .map(|n| n.data().loc())
+        .max()
+        .unwrap_or(0);
+
+    let arrow = SvgPath::new()
+        .set(
+            "d",
+            Data::new()
+                .move_to((0, 0))
+                .line_to((10, 3.5))
+                .line_to((0, 7))
+                .close(),
+        )
+        .set("fill", "lightblue");
+    let marker = Marker::new()
+        .set("id", "arrowhead")
+        .set("markerWidth", 10)
+        .set("markerHeight", 7)
+        .set("refX", 10)
+        .set("refY", 3.5)
+        .set("orient", "auto")
+        .add(arrow);
+
+    let mut viewport = Group::new().set("id", "viewport");
+    for &(from, to) in &edge_list {
+        let (x1, y1) = positions[graph_nodes[from].data().file()];
+        let (x2, y2) = positions[graph_nodes[to].data().file()];
+        viewport = viewport.add(
+            Line::new()
+                .set("data-from", from)
+                .set("data-to", to)
+                .set("x1", x1)
+                .set("y1", y1)
+                .set("x2", x2)
+                .set("y2", y2)
+                .set("stroke", "lightblue")
+                .set("stroke-width", 2)
+                .set("marker-end", "url(#arrowhead)"),
+        );
+    }
+
+    for node in graph_nodes {
+        let file = node.data().file();
+        let i = index_of[file];
+        let (x, y) = positions[file];
+        let radius = node.calculate_size(min_loc, max_loc, MIN_NODE_RADIUS, MAX_NODE_RADIUS, None);
+        let tooltip = format!(
+            "{}\nLOC: {}\nDependencies: {}\nDependents: {}",
+            file.display(),
+            node.data().loc(),
+            dependencies[i],
+            degrees[i] - dependencies[i]
+        );
+        viewport = viewport.add(
+            Circle::new()
+                .set("data-index", i)
+                .set("cx", x)
+                .set("cy", y)
+                .set("r", radius)
+                .set("fill", node.data().language().color())
+                .set("stroke", "black")
+                .set("stroke-width", 2)
+                .add(Title::new(tooltip)),
+        );
+        if let Some(name) = node.label(None) {
+            viewport = viewport.add(
+                Text::new(name)
+                    .set("x", x)
+                    .set("y", y)
+                    .set("text-anchor", "middle")
+                    .set("dominant-baseline", "middle")
+                    .set("font-size", 12)
+                    .set("pointer-events", "none"),
+            );
+        }
+    }
+
+    Document::new()
+        .set("viewBox", (0, 0, CANVAS_WIDTH as i32, CANVAS_HEIGHT as i32))
+        .add(marker)
+        .add(viewport)
+}
+
+/// Render a standalone HTML page with the graph, its legend, and the pan/zoom script
+fn build_html(
+    graph_nodes: &[GraphNode],
+    detected_languages: &HashSet<Language>,
+    options: &ExportOptions,
+) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>seiri</title>\n<style>",
+    );
+    html.push_str(STYLE);
+    html.push_str("</style>\n</head>\n<body>\n<div id=\"legend\">\n");
+    for lang in legend_languages(graph_nodes, detected_languages, 0) {
+        html.push_str(&format!(
+            "<div><span class=\"swatch\" style=\"background: {}\"></span>{}</div>\n",
+            lang.color(),
+            escape_html(lang.to_string())
+        ));
+    }
+    html.push_str("</div>\n");
+    html.push_str(&build_svg(graph_nodes, options).to_string());
+    html.push_str("\n<script>");
+    html.push_str(SCRIPT);
+    html.push_str("</script>\n</body>\n</html>\n");
+    html
+}
+
+/// Export the graph as a self-contained HTML page that can be panned, zoomed and
+/// clicked through offline
+pub fn export_graph_as_html(
+    graph_nodes: &[GraphNode],
+    output_path: &Path,
+    detected_languages: HashSet<Language>,
+    options: &ExportOptions,
+) -> Result<(), String> {
+    let mut file = File::create(output_path).map_err(|e| e.to_string())?;
+    file.write_all(build_html(graph_nodes, &detected_languages, options).as_bytes())
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::auto_positions;
+    use crate::layout::LayoutType;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_html_export() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.html");
+        let graph_nodes = vec![
+            GraphNode::for_test(
+                "src/main.rs",
+                Language::Rust,
+                40,
+                &["src/util.rs", "app.py"],
+            ),
+            GraphNode::for_test("src/util.rs", Language::Rust, 10, &[]),
+            GraphNode::for_test("app.py", Language::Python, 25, &["src/util.rs"]),
+        ];
+
+        export_graph_as_html(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust, Language::Python]),
+            &ExportOptions::default(),
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(content.starts_with("<!DOCTYPE html>"));
+        assert!(content.contains("<svg"));
+        assert!(content.contains("<script>"));
+        assert_eq!(content.matches("<circle").count(), graph_nodes.len());
+        assert_eq!(content.matches("<line").count(), 3);
+        // Nothing is fetched over the network
+        assert!(!content.contains("src=\"http"));
+        assert!(!content.contains("href=\"http"));
+
+        assert!(content.contains("src/util.rs\nLOC: 10\nDependencies: 0\nDependents: 2"));
+        assert!(content.contains("src/main.rs\nLOC: 40\nDependencies: 2\nDependents: 0"));
+        assert!(content.contains("</span>Python</div>"));
+        assert!(content.contains("</span>Rust</div>"));
+    }
+
+    #[test]
+    fn test_html_export_uses_layout() {
+        let graph_nodes = vec![
+            GraphNode::for_test("main.rs", Language::Rust, 10, &["util.rs"]),
+            GraphNode::for_test("util.rs", Language::Rust, 10, &[]),
+        ];
+        let options = ExportOptions {
+            layout: LayoutType::Grid,
+            ..Default::default()
+        };
+
+        let document = build_svg(&graph_nodes, &options).to_string();
+        let positions = auto_positions(&graph_nodes, LayoutType::Grid);
+        let (x, y) = positions[&PathBuf::from("util.rs")];
+        assert!(
+            document.contains(&format!("cx=\"{x}\" cy=\"{y}\"")),
+            "{document}"
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use crate::analysis::{GraphAnalysis, build_dependency_graph};
+use crate::core::defs::GraphNode;
+use petgraph::graph::NodeIndex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize)]
+struct JsonAnalysis<'a> {
+    scc_sizes: Vec<usize>,
+    largest_scc_size: usize,
+    /// Betweenness centrality keyed by file path
+    betweenness_centrality: BTreeMap<&'a PathBuf, f64>,
+}
+
+#[derive(Serialize)]
+struct JsonExport<'a> {
+    files: &'a [GraphNode],
+    analysis: JsonAnalysis<'a>,
+}
+
+pub fn export_graph_as_json(graph_nodes: &[GraphNode], output_path: &Path) -> Result<(), String> {
+    let analysis = GraphAnalysis::analyze_graph(&build_dependency_graph(graph_nodes));
+    let betweenness_centrality = graph_nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| {
+            let score = analysis
+                .get_betweenness_centrality(NodeIndex::new(i))
+                .unwrap_or(0.0);
+            (node.data().file(), score)
+        })
+        .collect();
+
+    let export = JsonExport {
+        files: graph_nodes,
+        analysis: JsonAnalysis {
+            scc_sizes: analysis.scc_sizes.clone(),
+            largest_scc_size: analysis.largest_scc_size,
+            betweenness_centrality,
+        },
+    };
+
+    let file = File::create(output_path).map_err(|e| e.to_string())?;
+    serde_json::to_writer_pretty(file, &export).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::defs::{FileNode, Import, Language};
+    use std::collections::HashSet;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_json_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.json");
+        let main = FileNode::new(
+            PathBuf::from("src/main.cpp"),
+            12,
+            Language::Cpp,
+            HashSet::from([
+                Import::new("util.h".to_string(), true),
+                Import::new("vector".to_string(), false),
+            ]),
+            HashSet::from(["main".to_string()]),
+            HashSet::new(),
+            HashSet::new(),
+        );
+        let util = FileNode::new(
+            PathBuf::from("src/util.h"),
+            4,
+            Language::Cpp,
+            HashSet::new(),
+            HashSet::new(),
+            HashSet::from(["Util".to_string()]),
+            HashSet::new(),
+        );
+        let graph_nodes = vec![
+            GraphNode::new(main, vec![PathBuf::from("src/util.h")]),
+            GraphNode::new(util, vec![]),
+        ];
+
+        export_graph_as_json(&graph_nodes, &output).unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let files = json["files"].as_array().unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0]["file"], "src/main.cpp");
+        assert_eq!(files[0]["language"], "C++");
+        assert_eq!(files[0]["loc"], 12);
+        assert_eq!(files[0]["edges"][0], "src/util.h");
+        assert_eq!(files[0]["imports"].as_array().unwrap().len(), 2);
+        assert_eq!(json["analysis"]["largest_scc_size"], 1);
+        assert_eq!(json["analysis"]["scc_sizes"].as_array().unwrap().len(), 2);
+        assert!(json["analysis"]["betweenness_centrality"]["src/util.h"].is_number());
+    }
+}
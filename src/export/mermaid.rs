@@ -0,0 +1,120 @@
+use super::{legend_languages, node_id};
+use crate::core::defs::{GraphNode, Language};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Name of the `classDef` used to color a language's nodes, e.g. `lang_cpp` for C++
+fn class_name(language: Language) -> String {
+    let name: String = language
+        .to_string()
+        .to_ascii_lowercase()
+        .chars()
+        .map(|c| if c == '+' { 'p' } else { c })
+        .collect();
+    format!("lang_{name}")
+}
+
+/// Escape a label for use inside a quoted Mermaid node label
+fn escape_label(label: &str) -> String {
+    label.replace('"', "#quot;")
+}
+
+/// Render the graph as a Mermaid `flowchart`
+fn build_mermaid(graph_nodes: &[GraphNode], detected_languages: &HashSet<Language>) -> String {
+    let mut mermaid = String::from("flowchart LR\n");
+
+    let mut ids: HashMap<&PathBuf, String> = HashMap::new();
+    for (i, node) in graph_nodes.iter().enumerate() {
+        let file = node.data().file();
+        let stem = file
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let id = node_id(&stem, i);
+        mermaid.push_str(&format!("    {id}[\"{}\"]\n", escape_label(&stem)));
+        ids.insert(file, id);
+    }
+
+    for node in graph_nodes {
+        let from = &ids[node.data().file()];
+        for edge in node.edges() {
+            if let Some(to) = ids.get(edge) {
+                mermaid.push_str(&format!("    {from} --> {to}\n"));
+            }
+        }
+    }
+
+    for lang in legend_languages(graph_nodes, detected_languages, 0) {
+        let members: Vec<&str> = graph_nodes
+            .iter()
+            .filter(|node| node.data().language() == &lang)
+            .map(|node| ids[node.data().file()].as_str())
+            .collect();
+        let class = class_name(lang);
+        mermaid.push_str(&format!(
+            "    classDef {class} fill:{},stroke:#333\n",
+            lang.color()
+        ));
+        mermaid.push_str(&format!("    class {} {class}\n", members.join(",")));
+    }
+
+    mermaid
+}
+
+pub fn export_graph_as_mermaid(
+    graph_nodes: &[GraphNode],
+    output_path: &Path,
+    detected_languages: HashSet<Language>,
+) -> Result<(), String> {
+    let mut file = File::create(output_path).map_err(|e| e.to_string())?;
+    file.write_all(build_mermaid(graph_nodes, &detected_languages).as_bytes())
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_mermaid_nodes_edges_and_classes() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.mmd");
+        let graph_nodes = vec![
+            GraphNode::for_test(
+                "src/my main.rs",
+                Language::Rust,
+                10,
+                &["src/util.lib.rs", "src/api.h"],
+            ),
+            GraphNode::for_test("src/util.lib.rs", Language::Rust, 10, &["src/api.h"]),
+            GraphNode::for_test("src/api.h", Language::Cpp, 10, &[]),
+        ];
+
+        export_graph_as_mermaid(
+            &graph_nodes,
+            &output,
+            HashSet::from([Language::Rust, Language::Cpp, Language::Python]),
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(content.starts_with("flowchart LR\n"));
+        assert!(content.contains("    n0_my_main[\"my main\"]\n"));
+        assert!(content.contains("    n1_util_lib[\"util.lib\"]\n"));
+        assert!(content.contains("    n0_my_main --> n1_util_lib\n"));
+        assert!(content.contains("    n1_util_lib --> n2_api\n"));
+        let edge_count: usize = graph_nodes.iter().map(|n| n.edges().len()).sum();
+        assert_eq!(content.matches("-->").count(), edge_count);
+
+        assert!(content.contains("classDef lang_rust fill:#DEA584"));
+        assert!(content.contains("class n0_my_main,n1_util_lib lang_rust\n"));
+        assert!(content.contains("class n2_api lang_cpp\n"));
+        // Languages without files get no class
+        assert!(!content.contains("lang_python"));
+    }
+}
@@ -1,13 +1,65 @@
-use crate::analysis::GraphAnalysis;
-use crate::core::defs::GraphNode;
+use crate::analysis::{
+    AnalysisCache, GraphAnalysis, MetricMode, build_dependency_graph, build_edge_list,
+    find_bidirectional_pairs, out_degrees, total_degrees,
+};
+use crate::core::defs::{GraphNode, Import, Language};
+use crate::export::{self, ExportOptions, NodeState, NodeStyle};
 use crate::gui::camera::Camera;
+use crate::gui::table::{NodeTable, build_rows};
+use crate::layout::directories::{DirectoryTree, TREE_PADDING};
+use crate::layout::grid::directory_groups;
+use crate::layout::overlap::{OverlapConfig, remove_overlaps};
+use crate::layout::radial::{RadialConfig, RadialLayout};
 use crate::layout::{self, LayoutType};
 use eframe::egui;
-use egui::{Rect, Response, Sense, Ui, Vec2, pos2, vec2};
+use egui::{Pos2, Rect, Response, Sense, Ui, Vec2, vec2};
 use petgraph::{Graph, graph::NodeIndex};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc::Receiver;
 
 mod camera;
+mod table;
+
+/// What node fill colors stand for
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ColorBy {
+    /// Each file's language, with the largest SCC in red
+    #[default]
+    Language,
+    /// The community each file was assigned to by community detection
+    Community,
+}
+
+impl FromStr for ColorBy {
+    type Err = String;
+
+    /// Parse a coloring as given on the command line, e.g. "community"
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "language" => Ok(ColorBy::Language),
+            "community" => Ok(ColorBy::Community),
+            _ => Err(format!(
+                "Unknown coloring: {name} (expected language or community)"
+            )),
+        }
+    }
+}
+
+/// Fill colors of communities, reused in order once there are more communities
+const COMMUNITY_COLORS: [egui::Color32; 10] = [
+    egui::Color32::from_rgb(31, 119, 180),
+    egui::Color32::from_rgb(255, 127, 14),
+    egui::Color32::from_rgb(44, 160, 44),
+    egui::Color32::from_rgb(214, 39, 40),
+    egui::Color32::from_rgb(148, 103, 189),
+    egui::Color32::from_rgb(140, 86, 75),
+    egui::Color32::from_rgb(227, 119, 194),
+    egui::Color32::from_rgb(127, 127, 127),
+    egui::Color32::from_rgb(188, 189, 34),
+    egui::Color32::from_rgb(23, 190, 207),
+];
 
 pub struct SeiriGraph {
     pub graph_nodes: Vec<GraphNode>,
@@ -28,18 +80,71 @@ pub struct SeiriGraph {
     min_node_radius: f32,
     max_node_radius: f32,
     show_labels: bool,
+    /// Dark, light, or following the system, kept for the rest of the session
+    theme: egui::ThemePreference,
+    /// Truncate node labels to this many characters
+    max_label_len: Option<usize>,
+    /// Draw each node's out-degree in a badge next to it
+    show_degree: bool,
+    /// Draw edges touching a node with more edges than this without arrowheads
+    max_arrow_degree: Option<usize>,
+    /// Draw dependencies that only test code has in a color of their own
+    highlight_test_edges: bool,
+    /// Push overlapping nodes apart after every layout
+    no_overlap: bool,
+    /// Draw a translucent region around the nodes of each language
+    group_by_language: bool,
+    /// Draw the directory tree as nested boxes behind the graph
+    show_tree: bool,
+    color_by: ColorBy,
     show_dependencies: bool,
+    /// Grow nodes by their betweenness centrality on top of their LOC-based size
+    size_by_centrality: bool,
+    /// Show a sortable table of all nodes instead of the graph
+    show_table: bool,
+    /// Files with uncommitted changes, drawn with a dashed border
+    modified_files: HashSet<PathBuf>,
 
     // Node size calculation
     min_loc: u32,
     max_loc: u32,
 
-    // Graph analysis
-    graph_analysis: Option<GraphAnalysis>,
+    // Graph analysis, only recomputed when the graph itself changes
+    graph: Graph<(), ()>,
+    analysis_cache: AnalysisCache,
+    metric_mode: MetricMode,
+    /// Summary shown in the metrics header bar, rebuilt whenever the analysis runs
+    metrics_header: String,
+    /// Node pairs that depend on each other, drawn as a single double-headed edge
+    bidirectional_pairs: HashSet<(usize, usize)>,
+    /// Outgoing edge count of each node, shown when `show_degree` is set
+    out_degrees: Vec<usize>,
+    /// Incoming plus outgoing edge count of each node, compared against `max_arrow_degree`
+    total_degrees: Vec<usize>,
+    /// Directory cluster of each node, drawn as one glyph when zoomed far out
+    cluster_of: Vec<usize>,
+    /// Directories of the files, drawn behind the graph when `show_tree` is set
+    directory_tree: DirectoryTree,
+    node_table: NodeTable,
+    /// Substring the details panel's import lists are filtered by
+    import_filter: String,
+    /// How many imports of each kind the details panel lists, grown by "Show more"
+    imports_shown: usize,
+    /// Node that `import_filter` and `imports_shown` were set for
+    imports_node: Option<usize>,
+
+    /// Where the "Export" button writes the current view (`.svg` or `.png`)
+    export_path: String,
+    /// Outcome of the last export, shown next to the button
+    export_status: Option<String>,
+
+    /// Rebuilt graphs sent by watch mode, with the files modified at that point
+    updates: Option<Receiver<(Vec<GraphNode>, HashSet<PathBuf>)>>,
 }
 
 impl SeiriGraph {
-    pub fn new(graph_nodes: Vec<GraphNode>) -> Self {
+    /// Build the viewer for `graph_nodes`, starting out with `layout_type`
+    pub fn new(graph_nodes: Vec<GraphNode>, layout_type: LayoutType) -> Self {
         let n = graph_nodes.len();
 
         // Calculate min/max LOC
@@ -59,46 +164,100 @@ impl SeiriGraph {
             camera_pos: Vec2::ZERO,
             camera: Camera::default(),
             node_positions: vec![Vec2::ZERO; n],
-            layout_type: LayoutType::default(),
+            layout_type,
             selected_node: None,
             hovered_node: None,
             min_node_radius: 20.0,
             max_node_radius: 40.0,
             show_labels: true,
+            theme: egui::ThemePreference::System,
+            max_label_len: None,
+            show_degree: false,
+            max_arrow_degree: None,
+            highlight_test_edges: false,
+            no_overlap: false,
+            group_by_language: false,
+            show_tree: false,
+            color_by: ColorBy::default(),
             show_dependencies: true,
+            size_by_centrality: true,
+            show_table: false,
+            modified_files: HashSet::new(),
             min_loc,
             max_loc,
-            graph_analysis: None,
+            graph: Graph::new(),
+            analysis_cache: AnalysisCache::new(),
+            metric_mode: MetricMode::default(),
+            metrics_header: String::new(),
+            bidirectional_pairs: HashSet::new(),
+            out_degrees: Vec::new(),
+            total_degrees: Vec::new(),
+            cluster_of: Vec::new(),
+            directory_tree: DirectoryTree::from_files(&[]),
+            node_table: NodeTable::default(),
+            import_filter: String::new(),
+            imports_shown: IMPORTS_PAGE_SIZE,
+            imports_node: None,
+            export_path: String::from("graph.svg"),
+            export_status: None,
+            updates: None,
         };
         app.initialize_positions();
         app
     }
 
-    fn initialize_positions(&mut self) {
-        let n = self.graph_nodes.len();
-        if n == 0 {
-            return;
-        }
-
-        // Create a graph for layout
-        let mut graph = Graph::new();
-        let mut node_indices = Vec::with_capacity(n);
+    /// Swap in a rebuilt graph, keeping the current view settings
+    fn replace_graph(&mut self, graph_nodes: Vec<GraphNode>) {
+        self.min_loc = graph_nodes
+            .iter()
+            .map(|n| n.data().loc())
+            .min()
+            .unwrap_or(0);
+        self.max_loc = graph_nodes
+            .iter()
+            .map(|n| n.data().loc())
+            .max()
+            .unwrap_or(0);
+        self.node_positions = vec![Vec2::ZERO; graph_nodes.len()];
+        self.graph_nodes = graph_nodes;
+        self.selected_node = None;
+        self.hovered_node = None;
+        self.initialize_positions();
+    }
 
-        // Add nodes
-        for _ in 0..n {
-            node_indices.push(graph.add_node(()));
-        }
+    fn initialize_positions(&mut self) {
+        // Create a graph for layout and analysis, where node `i` is `graph_nodes[i]`
+        self.graph = build_dependency_graph(&self.graph_nodes);
+        self.refresh_analysis();
+        let edge_list = build_edge_list(&self.graph_nodes);
+        self.bidirectional_pairs = find_bidirectional_pairs(&edge_list);
+        self.out_degrees = out_degrees(self.graph_nodes.len(), &edge_list);
+        self.total_degrees = total_degrees(self.graph_nodes.len(), &edge_list);
+        self.cluster_of = directory_groups(&self.graph_nodes);
+        let files: Vec<&Path> = self
+            .graph_nodes
+            .iter()
+            .map(|node| node.data().file().as_path())
+            .collect();
+        self.directory_tree = DirectoryTree::from_files(&files);
 
-        // Add edges based on dependencies
-        for (from_idx, node) in self.graph_nodes.iter().enumerate() {
-            for (dep_idx, _edge) in node.edges().iter().enumerate() {
-                graph.add_edge(node_indices[from_idx], node_indices[dep_idx], ());
-            }
+        if self.graph_nodes.is_empty() {
+            return;
         }
 
         // Get layout positions
-        let layout = layout::create_layout(self.layout_type);
-        let raw_positions = layout.layout(&graph);
+        let layout: Box<dyn layout::Layout> = match self.layout_type {
+            // Center on the selected node, or let the layout pick the busiest one
+            LayoutType::Radial => {
+                let radial = RadialLayout::new(RadialConfig::default());
+                match self.selected_node {
+                    Some(selected) => Box::new(radial.with_root(NodeIndex::new(selected))),
+                    None => Box::new(radial),
+                }
+            }
+            layout_type => layout::create_layout_for(layout_type, &self.graph_nodes),
+        };
+        let raw_positions = layout.layout(&self.graph);
 
         // Find the bounds of the layout
         let mut min_x = f32::INFINITY;
@@ -116,11 +275,10 @@ impl SeiriGraph {
         // Calculate center and scale
         let width = max_x - min_x;
         let height = max_y - min_y;
-        let target_size = 800.0; // Target layout size
         let scale = if width > height {
-            target_size / width
+            LAYOUT_SIZE / width
         } else {
-            target_size / height
+            LAYOUT_SIZE / height
         };
 
         // Center of the layout
@@ -128,34 +286,93 @@ impl SeiriGraph {
         let center_y = (min_y + max_y) / 2.0;
 
         // initialize positions
-        for (i, node_idx) in node_indices.iter().enumerate() {
-            if let Some(&(x, y)) = raw_positions.get(node_idx) {
+        for (i, node_idx) in self.graph.node_indices().enumerate() {
+            if let Some(&(x, y)) = raw_positions.get(&node_idx) {
                 // scale and center the coordinates in world space
                 let world_x = (x - center_x) * scale + 500.0; // center at world position 500, matches default of 1000
                 let world_y = (y - center_y) * scale + 500.0;
                 self.node_positions[i] = vec2(world_x, world_y);
             }
         }
-
-        // Analyze graph structure
-        self.graph_analysis = Some(GraphAnalysis::analyze_graph(&graph));
+        if self.no_overlap {
+            self.remove_overlaps();
+        }
 
         // Reset camera and zoom to frame the layout
         self.camera_pos = egui::Vec2::ZERO;
         self.camera.reset();
     }
 
+    /// Write the graph to `export_path` as SVG or PNG, keeping the current node positions
+    fn export_view(&self) -> Result<(), String> {
+        let path = Path::new(self.export_path.trim());
+        let positions: Vec<_> = self
+            .graph_nodes
+            .iter()
+            .zip(&self.node_positions)
+            .map(|(node, position)| (node.data().file(), (position.x, position.y)))
+            .collect();
+        let positions = export::fit_to_canvas(&positions);
+        let languages: HashSet<Language> = self
+            .graph_nodes
+            .iter()
+            .map(|node| *node.data().language())
+            .collect();
+        let options = ExportOptions {
+            max_label_len: self.max_label_len,
+            show_degree: self.show_degree,
+            modified_files: self.modified_files.clone(),
+            no_overlap: self.no_overlap,
+            show_tree: self.show_tree,
+            max_arrow_degree: self.max_arrow_degree,
+            highlight_test_edges: self.highlight_test_edges,
+            ..Default::default()
+        };
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("svg") => export::export_graph_as_svg(
+                &self.graph_nodes,
+                path,
+                languages,
+                &options,
+                Some(&positions),
+            ),
+            Some("png") => export::export_graph_as_png(
+                &self.graph_nodes,
+                path,
+                languages,
+                &options,
+                Some(&positions),
+            ),
+            _ => Err(String::from("Export path must end in .svg or .png")),
+        }
+    }
+
+    /// Recompute the graph metrics in the current mode, and the table rows that show them
+    fn refresh_analysis(&mut self) {
+        let analysis = self.analysis_cache.analyze(&self.graph, self.metric_mode);
+        self.metrics_header = metrics_header_text(&self.graph_nodes, analysis);
+        self.node_table.set_rows(build_rows(
+            &self.graph_nodes,
+            self.analysis_cache.analysis(),
+        ));
+    }
+
     fn get_node_color(&self, index: usize) -> egui::Color32 {
         let node = &self.graph_nodes[index];
         let is_external = !node.data().file().exists();
-        let in_largest_scc = self
-            .graph_analysis
-            .as_ref()
+        let analysis = self.analysis_cache.analysis();
+        let in_largest_scc = analysis
             .map(|analysis| analysis.is_in_largest_scc(NodeIndex::new(index)))
             .unwrap_or(false);
+        let community = analysis
+            .filter(|_| self.color_by == ColorBy::Community)
+            .and_then(|analysis| analysis.get_community(NodeIndex::new(index)));
 
         // change base color based on node type
-        let base_color = if in_largest_scc {
+        let base_color = if let Some(community) = community {
+            COMMUNITY_COLORS[community % COMMUNITY_COLORS.len()]
+        } else if in_largest_scc {
             egui::Color32::from_rgb(255, 100, 100) // Red for SCC nodes
         } else if is_external {
             egui::Color32::from_hex(node.data().language().color()).unwrap_or(egui::Color32::GRAY)
@@ -174,8 +391,60 @@ impl SeiriGraph {
         }
     }
 
+    /// Turn overlap removal on or off, applying it to the current layout right away
+    fn apply_no_overlap(&mut self, no_overlap: bool) {
+        self.no_overlap = no_overlap;
+        if no_overlap {
+            self.remove_overlaps();
+        }
+    }
+
+    /// Push apart nodes whose circles overlap at their current size
+    fn remove_overlaps(&mut self) {
+        let radii: Vec<f32> = (0..self.graph_nodes.len())
+            .map(|i| self.node_radius(i))
+            .collect();
+        let mut positions: Vec<(f32, f32)> =
+            self.node_positions.iter().map(|p| (p.x, p.y)).collect();
+        remove_overlaps(&mut positions, &radii, &OverlapConfig::default());
+        for (position, (x, y)) in self.node_positions.iter_mut().zip(positions) {
+            *position = vec2(x, y);
+        }
+    }
+
+    /// Radius of node `i` in world units, based on LOC and optionally betweenness centrality
+    fn node_radius(&self, i: usize) -> f32 {
+        let betweenness_score = if self.size_by_centrality {
+            self.analysis_cache
+                .analysis()
+                .and_then(|analysis| analysis.get_betweenness_centrality(NodeIndex::new(i)))
+        } else {
+            None
+        };
+
+        self.graph_nodes[i].calculate_size(
+            self.min_loc,
+            self.max_loc,
+            self.min_node_radius,
+            self.max_node_radius,
+            betweenness_score,
+        )
+    }
+
     fn draw_graph(&mut self, ui: &mut Ui, canvas_rect: &Rect) {
         let painter = ui.painter_at(*canvas_rect);
+        let detail = detail_level(self.camera.zoom_level(), self.graph_nodes.len());
+        if detail == DetailLevel::Clusters {
+            self.draw_clusters(&painter, canvas_rect);
+            return;
+        }
+
+        if self.show_tree {
+            self.draw_directory_tree(&painter, canvas_rect);
+        }
+        if self.group_by_language {
+            self.draw_language_hulls(&painter, canvas_rect);
+        }
 
         // Draw edges first (behind nodes)
         if self.show_dependencies {
@@ -190,6 +459,13 @@ impl SeiriGraph {
                         .iter()
                         .position(|n| n.data().file() == edge_file)
                     {
+                        // Mutual dependencies are drawn once, from the lower index
+                        let is_bidirectional =
+                            self.bidirectional_pairs.contains(&(i.min(j), i.max(j)));
+                        if is_bidirectional && i > j {
+                            continue;
+                        }
+
                         let to_pos = self
                             .camera
                             .world_to_screen(self.node_positions[j].to_pos2(), canvas_rect);
@@ -198,12 +474,17 @@ impl SeiriGraph {
                         if canvas_rect.contains(egui::pos2(from_pos.x, from_pos.y))
                             || canvas_rect.contains(egui::pos2(to_pos.x, to_pos.y))
                         {
-                            let edge_color =
-                                if Some(i) == self.selected_node || Some(j) == self.selected_node {
-                                    egui::Color32::from_rgb(255, 150, 50)
-                                } else {
-                                    egui::Color32::from_rgba_premultiplied(100, 150, 200, 80)
-                                };
+                            let edge_color = if Some(i) == self.selected_node
+                                || Some(j) == self.selected_node
+                            {
+                                egui::Color32::from_rgb(255, 150, 50)
+                            } else if self.highlight_test_edges
+                                && export::is_test_edge(&self.graph_nodes, i, j, is_bidirectional)
+                            {
+                                egui::Color32::from_rgba_unmultiplied(148, 103, 189, 160)
+                            } else {
+                                egui::Color32::from_rgba_premultiplied(100, 150, 200, 80)
+                            };
 
                             // Draw the main line
                             painter.line_segment(
@@ -217,36 +498,28 @@ impl SeiriGraph {
                                 ),
                             );
 
-                            // Calculate arrow direction
-                            let dir = (to_pos - from_pos).normalized();
-                            let arrow_size = 10.0 * self.camera.zoom_level().sqrt();
-                            let arrow_angle: f32 = 0.5; // ~30 degrees in radians
-
-                            // Calculate arrowhead points
-                            let arrow_end = to_pos - dir * (20.0 * self.camera.zoom_level().sqrt()); // Pull back from the end
-                            let left = arrow_end
-                                + arrow_size
-                                    * vec2(
-                                        -dir.x * arrow_angle.cos() + dir.y * arrow_angle.sin(),
-                                        -dir.x * arrow_angle.sin() - dir.y * arrow_angle.cos(),
-                                    );
-                            let right = arrow_end
-                                + arrow_size
-                                    * vec2(
-                                        -dir.x * arrow_angle.cos() - dir.y * arrow_angle.sin(),
-                                        dir.x * arrow_angle.sin() - dir.y * arrow_angle.cos(),
-                                    );
-
-                            // Draw arrowhead
-                            painter.add(egui::Shape::convex_polygon(
-                                vec![
-                                    pos2(to_pos.x, to_pos.y),
-                                    pos2(left.x, left.y),
-                                    pos2(right.x, right.y),
-                                ],
-                                edge_color,
-                                egui::Stroke::new(1.0, edge_color),
-                            ));
+                            // Draw arrowhead, on both ends for mutual dependencies,
+                            // unless the edge touches a hub
+                            let zoom = self.camera.zoom_level();
+                            if export::shows_arrowheads(
+                                &self.total_degrees,
+                                i,
+                                j,
+                                self.max_arrow_degree,
+                            ) {
+                                painter.add(egui::Shape::convex_polygon(
+                                    arrowhead_points(from_pos, to_pos, zoom).to_vec(),
+                                    edge_color,
+                                    egui::Stroke::new(1.0, edge_color),
+                                ));
+                                if is_bidirectional {
+                                    painter.add(egui::Shape::convex_polygon(
+                                        arrowhead_points(to_pos, from_pos, zoom).to_vec(),
+                                        edge_color,
+                                        egui::Stroke::new(1.0, edge_color),
+                                    ));
+                                }
+                            }
                         }
                     }
                 }
@@ -260,24 +533,18 @@ impl SeiriGraph {
                 .world_to_screen(self.node_positions[i].to_pos2(), canvas_rect);
 
             // Only draw visible nodes
-            let betweenness_score = self
-                .graph_analysis
-                .as_ref()
-                .and_then(|analysis| analysis.get_betweenness_centrality(NodeIndex::new(i)));
-
-            let base_radius = self.graph_nodes[i].calculate_size(
-                self.min_loc,
-                self.max_loc,
-                self.min_node_radius,
-                self.max_node_radius,
-                betweenness_score,
-            );
+            let base_radius = self.node_radius(i);
             let node_radius = base_radius * self.camera.zoom_level();
             if !canvas_rect.expand(node_radius).contains(screen_pos) {
                 continue;
             }
 
             let color = self.get_node_color(i);
+            if detail == DetailLevel::Dots {
+                // Borders, labels and badges would only be noise at this size
+                painter.circle_filled(screen_pos, node_radius, color);
+                continue;
+            }
 
             // Node circle with subtle shadow
             painter.circle_filled(
@@ -293,34 +560,51 @@ impl SeiriGraph {
             } else {
                 egui::Color32::from_rgb(60, 60, 60)
             };
-            painter.circle_stroke(
-                screen_pos,
-                node_radius,
-                egui::Stroke::new(2.0 * self.camera.zoom_level().sqrt(), border_color),
+            let style = NodeStyle::for_state(NodeState::of(node, &self.modified_files));
+            let border_stroke = egui::Stroke::new(
+                style.stroke_width(2.0 * self.camera.zoom_level().sqrt()),
+                border_color,
             );
+            match style.dash_array(border_stroke.width) {
+                Some([dash, gap]) => {
+                    let points: Vec<Pos2> = (0..=48)
+                        .map(|step| {
+                            let angle = step as f32 / 48.0 * std::f32::consts::TAU;
+                            screen_pos + node_radius * vec2(angle.cos(), angle.sin())
+                        })
+                        .collect();
+                    painter.extend(egui::Shape::dashed_line(&points, border_stroke, dash, gap));
+                }
+                None => {
+                    painter.circle_stroke(screen_pos, node_radius, border_stroke);
+                }
+            }
 
             // Node label with background for better readability
             if self.show_labels
-                && self.camera.zoom_level() > 0.3
-                && let Some(name) = node.data().file().file_stem().and_then(|s| s.to_str())
+                && detail == DetailLevel::Full
+                && let Some(name) = node.label(self.max_label_len)
             {
                 let font_size = (12.0 * self.camera.zoom_level()).clamp(8.0, 16.0);
 
                 // Measure text to create appropriate background
                 let font_id = egui::FontId::proportional(font_size);
                 let text_galley =
-                    painter.layout_no_wrap(name.to_string(), font_id.clone(), egui::Color32::WHITE);
+                    painter.layout_no_wrap(name.clone(), font_id.clone(), egui::Color32::WHITE);
                 let text_rect = egui::Rect::from_center_size(
                     screen_pos,
                     text_galley.size() + vec2(6.0, 4.0) * self.camera.zoom_level(),
                 );
+                // Labels wider than the node sit on the theme's own background color
+                let label_background = ui.visuals().extreme_bg_color;
+                let has_background = text_galley.size().x > node_radius * 1.5;
 
                 // Only draw background if text is wider than the node
-                if text_galley.size().x > node_radius * 1.5 {
+                if has_background {
                     painter.rect_filled(
                         text_rect,
                         4.0 * self.camera.zoom_level(),
-                        egui::Color32::from_black_alpha(180),
+                        label_background.gamma_multiply(0.85),
                     );
                     painter.rect_stroke(
                         text_rect,
@@ -333,17 +617,12 @@ impl SeiriGraph {
                     );
                 }
 
-                // Draw text - always white for contrast against dark backgrounds
-                let text_color = if text_galley.size().x > node_radius * 1.5 {
-                    egui::Color32::WHITE // White text on dark background
+                // Text that fits in the node contrasts with the node color instead
+                let text_color = contrast_text_color(if has_background {
+                    label_background
                 } else {
-                    // Text fits in node - use contrast-based color
-                    if (color.r() as u16) + (color.g() as u16) + (color.b() as u16) > 400 {
-                        egui::Color32::BLACK
-                    } else {
-                        egui::Color32::WHITE
-                    }
-                };
+                    color
+                });
 
                 painter.text(
                     screen_pos,
@@ -353,6 +632,99 @@ impl SeiriGraph {
                     text_color,
                 );
             }
+
+            // Out-degree badge on the node's upper right
+            if self.show_degree {
+                let zoom = self.camera.zoom_level();
+                let badge_pos =
+                    screen_pos + vec2(1.0, -1.0) * node_radius * std::f32::consts::FRAC_1_SQRT_2;
+                let badge_radius = 8.0 * zoom.sqrt();
+                painter.circle_filled(badge_pos, badge_radius, egui::Color32::WHITE);
+                painter.circle_stroke(
+                    badge_pos,
+                    badge_radius,
+                    egui::Stroke::new(1.0, egui::Color32::from_rgb(60, 60, 60)),
+                );
+                painter.text(
+                    badge_pos,
+                    egui::Align2::CENTER_CENTER,
+                    self.out_degrees[i].to_string(),
+                    egui::FontId::proportional(10.0 * zoom.sqrt()),
+                    egui::Color32::BLACK,
+                );
+            }
+        }
+    }
+
+    /// Draw each directory cluster as a single circle at its members' centroid, sized
+    /// by how many files it holds and colored like its first file
+    fn draw_clusters(&self, painter: &egui::Painter, canvas_rect: &Rect) {
+        let mut clusters: HashMap<usize, (Vec2, usize, usize)> = HashMap::new();
+        for (i, &cluster) in self.cluster_of.iter().enumerate() {
+            let (sum, count, _) = clusters.entry(cluster).or_insert((Vec2::ZERO, 0, i));
+            *sum += self.node_positions[i];
+            *count += 1;
+        }
+
+        let zoom = self.camera.zoom_level();
+        for (sum, count, first) in clusters.into_values() {
+            let center = (sum / count as f32).to_pos2();
+            let screen_pos = self.camera.world_to_screen(center, canvas_rect);
+            let radius = (self.min_node_radius * zoom * (count as f32).sqrt()).max(3.0);
+            if canvas_rect.expand(radius).contains(screen_pos) {
+                painter.circle_filled(screen_pos, radius, self.get_node_color(first));
+            }
+        }
+    }
+
+    /// Draw each directory as a faint box around its files and subdirectories
+    fn draw_directory_tree(&self, painter: &egui::Painter, canvas_rect: &Rect) {
+        let zoom = self.camera.zoom_level();
+        let centers: Vec<(f32, f32)> = self.node_positions.iter().map(|p| (p.x, p.y)).collect();
+        let radii: Vec<f32> = (0..self.graph_nodes.len())
+            .map(|i| self.node_radius(i))
+            .collect();
+        let color = egui::Color32::from_gray(128);
+        for directory in self.directory_tree.boxes(&centers, &radii, TREE_PADDING) {
+            let corner =
+                |(x, y): (f32, f32)| self.camera.world_to_screen(Pos2::new(x, y), canvas_rect);
+            painter.rect(
+                Rect::from_min_max(corner(directory.min), corner(directory.max)),
+                6.0 * zoom,
+                color.gamma_multiply(0.06),
+                egui::Stroke::new(1.0, color.gamma_multiply(0.3)),
+                egui::StrokeKind::Middle,
+            );
+        }
+    }
+
+    /// Draw the convex hull around the nodes of each language, in its color, with every
+    /// node padded by a circle so single nodes and pairs get a rounded region too
+    fn draw_language_hulls(&self, painter: &egui::Painter, canvas_rect: &Rect) {
+        let zoom = self.camera.zoom_level();
+        let mut outlines: HashMap<Language, Vec<Pos2>> = HashMap::new();
+        for (i, node) in self.graph_nodes.iter().enumerate() {
+            let center = self
+                .camera
+                .world_to_screen(self.node_positions[i].to_pos2(), canvas_rect);
+            let radius = (self.node_radius(i) + HULL_PADDING) * zoom;
+            let outline = outlines.entry(*node.data().language()).or_default();
+            outline.extend((0..HULL_SEGMENTS).map(|k| {
+                let angle = k as f32 * std::f32::consts::TAU / HULL_SEGMENTS as f32;
+                center + radius * vec2(angle.cos(), angle.sin())
+            }));
+        }
+
+        // Overlapping regions blend the same way every frame
+        let mut outlines: Vec<_> = outlines.into_iter().collect();
+        outlines.sort_by_key(|(language, _)| language.to_string());
+        for (language, outline) in outlines {
+            let color = egui::Color32::from_hex(language.color()).unwrap_or(egui::Color32::GRAY);
+            painter.add(egui::Shape::convex_polygon(
+                convex_hull(&outline),
+                color.gamma_multiply(0.15),
+                egui::Stroke::new(1.0, color.gamma_multiply(0.6)),
+            ));
         }
     }
 
@@ -361,12 +733,51 @@ impl SeiriGraph {
     ////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////
 
     fn handle_interaction(&mut self, ui: &mut Ui, response: &mut Response, canvas_rect: &Rect) {
+        self.handle_keys(ui, canvas_rect);
         self.handle_hover(response, canvas_rect);
         self.handle_zoom(ui, response, canvas_rect);
         self.handle_pan(response, canvas_rect);
         self.handle_click(response, canvas_rect);
     }
 
+    /// Handle keyboard shortcuts: `F` fits every node into view, `R` resets the zoom,
+    /// `Escape` deselects, and the arrow keys pan. Ignored while a text field has focus.
+    fn handle_keys(&mut self, ui: &mut Ui, canvas_rect: &Rect) {
+        if ui.ctx().wants_keyboard_input() {
+            return;
+        }
+
+        let (fit, reset, deselect, pan) = ui.input(|i| {
+            let direction = |key, delta: Vec2| if i.key_down(key) { delta } else { Vec2::ZERO };
+            let pan = direction(egui::Key::ArrowLeft, vec2(1.0, 0.0))
+                + direction(egui::Key::ArrowRight, vec2(-1.0, 0.0))
+                + direction(egui::Key::ArrowUp, vec2(0.0, 1.0))
+                + direction(egui::Key::ArrowDown, vec2(0.0, -1.0));
+            (
+                i.key_pressed(egui::Key::F),
+                i.key_pressed(egui::Key::R),
+                i.key_pressed(egui::Key::Escape),
+                pan * KEY_PAN_SPEED * i.stable_dt,
+            )
+        });
+
+        if fit {
+            let nodes: Vec<_> = (0..self.graph_nodes.len())
+                .map(|i| (self.node_positions[i].to_pos2(), self.node_radius(i)))
+                .collect();
+            self.camera.fit(nodes, FIT_MARGIN);
+        }
+        if reset {
+            self.camera.reset();
+        }
+        if deselect {
+            self.selected_node = None;
+        }
+        if pan != Vec2::ZERO {
+            self.camera.pan(pan, canvas_rect);
+        }
+    }
+
     /// Handle highlighting of nodes hovered over.
     fn handle_hover(&mut self, response: &mut Response, canvas_rect: &Rect) {
         if !response.hovered() {
@@ -378,19 +789,7 @@ impl SeiriGraph {
             for (i, _) in self.graph_nodes.iter().enumerate() {
                 let dist = (world_mouse - self.node_positions[i]).to_vec2().length();
 
-                // TODO: insanely inefficient, please change this someday
-                let betweenness_score = self
-                    .graph_analysis
-                    .as_ref()
-                    .and_then(|analysis| analysis.get_betweenness_centrality(NodeIndex::new(i)));
-
-                let node_radius = self.graph_nodes[i].calculate_size(
-                    self.min_loc,
-                    self.max_loc,
-                    self.min_node_radius,
-                    self.max_node_radius,
-                    betweenness_score,
-                );
+                let node_radius = self.node_radius(i);
                 if dist < node_radius {
                     self.hovered_node = Some(i);
                     return;
@@ -436,19 +835,7 @@ impl SeiriGraph {
             for (i, _) in self.graph_nodes.iter().enumerate() {
                 let dist = (world_mouse - self.node_positions[i]).to_vec2().length();
 
-                // TODO: insanely inefficient, please change this someday
-                let betweenness_score = self
-                    .graph_analysis
-                    .as_ref()
-                    .and_then(|analysis| analysis.get_betweenness_centrality(NodeIndex::new(i)));
-
-                let node_radius = self.graph_nodes[i].calculate_size(
-                    self.min_loc,
-                    self.max_loc,
-                    self.min_node_radius,
-                    self.max_node_radius,
-                    betweenness_score,
-                );
+                let node_radius = self.node_radius(i);
                 if dist < node_radius {
                     response.dnd_set_drag_payload(i);
                     break;
@@ -483,19 +870,7 @@ impl SeiriGraph {
                     .to_vec2()
                     .length();
 
-                // TODO: insanely inefficient, please change this someday
-                let betweenness_score = self
-                    .graph_analysis
-                    .as_ref()
-                    .and_then(|analysis| analysis.get_betweenness_centrality(NodeIndex::new(i)));
-
-                let node_radius = self.graph_nodes[i].calculate_size(
-                    self.min_loc,
-                    self.max_loc,
-                    self.min_node_radius,
-                    self.max_node_radius,
-                    betweenness_score,
-                );
+                let node_radius = self.node_radius(i);
                 if dist < node_radius {
                     self.selected_node = Some(i);
                     return;
@@ -517,7 +892,7 @@ impl SeiriGraph {
         ui.heading("Graph Analysis");
         ui.add_space(8.0);
 
-        if let Some(analysis) = &self.graph_analysis {
+        if let Some(analysis) = self.analysis_cache.analysis() {
             // SCCs summary
             ui.collapsing("Strongly Connected Components", |ui| {
                 ui.label(format!("Total SCCs: {}", analysis.scc_sizes.len()));
@@ -614,7 +989,15 @@ impl SeiriGraph {
 
             ui.horizontal(|ui| {
                 ui.checkbox(&mut self.show_labels, "Show Labels");
+                ui.checkbox(&mut self.show_degree, "Show Degree");
                 ui.checkbox(&mut self.show_dependencies, "Show Dependencies");
+                ui.checkbox(&mut self.size_by_centrality, "Size by centrality");
+                ui.checkbox(&mut self.group_by_language, "Group by language");
+                ui.checkbox(&mut self.show_tree, "Directory tree");
+                if ui.checkbox(&mut self.no_overlap, "No Overlap").changed() {
+                    self.initialize_positions();
+                }
+                ui.checkbox(&mut self.show_table, "Table");
             });
 
             ui.separator();
@@ -623,6 +1006,9 @@ impl SeiriGraph {
                 .selected_text(match self.layout_type {
                     LayoutType::Circular => "Circular",
                     LayoutType::Sugiyama => "Sugiyama",
+                    LayoutType::ForceDirected => "Force-directed",
+                    LayoutType::Grid => "Grid",
+                    LayoutType::Radial => "Radial",
                 })
                 .show_ui(ui, |ui| {
                     let mut changed = false;
@@ -632,6 +1018,20 @@ impl SeiriGraph {
                     changed |= ui
                         .selectable_value(&mut self.layout_type, LayoutType::Sugiyama, "Sugiyama")
                         .clicked();
+                    changed |= ui
+                        .selectable_value(
+                            &mut self.layout_type,
+                            LayoutType::ForceDirected,
+                            "Force-directed",
+                        )
+                        .clicked();
+                    changed |= ui
+                        .selectable_value(&mut self.layout_type, LayoutType::Grid, "Grid")
+                        .clicked();
+                    // Picking Radial again re-roots it at the currently selected node
+                    changed |= ui
+                        .selectable_value(&mut self.layout_type, LayoutType::Radial, "Radial")
+                        .clicked();
                     if changed {
                         self.initialize_positions();
                     }
@@ -639,6 +1039,69 @@ impl SeiriGraph {
 
             ui.separator();
 
+            egui::ComboBox::from_label("Metrics")
+                .selected_text(match self.metric_mode {
+                    MetricMode::Directed => "Directed",
+                    MetricMode::Undirected => "Undirected",
+                })
+                .show_ui(ui, |ui| {
+                    let mut changed = false;
+                    changed |= ui
+                        .selectable_value(&mut self.metric_mode, MetricMode::Directed, "Directed")
+                        .clicked();
+                    changed |= ui
+                        .selectable_value(
+                            &mut self.metric_mode,
+                            MetricMode::Undirected,
+                            "Undirected",
+                        )
+                        .clicked();
+                    if changed {
+                        self.refresh_analysis();
+                    }
+                });
+
+            ui.separator();
+
+            egui::ComboBox::from_label("Theme")
+                .selected_text(match self.theme {
+                    egui::ThemePreference::System => "System",
+                    egui::ThemePreference::Dark => "Dark",
+                    egui::ThemePreference::Light => "Light",
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.theme, egui::ThemePreference::System, "System");
+                    ui.selectable_value(&mut self.theme, egui::ThemePreference::Dark, "Dark");
+                    ui.selectable_value(&mut self.theme, egui::ThemePreference::Light, "Light");
+                });
+
+            ui.separator();
+
+            egui::ComboBox::from_label("Color")
+                .selected_text(match self.color_by {
+                    ColorBy::Language => "Language",
+                    ColorBy::Community => "Community",
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.color_by, ColorBy::Language, "Language");
+                    ui.selectable_value(&mut self.color_by, ColorBy::Community, "Community");
+                });
+
+            ui.separator();
+
+            ui.add(egui::TextEdit::singleline(&mut self.export_path).desired_width(120.0));
+            if ui.button("Export").clicked() {
+                self.export_status = Some(match self.export_view() {
+                    Ok(()) => format!("Exported to {}", self.export_path.trim()),
+                    Err(e) => format!("Export failed: {e}"),
+                });
+            }
+            if let Some(status) = &self.export_status {
+                ui.label(status);
+            }
+
+            ui.separator();
+
             ui.label(format!("Nodes: {}", self.graph_nodes.len()));
 
             ui.separator();
@@ -656,14 +1119,31 @@ impl SeiriGraph {
             ui.label(format!("📁 {}", node.file().display()));
             ui.label(format!("🔧 {}", node.language().to_string()));
             ui.label(format!("📊 {} lines", node.loc()));
+            if node.is_generated() {
+                ui.label("⚙ Generated file");
+            }
+            if let Some(locality) = node.import_locality() {
+                let local = node.imports().iter().filter(|i| i.is_local()).count();
+                ui.label(format!(
+                    "🏠 Import locality: {locality:.2} ({local}/{} local)",
+                    node.imports().len()
+                ));
+            }
 
             // Add betweenness centrality score if available
-            if let Some(analysis) = &self.graph_analysis
+            if let Some(analysis) = self.analysis_cache.analysis()
                 && let Some(score) =
                     analysis.get_betweenness_centrality(NodeIndex::new(selected_idx))
             {
                 ui.label(format!("🔄 Betweenness: {:.3}", score));
             }
+            if let Some(community) = self
+                .analysis_cache
+                .analysis()
+                .and_then(|analysis| analysis.get_community(NodeIndex::new(selected_idx)))
+            {
+                ui.label(format!("🧩 Community: {community}"));
+            }
         });
 
         ui.separator();
@@ -679,6 +1159,15 @@ impl SeiriGraph {
                 .collect();
             let outgoing = self.graph_nodes[selected_idx].edges();
 
+            if let Some(analysis) = self.analysis_cache.analysis()
+                && let (Some(in_degree), Some(out_degree)) = (
+                    analysis.get_in_degree(NodeIndex::new(selected_idx)),
+                    analysis.get_out_degree(NodeIndex::new(selected_idx)),
+                )
+            {
+                ui.label(format!("In-degree: {in_degree} | Out-degree: {out_degree}"));
+            }
+
             ui.collapsing(format!("📥 Incoming ({})", incoming.len()), |ui| {
                 for (idx, dep_node) in incoming {
                     let name = dep_node
@@ -741,28 +1230,53 @@ impl SeiriGraph {
             }
         });
 
-        // Imports
+        // Imports, filtered and listed a page at a time so barrels with hundreds of
+        // them stay usable
         if !node.imports().is_empty() {
+            if self.imports_node != Some(selected_idx) {
+                self.imports_node = Some(selected_idx);
+                self.import_filter.clear();
+                self.imports_shown = IMPORTS_PAGE_SIZE;
+            }
+
             ui.separator();
             ui.group(|ui| {
                 ui.strong("Imports");
+                ui.horizontal(|ui| {
+                    ui.label("🔍");
+                    let filter = egui::TextEdit::singleline(&mut self.import_filter)
+                        .hint_text("Filter imports");
+                    if ui.add(filter).changed() {
+                        self.imports_shown = IMPORTS_PAGE_SIZE;
+                    }
+                });
+
                 let (local, external): (Vec<_>, Vec<_>) =
                     node.imports().iter().partition(|imp| imp.is_local());
-
-                if !local.is_empty() {
-                    ui.collapsing(format!("🏠 Local ({})", local.len()), |ui| {
-                        for imp in local {
-                            ui.monospace(imp.path());
-                        }
-                    });
-                }
-
-                if !external.is_empty() {
-                    ui.collapsing(format!("🌐 External ({})", external.len()), |ui| {
-                        for imp in external {
-                            ui.monospace(imp.path());
-                        }
-                    });
+                let shown = self.imports_shown;
+                for (heading, paths) in [
+                    ("🏠 Local", filter_imports(local, &self.import_filter)),
+                    ("🌐 External", filter_imports(external, &self.import_filter)),
+                ] {
+                    if paths.is_empty() {
+                        continue;
+                    }
+                    // A fixed id keeps the section open while the filter changes its count
+                    egui::CollapsingHeader::new(format!("{heading} ({})", paths.len()))
+                        .id_salt(heading)
+                        .show(ui, |ui| {
+                            for path in paths.iter().take(shown) {
+                                ui.monospace(*path);
+                            }
+                            let hidden = paths.len().saturating_sub(shown);
+                            if hidden > 0
+                                && ui
+                                    .button(format!("Show {} more", hidden.min(IMPORTS_PAGE_SIZE)))
+                                    .clicked()
+                            {
+                                self.imports_shown += IMPORTS_PAGE_SIZE;
+                            }
+                        });
                 }
             });
         }
@@ -799,19 +1313,177 @@ impl SeiriGraph {
                     ui.label("• Drag nodes to reposition");
                     ui.label("• Scroll to zoom");
                     ui.label("• Drag empty space to pan");
+                    ui.label("• F to fit, R to reset zoom, arrows to pan");
                 });
             });
         }
     }
 }
 
+/// Black or white, whichever reads better on `background`, judged by its perceived
+/// brightness
+fn contrast_text_color(background: egui::Color32) -> egui::Color32 {
+    let [r, g, b, _] = background.to_srgba_unmultiplied();
+    let brightness = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+    if brightness > 150.0 {
+        egui::Color32::BLACK
+    } else {
+        egui::Color32::WHITE
+    }
+}
+
+/// Build the one-line summary shown in the metrics header bar
+fn metrics_header_text(graph_nodes: &[GraphNode], analysis: &GraphAnalysis) -> String {
+    let most_central = analysis
+        .betweenness_centrality
+        .iter()
+        .filter(|&(_, &score)| score > 0.0)
+        .max_by(|a, b| a.1.total_cmp(b.1).then(b.0.cmp(a.0)))
+        .and_then(|(idx, _)| graph_nodes.get(idx.index()))
+        .and_then(|node| node.data().file().file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "-".to_string());
+
+    format!(
+        "Edges: {} | SCCs: {} | Largest SCC: {} | Most central: {}",
+        build_edge_list(graph_nodes).len(),
+        analysis.scc_sizes.len(),
+        analysis.largest_scc_size,
+        most_central
+    )
+}
+
+/// Size the initial layout is scaled to, in world units
+const LAYOUT_SIZE: f32 = 800.0;
+/// World units between a node's border and the language region drawn around it
+const HULL_PADDING: f32 = 12.0;
+/// Points on the circle around each node that language regions are wrapped around
+const HULL_SEGMENTS: usize = 12;
+
+/// How many imports of each kind the details panel lists before a "Show more" button
+const IMPORTS_PAGE_SIZE: usize = 50;
+/// World units left around the graph when fitting it into view
+const FIT_MARGIN: f32 = 40.0;
+/// Screen pixels per second the view pans while an arrow key is held
+const KEY_PAN_SPEED: f32 = 600.0;
+
+/// Sorted paths of the `imports` that contain `filter`, ignoring case
+fn filter_imports<'a>(imports: impl IntoIterator<Item = &'a Import>, filter: &str) -> Vec<&'a str> {
+    let filter = filter.trim().to_lowercase();
+    let mut paths: Vec<&str> = imports
+        .into_iter()
+        .map(Import::path)
+        .filter(|path| path.to_lowercase().contains(&filter))
+        .collect();
+    paths.sort_unstable();
+    paths
+}
+
+/// How much of each node `draw_graph` renders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DetailLevel {
+    /// Bordered nodes with labels and badges
+    Full,
+    /// Plain dots, without labels
+    Dots,
+    /// One glyph per directory cluster instead of individual nodes
+    Clusters,
+}
+
+/// Pick the level of detail for `node_count` nodes at `zoom`, from the typical
+/// on-screen distance between neighbouring nodes in a layout of `LAYOUT_SIZE`
+fn detail_level(zoom: f32, node_count: usize) -> DetailLevel {
+    let spacing = zoom * LAYOUT_SIZE / (node_count.max(1) as f32).sqrt();
+    if spacing < 6.0 {
+        DetailLevel::Clusters
+    } else if zoom <= 0.3 || spacing < 24.0 {
+        DetailLevel::Dots
+    } else {
+        DetailLevel::Full
+    }
+}
+
+/// Convex hull of `points` by Andrew's monotone chain, starting from the leftmost point
+/// and clockwise on screen, without repeating the first point at the end. Duplicates and
+/// points on the hull's edges are dropped, so one distinct point gives one point back
+/// and points on a line give the two ends of it.
+fn convex_hull(points: &[Pos2]) -> Vec<Pos2> {
+    let mut points = points.to_vec();
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    // Positive when `o -> a -> b` turns clockwise on screen, where y points down
+    let cross = |o: Pos2, a: Pos2, b: Pos2| (a - o).x * (b - o).y - (a - o).y * (b - o).x;
+    let mut hull: Vec<Pos2> = Vec::with_capacity(points.len() * 2);
+    // One half left to right, then the other right to left
+    for pass in [points.clone(), points.into_iter().rev().collect()] {
+        let start = hull.len();
+        for point in pass {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // The last point of each half starts the other one
+        hull.pop();
+    }
+    hull
+}
+
+/// Calculate the three corners of an arrowhead pointing at `to`, pulled back from the node center
+fn arrowhead_points(from: Pos2, to: Pos2, zoom: f32) -> [Pos2; 3] {
+    let dir = (to - from).normalized();
+    let arrow_size = 10.0 * zoom.sqrt();
+    let arrow_angle: f32 = 0.5; // ~30 degrees in radians
+
+    // Calculate arrowhead points
+    let arrow_end = to - dir * (20.0 * zoom.sqrt()); // Pull back from the end
+    let left = arrow_end
+        + arrow_size
+            * vec2(
+                -dir.x * arrow_angle.cos() + dir.y * arrow_angle.sin(),
+                -dir.x * arrow_angle.sin() - dir.y * arrow_angle.cos(),
+            );
+    let right = arrow_end
+        + arrow_size
+            * vec2(
+                -dir.x * arrow_angle.cos() - dir.y * arrow_angle.sin(),
+                dir.x * arrow_angle.sin() - dir.y * arrow_angle.cos(),
+            );
+
+    [to, left, right]
+}
+
 impl eframe::App for SeiriGraph {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Pick up the latest rebuilt graph from watch mode, if any
+        if let Some((graph_nodes, modified_files)) =
+            self.updates.as_ref().and_then(|rx| rx.try_iter().last())
+        {
+            self.modified_files = modified_files;
+            self.replace_graph(graph_nodes);
+        }
+
+        // Switches between egui's dark and light visuals
+        if ctx.options(|options| options.theme_preference) != self.theme {
+            ctx.set_theme(self.theme);
+        }
+
         // Controls panel
         egui::TopBottomPanel::top("controls").show(ctx, |ui| {
             self.render_controls_panel(ui);
         });
 
+        // Metrics header bar
+        egui::TopBottomPanel::top("metrics").show(ctx, |ui| {
+            ui.small(&self.metrics_header);
+        });
+
         // Details panel for selected node
         if let Some(selected_idx) = self.selected_node {
             egui::SidePanel::right("details")
@@ -830,17 +1502,78 @@ impl eframe::App for SeiriGraph {
                 });
         }
 
-        // Main graph view
+        // Main graph view, or the node table in its place
         egui::CentralPanel::default().show(ctx, |ui| {
-            self.render_viewport(ui);
+            if self.show_table {
+                if let Some(idx) = self.node_table.show(ui, self.selected_node) {
+                    self.selected_node = Some(idx);
+                }
+            } else {
+                self.render_viewport(ui);
+            }
         });
 
         ctx.request_repaint();
     }
 }
 
-pub fn run_gui(graph_nodes: Vec<GraphNode>) {
-    let app = SeiriGraph::new(graph_nodes);
+/// Options controlling how the GUI renders the graph
+#[derive(Debug, Clone, Default)]
+pub struct GuiOptions {
+    /// Truncate node labels to this many characters
+    pub max_label_len: Option<usize>,
+    /// Start with out-degree badges shown on each node
+    pub show_degree: bool,
+    /// Files with uncommitted changes, drawn with a dashed border
+    pub modified_files: HashSet<PathBuf>,
+    /// Layout shown when the GUI opens
+    pub layout: LayoutType,
+    /// Draw edges touching a node with more edges than this without arrowheads
+    pub max_arrow_degree: Option<usize>,
+    /// Draw dependencies that only test code has in a color of their own
+    pub highlight_test_edges: bool,
+    /// What node fill colors stand for
+    pub color_by: ColorBy,
+    /// Push overlapping nodes apart after every layout
+    pub no_overlap: bool,
+    /// Draw the directory tree as nested boxes behind the graph
+    pub show_tree: bool,
+}
+
+pub fn run_gui(graph_nodes: Vec<GraphNode>, options: &GuiOptions) {
+    let mut app = SeiriGraph::new(graph_nodes, options.layout);
+    app.max_label_len = options.max_label_len;
+    app.show_degree = options.show_degree;
+    app.modified_files = options.modified_files.clone();
+    app.max_arrow_degree = options.max_arrow_degree;
+    app.highlight_test_edges = options.highlight_test_edges;
+    app.color_by = options.color_by;
+    app.show_tree = options.show_tree;
+    app.apply_no_overlap(options.no_overlap);
+    launch(app);
+}
+
+/// Run the GUI, replacing the displayed graph, and the set of modified files, whenever a
+/// new one arrives on `updates`
+pub fn run_gui_with_updates(
+    graph_nodes: Vec<GraphNode>,
+    options: &GuiOptions,
+    updates: Receiver<(Vec<GraphNode>, HashSet<PathBuf>)>,
+) {
+    let mut app = SeiriGraph::new(graph_nodes, options.layout);
+    app.max_label_len = options.max_label_len;
+    app.show_degree = options.show_degree;
+    app.modified_files = options.modified_files.clone();
+    app.max_arrow_degree = options.max_arrow_degree;
+    app.highlight_test_edges = options.highlight_test_edges;
+    app.color_by = options.color_by;
+    app.show_tree = options.show_tree;
+    app.apply_no_overlap(options.no_overlap);
+    app.updates = Some(updates);
+    launch(app);
+}
+
+fn launch(app: SeiriGraph) {
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1200.0, 800.0])
@@ -855,3 +1588,146 @@ pub fn run_gui(graph_nodes: Vec<GraphNode>) {
         Box::new(|_cc| Ok(Box::new(app))),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::defs::Language;
+    use egui::pos2;
+    use petgraph::visit::EdgeRef;
+
+    #[test]
+    fn test_graph_edges_match_dependencies() {
+        // Indexing targets by their position in the edge list would give c -> a and b -> b
+        let app = SeiriGraph::new(
+            vec![
+                GraphNode::for_test("a.rs", Language::Rust, 10, &[]),
+                GraphNode::for_test("b.rs", Language::Rust, 10, &["a.rs", "c.rs"]),
+                GraphNode::for_test("c.rs", Language::Rust, 10, &["b.rs"]),
+            ],
+            LayoutType::default(),
+        );
+
+        let edges: HashSet<(usize, usize)> = app
+            .graph
+            .edge_references()
+            .map(|e| (e.source().index(), e.target().index()))
+            .collect();
+        assert_eq!(edges, HashSet::from([(1, 0), (1, 2), (2, 1)]));
+
+        let analysis = app.analysis_cache.analysis().unwrap();
+        assert_eq!(analysis.largest_scc_size, 2);
+        assert!(analysis.is_in_largest_scc(NodeIndex::new(1)));
+        assert!(analysis.is_in_largest_scc(NodeIndex::new(2)));
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let points = [
+            pos2(0.0, 0.0),
+            pos2(4.0, 0.0),
+            pos2(4.0, 4.0),
+            pos2(0.0, 4.0),
+            // Inside, on an edge, and a duplicate corner
+            pos2(2.0, 2.0),
+            pos2(2.0, 0.0),
+            pos2(4.0, 4.0),
+        ];
+        assert_eq!(
+            convex_hull(&points),
+            vec![
+                pos2(0.0, 0.0),
+                pos2(4.0, 0.0),
+                pos2(4.0, 4.0),
+                pos2(0.0, 4.0)
+            ]
+        );
+
+        assert_eq!(convex_hull(&[pos2(1.0, 1.0); 3]), vec![pos2(1.0, 1.0)]);
+        let line = [pos2(0.0, 0.0), pos2(2.0, 2.0), pos2(1.0, 1.0)];
+        assert_eq!(convex_hull(&line), vec![pos2(0.0, 0.0), pos2(2.0, 2.0)]);
+        assert!(convex_hull(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_contrast_text_color() {
+        let black = egui::Color32::BLACK;
+        let white = egui::Color32::WHITE;
+        assert_eq!(contrast_text_color(white), black);
+        assert_eq!(contrast_text_color(black), white);
+        // The default dark and light theme backgrounds behind wide labels
+        assert_eq!(
+            contrast_text_color(egui::Visuals::dark().extreme_bg_color),
+            white
+        );
+        assert_eq!(
+            contrast_text_color(egui::Visuals::light().extreme_bg_color),
+            black
+        );
+        // Yellow is bright despite a low blue channel, navy is dark
+        assert_eq!(
+            contrast_text_color(egui::Color32::from_rgb(240, 220, 60)),
+            black
+        );
+        assert_eq!(
+            contrast_text_color(egui::Color32::from_rgb(20, 30, 120)),
+            white
+        );
+    }
+
+    #[test]
+    fn test_filter_imports() {
+        let imports = [
+            Import::new("crate::core::defs".to_string(), true),
+            Import::new("crate::export::Svg".to_string(), true),
+            Import::new("std::collections::HashMap".to_string(), false),
+        ];
+
+        assert_eq!(filter_imports(&imports, "svg"), vec!["crate::export::Svg"]);
+        assert_eq!(
+            filter_imports(&imports, " crate:: "),
+            vec!["crate::core::defs", "crate::export::Svg"]
+        );
+        assert_eq!(filter_imports(&imports, "").len(), 3);
+        assert!(filter_imports(&imports, "tokio").is_empty());
+    }
+
+    #[test]
+    fn test_detail_level() {
+        // Small graphs keep their labels until zoomed far out
+        assert_eq!(detail_level(1.0, 20), DetailLevel::Full);
+        assert_eq!(detail_level(0.3, 20), DetailLevel::Dots);
+
+        // Large graphs drop labels, then individual nodes, sooner
+        assert_eq!(detail_level(1.0, 2000), DetailLevel::Dots);
+        assert_eq!(detail_level(0.5, 2000), DetailLevel::Dots);
+        assert_eq!(detail_level(0.1, 2000), DetailLevel::Clusters);
+
+        // Zooming back in restores the detail
+        assert_eq!(detail_level(2.0, 2000), DetailLevel::Full);
+        assert_eq!(detail_level(8.0, 20_000), DetailLevel::Full);
+    }
+
+    #[test]
+    fn test_metrics_header_text() {
+        // a -> b -> c -> b: b and c form a cycle, and b sits on the most paths
+        let graph_nodes = vec![
+            GraphNode::for_test("a.rs", Language::Rust, 10, &["b.rs"]),
+            GraphNode::for_test("b.rs", Language::Rust, 10, &["c.rs"]),
+            GraphNode::for_test("c.rs", Language::Rust, 10, &["b.rs"]),
+        ];
+        let analysis = GraphAnalysis::analyze_graph(&build_dependency_graph(&graph_nodes));
+
+        assert_eq!(
+            metrics_header_text(&graph_nodes, &analysis),
+            "Edges: 3 | SCCs: 2 | Largest SCC: 2 | Most central: b.rs"
+        );
+
+        let isolated = vec![GraphNode::for_test("a.rs", Language::Rust, 10, &[])];
+        let analysis = GraphAnalysis::analyze_graph(&build_dependency_graph(&isolated));
+        assert_eq!(
+            metrics_header_text(&isolated, &analysis),
+            "Edges: 0 | SCCs: 1 | Largest SCC: 1 | Most central: -"
+        );
+    }
+}
@@ -1,7 +1,35 @@
-use egui::{Pos2, Rect, Vec2, emath::RectTransform, pos2, vec2};
+use egui::{Pos2, Rect, Vec2, emath::RectTransform, vec2};
 
 const MIN_ZOOM_LEVEL: f32 = 0.1;
 const MAX_ZOOM_LEVEL: f32 = 1000.0;
+/// Side length of the viewport at zoom level 1.0
+const DEFAULT_VIEWPORT_SIZE: f32 = 1000.0;
+
+/// Viewport framing every node, given as its position and radius, with `margin` world
+/// units to spare on each side, and the zoom level it corresponds to. A single node is
+/// centered at zoom level 1.0 rather than blown up to fill the view.
+pub fn fit_viewport(
+    nodes: impl IntoIterator<Item = (Pos2, f32)>,
+    margin: f32,
+) -> Option<(Rect, f32)> {
+    let mut nodes = nodes.into_iter().peekable();
+    let (first, _) = *nodes.peek()?;
+    let mut bounds = Rect::NOTHING;
+    let mut count = 0;
+    for (pos, radius) in nodes {
+        bounds = bounds.union(Rect::from_center_size(pos, Vec2::splat(radius * 2.0)));
+        count += 1;
+    }
+    if count == 1 {
+        let size = Vec2::splat(DEFAULT_VIEWPORT_SIZE);
+        return Some((Rect::from_center_size(first, size), 1.0));
+    }
+
+    let viewport = bounds.expand(margin);
+    let zoom_level = (DEFAULT_VIEWPORT_SIZE / viewport.width().max(viewport.height()))
+        .clamp(MIN_ZOOM_LEVEL, MAX_ZOOM_LEVEL);
+    Some((viewport, zoom_level))
+}
 
 pub struct Camera {
     viewport: Rect,
@@ -12,7 +40,7 @@ pub struct Camera {
 impl Default for Camera {
     fn default() -> Self {
         Self {
-            viewport: Rect::from_min_max(pos2(0.0, 0.0), pos2(1000.0, 1000.0)),
+            viewport: Rect::from_min_size(Pos2::ZERO, Vec2::splat(DEFAULT_VIEWPORT_SIZE)),
             zoom_level: 1.0,
         }
     }
@@ -86,7 +114,45 @@ impl Camera {
 
     #[inline]
     pub fn reset(&mut self) {
-        self.viewport = Rect::from_min_max(pos2(0.0, 0.0), pos2(1000.0, 1000.0));
-        self.zoom_level = 1.0;
+        *self = Self::default();
+    }
+
+    /// Show the nodes framed by [`fit_viewport`], or reset the view if there are none
+    pub fn fit(&mut self, nodes: impl IntoIterator<Item = (Pos2, f32)>, margin: f32) {
+        match fit_viewport(nodes, margin) {
+            Some((viewport, zoom_level)) => {
+                self.viewport = viewport;
+                self.zoom_level = zoom_level;
+            }
+            None => self.reset(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::pos2;
+
+    #[test]
+    fn test_fit_viewport() {
+        assert!(fit_viewport([], 10.0).is_none());
+
+        let (viewport, zoom_level) = fit_viewport([(pos2(20.0, 30.0), 5.0)], 10.0).unwrap();
+        assert_eq!(viewport.center(), pos2(20.0, 30.0));
+        assert_eq!(viewport.width(), DEFAULT_VIEWPORT_SIZE);
+        assert_eq!(zoom_level, 1.0);
+
+        let nodes = [(pos2(100.0, 100.0), 10.0), (pos2(300.0, 200.0), 20.0)];
+        let (viewport, zoom_level) = fit_viewport(nodes, 30.0).unwrap();
+        assert_eq!(
+            viewport,
+            Rect::from_min_max(pos2(60.0, 60.0), pos2(350.0, 250.0))
+        );
+        assert_eq!(zoom_level, DEFAULT_VIEWPORT_SIZE / 290.0);
+
+        // Nodes far apart never zoom out past the minimum
+        let nodes = [(pos2(0.0, 0.0), 1.0), (pos2(1.0e6, 0.0), 1.0)];
+        assert_eq!(fit_viewport(nodes, 0.0).unwrap().1, MIN_ZOOM_LEVEL);
     }
 }
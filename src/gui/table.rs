@@ -0,0 +1,278 @@
+use crate::analysis::{GraphAnalysis, build_edge_list};
+use crate::core::defs::{GraphNode, Language};
+use egui::{Sense, Ui};
+use egui_extras::{Column, TableBuilder};
+use petgraph::graph::NodeIndex;
+use std::cmp::Ordering;
+
+/// A sortable column of the node table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableColumn {
+    File,
+    Language,
+    Loc,
+    FanIn,
+    FanOut,
+    Betweenness,
+    Scc,
+}
+
+impl TableColumn {
+    pub const ALL: [TableColumn; 7] = [
+        TableColumn::File,
+        TableColumn::Language,
+        TableColumn::Loc,
+        TableColumn::FanIn,
+        TableColumn::FanOut,
+        TableColumn::Betweenness,
+        TableColumn::Scc,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TableColumn::File => "File",
+            TableColumn::Language => "Language",
+            TableColumn::Loc => "LOC",
+            TableColumn::FanIn => "Fan-in",
+            TableColumn::FanOut => "Fan-out",
+            TableColumn::Betweenness => "Betweenness",
+            TableColumn::Scc => "SCC size",
+        }
+    }
+}
+
+/// One row of the node table, precomputed so sorting doesn't touch the graph
+#[derive(Debug, Clone)]
+pub struct TableRow {
+    /// Index of the node in `graph_nodes`
+    pub index: usize,
+    pub file: String,
+    pub language: Language,
+    pub loc: u32,
+    pub fan_in: usize,
+    pub fan_out: usize,
+    pub betweenness: f64,
+    pub scc_size: usize,
+}
+
+/// Build one row per node, using the analysis for the betweenness and SCC columns if available
+pub fn build_rows(graph_nodes: &[GraphNode], analysis: Option<&GraphAnalysis>) -> Vec<TableRow> {
+    let mut fan_in = vec![0; graph_nodes.len()];
+    let mut fan_out = vec![0; graph_nodes.len()];
+    for (from, to) in build_edge_list(graph_nodes) {
+        fan_out[from] += 1;
+        fan_in[to] += 1;
+    }
+
+    graph_nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| TableRow {
+            index: i,
+            file: node
+                .data()
+                .file()
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            language: *node.data().language(),
+            loc: node.data().loc(),
+            fan_in: fan_in[i],
+            fan_out: fan_out[i],
+            betweenness: analysis
+                .and_then(|a| a.get_betweenness_centrality(NodeIndex::new(i)))
+                .unwrap_or(0.0),
+            scc_size: analysis
+                .and_then(|a| a.get_scc_size(NodeIndex::new(i)))
+                .unwrap_or(1),
+        })
+        .collect()
+}
+
+/// Compare two rows by a single column, in ascending order
+pub fn compare_rows(a: &TableRow, b: &TableRow, column: TableColumn) -> Ordering {
+    match column {
+        TableColumn::File => a.file.cmp(&b.file),
+        TableColumn::Language => a.language.to_string().cmp(b.language.to_string()),
+        TableColumn::Loc => a.loc.cmp(&b.loc),
+        TableColumn::FanIn => a.fan_in.cmp(&b.fan_in),
+        TableColumn::FanOut => a.fan_out.cmp(&b.fan_out),
+        TableColumn::Betweenness => a.betweenness.total_cmp(&b.betweenness),
+        TableColumn::Scc => a.scc_size.cmp(&b.scc_size),
+    }
+}
+
+/// Table of all nodes, sorted by a clickable column header
+pub struct NodeTable {
+    rows: Vec<TableRow>,
+    sort_column: TableColumn,
+    ascending: bool,
+}
+
+impl Default for NodeTable {
+    fn default() -> Self {
+        Self {
+            rows: Vec::new(),
+            sort_column: TableColumn::File,
+            ascending: true,
+        }
+    }
+}
+
+impl NodeTable {
+    /// Replace the rows, keeping the current sort order
+    pub fn set_rows(&mut self, rows: Vec<TableRow>) {
+        self.rows = rows;
+        self.sort();
+    }
+
+    /// Sort by `column`, flipping the direction if it is already the sort column
+    pub fn sort_by(&mut self, column: TableColumn) {
+        if self.sort_column == column {
+            self.ascending = !self.ascending;
+        } else {
+            self.sort_column = column;
+            self.ascending = true;
+        }
+        self.sort();
+    }
+
+    fn sort(&mut self) {
+        let (column, ascending) = (self.sort_column, self.ascending);
+        self.rows.sort_by(|a, b| {
+            let ordering = compare_rows(a, b, column);
+            if ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+    }
+
+    /// Render the table, returning the node index of a clicked row
+    pub fn show(&mut self, ui: &mut Ui, selected: Option<usize>) -> Option<usize> {
+        let mut clicked_column = None;
+        let mut clicked_row = None;
+
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .sense(Sense::click())
+            .column(Column::remainder().at_least(150.0).clip(true))
+            .columns(Column::auto().at_least(60.0), TableColumn::ALL.len() - 1)
+            .header(22.0, |mut header| {
+                for column in TableColumn::ALL {
+                    header.col(|ui| {
+                        let arrow = match (column == self.sort_column, self.ascending) {
+                            (false, _) => "",
+                            (true, true) => " ⏶",
+                            (true, false) => " ⏷",
+                        };
+                        if ui.button(format!("{}{arrow}", column.label())).clicked() {
+                            clicked_column = Some(column);
+                        }
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(20.0, self.rows.len(), |mut table_row| {
+                    let row = &self.rows[table_row.index()];
+                    table_row.set_selected(Some(row.index) == selected);
+
+                    table_row.col(|ui| {
+                        ui.label(&row.file);
+                    });
+                    table_row.col(|ui| {
+                        ui.label(row.language.to_string());
+                    });
+                    table_row.col(|ui| {
+                        ui.label(row.loc.to_string());
+                    });
+                    table_row.col(|ui| {
+                        ui.label(row.fan_in.to_string());
+                    });
+                    table_row.col(|ui| {
+                        ui.label(row.fan_out.to_string());
+                    });
+                    table_row.col(|ui| {
+                        ui.label(format!("{:.3}", row.betweenness));
+                    });
+                    table_row.col(|ui| {
+                        ui.label(row.scc_size.to_string());
+                    });
+
+                    if table_row.response().clicked() {
+                        clicked_row = Some(row.index);
+                    }
+                });
+            });
+
+        if let Some(column) = clicked_column {
+            self.sort_by(column);
+        }
+        clicked_row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(index: usize, file: &str, language: Language, value: usize) -> TableRow {
+        TableRow {
+            index,
+            file: file.to_string(),
+            language,
+            loc: value as u32,
+            fan_in: value,
+            fan_out: value,
+            betweenness: value as f64 / 10.0,
+            scc_size: value,
+        }
+    }
+
+    #[test]
+    fn test_compare_rows_by_each_column() {
+        let a = row(0, "a.rs", Language::Rust, 1);
+        let b = row(1, "b.py", Language::Python, 2);
+
+        assert_eq!(compare_rows(&a, &b, TableColumn::File), Ordering::Less);
+        assert_eq!(
+            compare_rows(&a, &b, TableColumn::Language),
+            Ordering::Greater
+        );
+        for column in [
+            TableColumn::Loc,
+            TableColumn::FanIn,
+            TableColumn::FanOut,
+            TableColumn::Betweenness,
+            TableColumn::Scc,
+        ] {
+            assert_eq!(compare_rows(&a, &b, column), Ordering::Less, "{column:?}");
+            assert_eq!(
+                compare_rows(&b, &a, column),
+                Ordering::Greater,
+                "{column:?}"
+            );
+            assert_eq!(compare_rows(&a, &a, column), Ordering::Equal, "{column:?}");
+        }
+    }
+
+    #[test]
+    fn test_sort_toggles_direction() {
+        let mut table = NodeTable::default();
+        table.set_rows(vec![
+            row(0, "b.rs", Language::Rust, 3),
+            row(1, "c.rs", Language::Rust, 1),
+            row(2, "a.rs", Language::Rust, 2),
+        ]);
+        let order = |table: &NodeTable| table.rows.iter().map(|r| r.index).collect::<Vec<_>>();
+        assert_eq!(order(&table), vec![2, 0, 1]);
+
+        table.sort_by(TableColumn::Loc);
+        assert_eq!(order(&table), vec![1, 2, 0]);
+        table.sort_by(TableColumn::Loc);
+        assert_eq!(order(&table), vec![0, 2, 1]);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::core::defs::GraphNode;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Layer config file, e.g.
+/// `{ "layers": { "ui": ["src/ui"], "core": ["src/core"] }, "allow": ["ui -> core"] }`
+#[derive(Debug, Deserialize)]
+struct LayerConfig {
+    /// Layer name to the path prefixes, relative to the project root, of the files in it
+    layers: HashMap<String, Vec<String>>,
+    /// Allowed dependency directions between layers, written as `from -> to`
+    #[serde(default)]
+    allow: Vec<String>,
+}
+
+/// Declared architecture layers and the dependency directions allowed between them.
+/// Dependencies within a layer, or involving files outside every layer, are always allowed.
+#[derive(Debug)]
+pub struct LayerRules {
+    /// Path prefixes and their layer, longest prefix first
+    prefixes: Vec<(PathBuf, String)>,
+    /// Allowed `(from, to)` layer pairs
+    allowed: HashSet<(String, String)>,
+}
+
+/// A dependency that crosses layers in a direction the rules don't allow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerViolation {
+    pub from: PathBuf,
+    pub from_layer: String,
+    pub to: PathBuf,
+    pub to_layer: String,
+}
+
+impl LayerRules {
+    /// Load layer rules from a JSON config file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read layer config {}: {e}", path.display()))?;
+        Self::from_json(&text).map_err(|e| format!("Invalid layer config {}: {e}", path.display()))
+    }
+
+    /// Parse layer rules from the JSON config format
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let config: LayerConfig = serde_json::from_str(text).map_err(|e| e.to_string())?;
+
+        let mut prefixes: Vec<(PathBuf, String)> = config
+            .layers
+            .iter()
+            .flat_map(|(layer, paths)| {
+                paths
+                    .iter()
+                    .map(move |prefix| (PathBuf::from(prefix), layer.clone()))
+            })
+            .collect();
+        prefixes.sort_by(|a, b| {
+            b.0.components()
+                .count()
+                .cmp(&a.0.components().count())
+                .then_with(|| a.0.cmp(&b.0))
+        });
+
+        let mut allowed = HashSet::new();
+        for rule in &config.allow {
+            let (from, to) = rule
+                .split_once("->")
+                .ok_or_else(|| format!("rule {rule:?} should look like \"from -> to\""))?;
+            let (from, to) = (from.trim(), to.trim());
+            for layer in [from, to] {
+                if !config.layers.contains_key(layer) {
+                    return Err(format!("rule {rule:?} uses undeclared layer {layer:?}"));
+                }
+            }
+            allowed.insert((from.to_string(), to.to_string()));
+        }
+
+        Ok(Self { prefixes, allowed })
+    }
+
+    /// The layer a file belongs to, by the longest matching path prefix
+    fn layer_of(&self, relative_path: &Path) -> Option<&str> {
+        self.prefixes
+            .iter()
+            .find(|(prefix, _)| relative_path.starts_with(prefix))
+            .map(|(_, layer)| layer.as_str())
+    }
+
+    /// Find every edge that goes from one layer to another without an `allow` rule.
+    /// File paths are matched against layer prefixes relative to `project_root`.
+    pub fn find_violations(
+        &self,
+        graph_nodes: &[GraphNode],
+        project_root: &Path,
+    ) -> Vec<LayerViolation> {
+        let layer_of = |file: &Path| self.layer_of(file.strip_prefix(project_root).unwrap_or(file));
+
+        let mut violations = Vec::new();
+        for node in graph_nodes {
+            let from = node.data().file();
+            let Some(from_layer) = layer_of(from) else {
+                continue;
+            };
+            for to in node.edges() {
+                let Some(to_layer) = layer_of(to) else {
+                    continue;
+                };
+                let pair = (from_layer.to_string(), to_layer.to_string());
+                if from_layer != to_layer && !self.allowed.contains(&pair) {
+                    violations.push(LayerViolation {
+                        from: from.clone(),
+                        from_layer: pair.0,
+                        to: to.clone(),
+                        to_layer: pair.1,
+                    });
+                }
+            }
+        }
+
+        violations.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::defs::Language;
+
+    const CONFIG: &str = r#"{
+        "layers": {
+            "ui": ["project/src/ui"],
+            "core": ["project/src/core"],
+            "storage": ["project/src/core/storage"]
+        },
+        "allow": ["ui -> core", "core -> storage"]
+    }"#;
+
+    #[test]
+    fn test_forbidden_edge_is_reported() {
+        let rules = LayerRules::from_json(CONFIG).unwrap();
+        let graph_nodes = vec![
+            GraphNode::for_test(
+                "/repo/project/src/ui/view.rs",
+                Language::Rust,
+                10,
+                &["/repo/project/src/core/model.rs"],
+            ),
+            GraphNode::for_test(
+                "/repo/project/src/core/model.rs",
+                Language::Rust,
+                10,
+                &[
+                    "/repo/project/src/ui/view.rs",
+                    "/repo/project/src/core/storage/db.rs",
+                    "/repo/project/src/core/util.rs",
+                    "/repo/project/src/main.rs",
+                ],
+            ),
+            GraphNode::for_test(
+                "/repo/project/src/core/storage/db.rs",
+                Language::Rust,
+                10,
+                &["/repo/project/src/ui/view.rs"],
+            ),
+        ];
+
+        let violations = rules.find_violations(&graph_nodes, Path::new("/repo"));
+        assert_eq!(
+            violations,
+            vec![
+                LayerViolation {
+                    from: PathBuf::from("/repo/project/src/core/model.rs"),
+                    from_layer: "core".to_string(),
+                    to: PathBuf::from("/repo/project/src/ui/view.rs"),
+                    to_layer: "ui".to_string(),
+                },
+                LayerViolation {
+                    from: PathBuf::from("/repo/project/src/core/storage/db.rs"),
+                    from_layer: "storage".to_string(),
+                    to: PathBuf::from("/repo/project/src/ui/view.rs"),
+                    to_layer: "ui".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let undeclared = r#"{ "layers": { "ui": ["ui"] }, "allow": ["ui -> core"] }"#;
+        assert!(
+            LayerRules::from_json(undeclared)
+                .unwrap_err()
+                .contains("undeclared layer")
+        );
+
+        let malformed = r#"{ "layers": { "ui": ["ui"] }, "allow": ["ui"] }"#;
+        assert!(LayerRules::from_json(malformed).is_err());
+    }
+}
@@ -1,9 +1,19 @@
 pub mod circular;
+pub mod directories;
+pub mod force;
+pub mod grid;
+pub mod overlap;
+pub mod radial;
 pub mod sugiyama;
 
+use crate::core::defs::GraphNode;
 use circular::{CircularConfig, CircularLayout};
+use force::{ForceDirectedConfig, ForceDirectedLayout};
+use grid::{GridConfig, GridLayout, directory_groups};
 use petgraph::graph::{Graph, NodeIndex};
+use radial::{RadialConfig, RadialLayout};
 use std::collections::HashMap;
+use std::str::FromStr;
 use sugiyama::{SugiyamaConfig, SugiyamaLayout};
 
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
@@ -11,6 +21,29 @@ pub enum LayoutType {
     #[default]
     Circular,
     Sugiyama,
+    ForceDirected,
+    /// Files clustered by parent directory
+    Grid,
+    /// Rings by BFS depth around a root node
+    Radial,
+}
+
+impl FromStr for LayoutType {
+    type Err = String;
+
+    /// Parse a layout name as given on the command line, e.g. "sugiyama" or "force"
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "circular" => Ok(LayoutType::Circular),
+            "sugiyama" => Ok(LayoutType::Sugiyama),
+            "force" | "force-directed" => Ok(LayoutType::ForceDirected),
+            "grid" => Ok(LayoutType::Grid),
+            "radial" => Ok(LayoutType::Radial),
+            _ => Err(format!(
+                "Unknown layout: {name} (expected circular, sugiyama, force, grid or radial)"
+            )),
+        }
+    }
 }
 
 pub trait Layout {
@@ -21,6 +54,23 @@ pub fn create_layout(layout_type: LayoutType) -> Box<dyn Layout> {
     match layout_type {
         LayoutType::Circular => Box::new(CircularLayout::new(CircularConfig::default())),
         LayoutType::Sugiyama => Box::new(SugiyamaLayout::new(SugiyamaConfig::default())),
+        LayoutType::ForceDirected => {
+            Box::new(ForceDirectedLayout::new(ForceDirectedConfig::default()))
+        }
+        LayoutType::Grid => Box::new(GridLayout::new(GridConfig::default())),
+        LayoutType::Radial => Box::new(RadialLayout::new(RadialConfig::default())),
+    }
+}
+
+/// Create a layout for `graph_nodes`, where node `i` of the laid out graph is
+/// `graph_nodes[i]`. Unlike [`create_layout`], layouts that look past the graph's
+/// shape get what they need, e.g. the grid clusters files by directory.
+pub fn create_layout_for(layout_type: LayoutType, graph_nodes: &[GraphNode]) -> Box<dyn Layout> {
+    match layout_type {
+        LayoutType::Grid => Box::new(
+            GridLayout::new(GridConfig::default()).with_groups(directory_groups(graph_nodes)),
+        ),
+        layout_type => create_layout(layout_type),
     }
 }
 
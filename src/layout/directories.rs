@@ -0,0 +1,196 @@
+use std::path::{Path, PathBuf};
+
+/// Space between a directory's box and the nodes and boxes inside it, used by both the GUI
+/// and SVG exports
+pub const TREE_PADDING: f32 = 8.0;
+
+/// A directory holding some of the analyzed files, with the files directly inside it
+/// and the subdirectories that contain any of the rest
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirectoryTree {
+    pub path: PathBuf,
+    /// Indices of the files directly in this directory
+    pub files: Vec<usize>,
+    /// Subdirectories, sorted by path
+    pub children: Vec<DirectoryTree>,
+}
+
+/// The area drawn for one directory: the box around its files' nodes and every
+/// subdirectory box, padded once more for each level it encloses
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirectoryBox {
+    pub path: PathBuf,
+    /// How many directories enclose this one
+    pub depth: usize,
+    pub min: (f32, f32),
+    pub max: (f32, f32),
+}
+
+impl DirectoryTree {
+    fn new(path: PathBuf) -> Self {
+        DirectoryTree {
+            path,
+            files: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Build the tree of the directories containing `files`, where file `i` is
+    /// `files[i]`, rooted at the deepest directory that contains all of them
+    pub fn from_files(files: &[&Path]) -> Self {
+        let parents: Vec<&Path> = files
+            .iter()
+            .map(|file| file.parent().unwrap_or(Path::new("")))
+            .collect();
+        let root = parents
+            .iter()
+            .skip(1)
+            .fold(parents.first().map(|p| p.to_path_buf()), |root, parent| {
+                root.map(|root| {
+                    root.components()
+                        .zip(parent.components())
+                        .take_while(|(a, b)| a == b)
+                        .map(|(a, _)| a)
+                        .collect()
+                })
+            })
+            .unwrap_or_default();
+
+        let mut tree = DirectoryTree::new(root.clone());
+        for (i, parent) in parents.iter().enumerate() {
+            let mut dir = &mut tree;
+            for component in parent.strip_prefix(&root).unwrap_or(parent).components() {
+                let path = dir.path.join(component);
+                let child = match dir.children.iter().position(|child| child.path == path) {
+                    Some(child) => child,
+                    None => {
+                        dir.children.push(DirectoryTree::new(path));
+                        dir.children.len() - 1
+                    }
+                };
+                dir = &mut dir.children[child];
+            }
+            dir.files.push(i);
+        }
+        tree.sort();
+        tree
+    }
+
+    fn sort(&mut self) {
+        self.children.sort_by(|a, b| a.path.cmp(&b.path));
+        self.children.iter_mut().for_each(DirectoryTree::sort);
+    }
+
+    /// Boxes of this directory and everything below it, outermost first. File `i` is a
+    /// node at `positions[i]` with radius `radii[i]`, and every box is `padding` larger
+    /// than what it encloses.
+    pub fn boxes(
+        &self,
+        positions: &[(f32, f32)],
+        radii: &[f32],
+        padding: f32,
+    ) -> Vec<DirectoryBox> {
+        let mut boxes = Vec::new();
+        self.collect_boxes(positions, radii, padding, 0, &mut boxes);
+        boxes
+    }
+
+    fn collect_boxes(
+        &self,
+        positions: &[(f32, f32)],
+        radii: &[f32],
+        padding: f32,
+        depth: usize,
+        boxes: &mut Vec<DirectoryBox>,
+    ) -> Option<((f32, f32), (f32, f32))> {
+        let index = boxes.len();
+        boxes.push(DirectoryBox {
+            path: self.path.clone(),
+            depth,
+            min: (0.0, 0.0),
+            max: (0.0, 0.0),
+        });
+
+        let mut bounds: Option<((f32, f32), (f32, f32))> = None;
+        let mut extend = |(min, max): ((f32, f32), (f32, f32))| {
+            bounds = Some(match bounds {
+                Some((lo, hi)) => (
+                    (lo.0.min(min.0), lo.1.min(min.1)),
+                    (hi.0.max(max.0), hi.1.max(max.1)),
+                ),
+                None => (min, max),
+            });
+        };
+        for &i in &self.files {
+            let ((x, y), r) = (positions[i], radii[i]);
+            extend(((x - r, y - r), (x + r, y + r)));
+        }
+        for child in &self.children {
+            if let Some(child_bounds) =
+                child.collect_boxes(positions, radii, padding, depth + 1, boxes)
+            {
+                extend(child_bounds);
+            }
+        }
+
+        let Some((min, max)) = bounds else {
+            boxes.remove(index);
+            return None;
+        };
+        let padded = (
+            (min.0 - padding, min.1 - padding),
+            (max.0 + padding, max.1 + padding),
+        );
+        (boxes[index].min, boxes[index].max) = padded;
+        Some(padded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_from_files() {
+        let files = [
+            "src/main.rs",
+            "src/core/defs.rs",
+            "src/core/resolvers/rust.rs",
+            "src/gui.rs",
+            "src/gui/camera.rs",
+        ]
+        .map(Path::new);
+        let tree = DirectoryTree::from_files(&files);
+
+        assert_eq!(tree.path, Path::new("src"));
+        assert_eq!(tree.files, vec![0, 3]);
+        let children: Vec<&Path> = tree.children.iter().map(|c| c.path.as_path()).collect();
+        assert_eq!(children, vec![Path::new("src/core"), Path::new("src/gui")]);
+        assert_eq!(tree.children[0].files, vec![1]);
+        assert_eq!(
+            tree.children[0].children[0].path,
+            Path::new("src/core/resolvers")
+        );
+        assert_eq!(tree.children[0].children[0].files, vec![2]);
+        assert_eq!(tree.children[1].files, vec![4]);
+
+        // Every box holds its own nodes and the boxes of its subdirectories
+        let positions = [
+            (0.0, 0.0),
+            (100.0, 0.0),
+            (200.0, 50.0),
+            (0.0, 100.0),
+            (50.0, 300.0),
+        ];
+        let boxes = tree.boxes(&positions, &[10.0; 5], 5.0);
+        assert_eq!(boxes.len(), 4);
+        assert_eq!(boxes[0].depth, 0);
+        assert_eq!(
+            (boxes[0].min, boxes[0].max),
+            ((-15.0, -20.0), (225.0, 320.0))
+        );
+        assert_eq!(boxes[2].path, Path::new("src/core/resolvers"));
+        assert_eq!((boxes[2].min, boxes[2].max), ((185.0, 35.0), (215.0, 65.0)));
+        assert_eq!((boxes[1].min, boxes[1].max), ((85.0, -15.0), (220.0, 70.0)));
+    }
+}
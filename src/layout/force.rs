@@ -0,0 +1,179 @@
+use crate::layout::Layout;
+use petgraph::graph::{Graph, NodeIndex};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+/// Configuration options for force-directed layout
+#[derive(Debug, Clone)]
+pub struct ForceDirectedConfig {
+    /// Number of simulation steps to run
+    pub iterations: usize,
+    /// Strength of the repulsive force pushing every pair of nodes apart
+    pub repulsion: f32,
+    /// Strength of the attractive force pulling connected nodes together
+    pub attraction: f32,
+    /// Strength of the pull towards the center, which keeps disconnected components close
+    pub gravity: f32,
+    /// Ideal distance between connected nodes
+    pub ideal_distance: f32,
+}
+
+impl Default for ForceDirectedConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 300,
+            repulsion: 1.0,
+            attraction: 1.0,
+            gravity: 0.05,
+            ideal_distance: 120.0,
+        }
+    }
+}
+
+/// Fruchterman-Reingold style force-directed layout
+pub struct ForceDirectedLayout {
+    config: ForceDirectedConfig,
+}
+
+impl ForceDirectedLayout {
+    pub fn new(config: ForceDirectedConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Layout for ForceDirectedLayout {
+    fn layout(&self, graph: &Graph<(), ()>) -> HashMap<NodeIndex, (f32, f32)> {
+        let nodes: Vec<NodeIndex> = graph.node_indices().collect();
+        let n = nodes.len();
+        if n == 0 {
+            return HashMap::new();
+        }
+
+        let k = self.config.ideal_distance;
+
+        // Start from a circle so the result is deterministic
+        let initial_radius = k * (n as f32).sqrt();
+        let mut positions: Vec<(f32, f32)> = (0..n)
+            .map(|i| {
+                let angle = i as f32 * 2.0 * PI / n as f32;
+                (initial_radius * angle.cos(), initial_radius * angle.sin())
+            })
+            .collect();
+
+        let edges: Vec<(usize, usize)> = graph
+            .edge_indices()
+            .filter_map(|e| graph.edge_endpoints(e))
+            .map(|(a, b)| (a.index(), b.index()))
+            .filter(|(a, b)| a != b)
+            .collect();
+
+        // The temperature caps how far a node may move per step and cools linearly
+        let initial_temperature = initial_radius / 2.0;
+        let iterations = self.config.iterations.max(1);
+
+        for step in 0..iterations {
+            let temperature = initial_temperature * (1.0 - step as f32 / iterations as f32);
+            let mut displacement = vec![(0.0f32, 0.0f32); n];
+
+            // Repulsion between every pair of nodes
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    let (mut dx, mut dy) = (
+                        positions[i].0 - positions[j].0,
+                        positions[i].1 - positions[j].1,
+                    );
+                    let mut distance = (dx * dx + dy * dy).sqrt();
+                    if distance < 0.01 {
+                        // Nudge overlapping nodes apart in a fixed direction
+                        let angle = (i + j) as f32;
+                        (dx, dy) = (angle.cos() * 0.01, angle.sin() * 0.01);
+                        distance = 0.01;
+                    }
+                    let force = self.config.repulsion * k * k / distance;
+                    let (fx, fy) = (dx / distance * force, dy / distance * force);
+                    displacement[i].0 += fx;
+                    displacement[i].1 += fy;
+                    displacement[j].0 -= fx;
+                    displacement[j].1 -= fy;
+                }
+            }
+
+            // Attraction along edges
+            for &(a, b) in &edges {
+                let (dx, dy) = (
+                    positions[a].0 - positions[b].0,
+                    positions[a].1 - positions[b].1,
+                );
+                let distance = (dx * dx + dy * dy).sqrt().max(0.01);
+                let force = self.config.attraction * distance * distance / k;
+                let (fx, fy) = (dx / distance * force, dy / distance * force);
+                displacement[a].0 -= fx;
+                displacement[a].1 -= fy;
+                displacement[b].0 += fx;
+                displacement[b].1 += fy;
+            }
+
+            // Gravity towards the origin and capped movement
+            for (position, disp) in positions.iter_mut().zip(displacement.iter_mut()) {
+                let distance = (position.0 * position.0 + position.1 * position.1).sqrt();
+                disp.0 -= self.config.gravity * position.0 * distance / k;
+                disp.1 -= self.config.gravity * position.1 * distance / k;
+
+                let length = (disp.0 * disp.0 + disp.1 * disp.1).sqrt();
+                if length > 0.0 {
+                    let capped = length.min(temperature);
+                    position.0 += disp.0 / length * capped;
+                    position.1 += disp.1 / length * capped;
+                }
+            }
+        }
+
+        nodes.into_iter().zip(positions).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_finite_and_distinct(positions: &HashMap<NodeIndex, (f32, f32)>) {
+        let points: Vec<&(f32, f32)> = positions.values().collect();
+        for (i, a) in points.iter().enumerate() {
+            assert!(a.0.is_finite() && a.1.is_finite());
+            for b in &points[i + 1..] {
+                let distance = ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt();
+                assert!(distance > 1.0, "nodes overlap: {a:?} and {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_five_node_graph() {
+        let mut graph = Graph::new();
+        let nodes: Vec<NodeIndex> = (0..5).map(|_| graph.add_node(())).collect();
+        graph.add_edge(nodes[0], nodes[1], ());
+        graph.add_edge(nodes[1], nodes[2], ());
+        graph.add_edge(nodes[2], nodes[0], ());
+        graph.add_edge(nodes[3], nodes[4], ());
+
+        let positions = ForceDirectedLayout::new(ForceDirectedConfig::default()).layout(&graph);
+
+        assert_eq!(positions.len(), 5);
+        assert_finite_and_distinct(&positions);
+    }
+
+    #[test]
+    fn test_disconnected_nodes_stay_bounded() {
+        let mut graph = Graph::new();
+        for _ in 0..5 {
+            graph.add_node(());
+        }
+
+        let positions = ForceDirectedLayout::new(ForceDirectedConfig::default()).layout(&graph);
+
+        assert_finite_and_distinct(&positions);
+        for &(x, y) in positions.values() {
+            assert!((x * x + y * y).sqrt() < 5000.0);
+        }
+    }
+}
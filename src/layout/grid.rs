@@ -0,0 +1,171 @@
+use crate::core::defs::GraphNode;
+use crate::layout::Layout;
+use petgraph::graph::{Graph, NodeIndex};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+/// Configuration options for grid layout
+#[derive(Debug, Clone)]
+pub struct GridConfig {
+    /// Distance between neighbouring nodes inside a cluster
+    pub cell_size: f32,
+    /// Extra space left between clusters
+    pub gutter: f32,
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        Self {
+            cell_size: 100.0,
+            gutter: 150.0,
+        }
+    }
+}
+
+/// Lays each cluster of nodes out in a small grid, and the clusters in a larger grid
+pub struct GridLayout {
+    config: GridConfig,
+    /// Cluster of each node, indexed like the graph's nodes; all nodes share one cluster when empty
+    groups: Vec<usize>,
+}
+
+impl GridLayout {
+    pub fn new(config: GridConfig) -> Self {
+        Self {
+            config,
+            groups: Vec::new(),
+        }
+    }
+
+    /// Cluster nodes by the given group ids, e.g. from [`directory_groups`]
+    pub fn with_groups(mut self, groups: Vec<usize>) -> Self {
+        self.groups = groups;
+        self
+    }
+}
+
+/// Group id of each node by its file's parent directory, numbered in directory order
+pub fn directory_groups(graph_nodes: &[GraphNode]) -> Vec<usize> {
+    let parents: Vec<&Path> = graph_nodes
+        .iter()
+        .map(|node| node.data().file().parent().unwrap_or(Path::new("")))
+        .collect();
+    let ids: BTreeMap<&Path, usize> = parents
+        .iter()
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .enumerate()
+        .map(|(id, parent)| (parent, id))
+        .collect();
+    parents.iter().map(|parent| ids[parent]).collect()
+}
+
+/// Columns and rows of the most square grid that holds `count` cells
+fn grid_dimensions(count: usize) -> (usize, usize) {
+    let columns = (count as f32).sqrt().ceil().max(1.0) as usize;
+    (columns, count.div_ceil(columns))
+}
+
+impl Layout for GridLayout {
+    fn layout(&self, graph: &Graph<(), ()>) -> HashMap<NodeIndex, (f32, f32)> {
+        let mut clusters: BTreeMap<usize, Vec<NodeIndex>> = BTreeMap::new();
+        for node in graph.node_indices() {
+            let group = self.groups.get(node.index()).copied().unwrap_or(0);
+            clusters.entry(group).or_default().push(node);
+        }
+
+        let cell = self.config.cell_size;
+        let (block_columns, _) = grid_dimensions(clusters.len());
+        let mut positions = HashMap::new();
+        let (mut block_x, mut block_y) = (0.0, 0.0);
+        let mut row_height: f32 = 0.0;
+
+        for (i, members) in clusters.values().enumerate() {
+            if i > 0 && i % block_columns == 0 {
+                block_x = 0.0;
+                block_y += row_height + self.config.gutter;
+                row_height = 0.0;
+            }
+
+            let (columns, rows) = grid_dimensions(members.len());
+            for (j, &node) in members.iter().enumerate() {
+                let x = block_x + (j % columns) as f32 * cell;
+                let y = block_y + (j / columns) as f32 * cell;
+                positions.insert(node, (x, y));
+            }
+
+            block_x += columns as f32 * cell + self.config.gutter;
+            row_height = row_height.max(rows as f32 * cell);
+        }
+        positions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::build_dependency_graph;
+    use crate::core::defs::Language;
+
+    fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn test_same_directory_files_are_adjacent() {
+        // Directories are interleaved so grouping can't come from the input order
+        let graph_nodes = vec![
+            GraphNode::for_test("src/a.rs", Language::Rust, 10, &[]),
+            GraphNode::for_test("tests/x.rs", Language::Rust, 10, &[]),
+            GraphNode::for_test("src/b.rs", Language::Rust, 10, &[]),
+            GraphNode::for_test("src/ui/c.rs", Language::Rust, 10, &[]),
+            GraphNode::for_test("tests/y.rs", Language::Rust, 10, &[]),
+            GraphNode::for_test("src/d.rs", Language::Rust, 10, &[]),
+            GraphNode::for_test("src/ui/e.rs", Language::Rust, 10, &[]),
+        ];
+        let groups = directory_groups(&graph_nodes);
+        assert_eq!(groups, vec![0, 2, 0, 1, 2, 0, 1]);
+
+        let graph = build_dependency_graph(&graph_nodes);
+        let positions = GridLayout::new(GridConfig::default())
+            .with_groups(groups.clone())
+            .layout(&graph);
+        assert_eq!(positions.len(), graph_nodes.len());
+
+        let position = |i: usize| positions[&NodeIndex::new(i)];
+        let mut max_within = 0.0_f32;
+        let mut min_between = f32::INFINITY;
+        for i in 0..graph_nodes.len() {
+            for j in i + 1..graph_nodes.len() {
+                let d = distance(position(i), position(j));
+                if groups[i] == groups[j] {
+                    max_within = max_within.max(d);
+                } else {
+                    min_between = min_between.min(d);
+                }
+            }
+        }
+        assert!(
+            max_within < min_between,
+            "clusters overlap: {max_within} >= {min_between}"
+        );
+    }
+
+    #[test]
+    fn test_single_cluster_is_a_grid() {
+        let mut graph = Graph::new();
+        for _ in 0..4 {
+            graph.add_node(());
+        }
+
+        let positions = GridLayout::new(GridConfig::default()).layout(&graph);
+
+        let mut points: Vec<(f32, f32)> = positions.values().copied().collect();
+        points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(
+            points,
+            vec![(0.0, 0.0), (0.0, 100.0), (100.0, 0.0), (100.0, 100.0)]
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use std::f32::consts::PI;
+
+/// Configuration options for the overlap removal pass run after a layout
+#[derive(Debug, Clone)]
+pub struct OverlapConfig {
+    /// Maximum number of passes over every pair of nodes
+    pub iterations: usize,
+    /// Space left between the borders of neighbouring nodes
+    pub gap: f32,
+}
+
+impl Default for OverlapConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 50,
+            gap: 4.0,
+        }
+    }
+}
+
+/// Push apart every pair of nodes whose circles overlap, each node of a pair moving
+/// half the overlap away from the other. Node `i` is at `positions[i]` with radius
+/// `radii[i]`; stops early once no pair overlaps.
+pub fn remove_overlaps(positions: &mut [(f32, f32)], radii: &[f32], config: &OverlapConfig) {
+    let n = positions.len().min(radii.len());
+    for _ in 0..config.iterations {
+        let mut moved = false;
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let min_distance = radii[i] + radii[j] + config.gap;
+                let dx = positions[j].0 - positions[i].0;
+                let dy = positions[j].1 - positions[i].1;
+                let distance = (dx * dx + dy * dy).sqrt();
+                if distance >= min_distance {
+                    continue;
+                }
+
+                // Nodes on the same spot get a direction of their own, so a stack of them
+                // spreads out instead of moving as one
+                let (dir_x, dir_y) = if distance > f32::EPSILON {
+                    (dx / distance, dy / distance)
+                } else {
+                    let angle = (i * n + j) as f32 * PI * (3.0 - 5.0f32.sqrt());
+                    (angle.cos(), angle.sin())
+                };
+                let push = (min_distance - distance) / 2.0;
+                positions[i].0 -= dir_x * push;
+                positions[i].1 -= dir_y * push;
+                positions[j].0 += dir_x * push;
+                positions[j].1 += dir_y * push;
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn test_stacked_nodes_are_separated() {
+        let mut positions = vec![(100.0, 100.0), (100.0, 100.0)];
+        let radii = [30.0, 12.0];
+        remove_overlaps(&mut positions, &radii, &OverlapConfig::default());
+        assert!(distance(positions[0], positions[1]) >= radii[0] + radii[1]);
+
+        // Nodes that are already apart stay where they are
+        let mut apart = vec![(0.0, 0.0), (200.0, 0.0)];
+        remove_overlaps(&mut apart, &radii, &OverlapConfig::default());
+        assert_eq!(apart, vec![(0.0, 0.0), (200.0, 0.0)]);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::analysis::GraphAnalysis;
+use crate::layout::Layout;
+use petgraph::graph::{Graph, NodeIndex};
+use std::collections::{HashMap, VecDeque};
+use std::f32::consts::PI;
+
+/// Configuration options for radial layout
+#[derive(Debug, Clone)]
+pub struct RadialConfig {
+    /// Distance between consecutive rings
+    pub ring_spacing: f32,
+}
+
+impl Default for RadialConfig {
+    fn default() -> Self {
+        Self {
+            ring_spacing: 120.0,
+        }
+    }
+}
+
+/// Places the root at the center and every other node on a ring by its BFS depth
+/// from the root, following dependencies. Each node gets a wedge of its parent's
+/// angle sized by how many leaves it leads to, so subtrees don't overlap. Nodes
+/// the root can't reach share one ring outside the rest.
+pub struct RadialLayout {
+    config: RadialConfig,
+    root: Option<NodeIndex>,
+}
+
+impl RadialLayout {
+    pub fn new(config: RadialConfig) -> Self {
+        Self { config, root: None }
+    }
+
+    /// Center the layout on `root` instead of the node with the highest betweenness
+    pub fn with_root(mut self, root: NodeIndex) -> Self {
+        self.root = Some(root);
+        self
+    }
+}
+
+/// Node that lies on the most shortest paths, the default center of the layout
+fn highest_betweenness(graph: &Graph<(), ()>) -> Option<NodeIndex> {
+    let analysis = GraphAnalysis::analyze_graph(graph);
+    graph.node_indices().max_by(|&a, &b| {
+        let score = |node| analysis.get_betweenness_centrality(node).unwrap_or(0.0);
+        // Prefer the lower index on ties, so the choice is stable
+        score(a).total_cmp(&score(b)).then(b.cmp(&a))
+    })
+}
+
+/// BFS depth of each node reachable from `root`, and the children of each node in
+/// the resulting tree, in visiting order
+fn bfs_tree(
+    graph: &Graph<(), ()>,
+    root: NodeIndex,
+) -> (
+    HashMap<NodeIndex, usize>,
+    HashMap<NodeIndex, Vec<NodeIndex>>,
+) {
+    let mut depth = HashMap::from([(root, 0)]);
+    let mut children: HashMap<NodeIndex, Vec<NodeIndex>> = HashMap::new();
+    let mut queue = VecDeque::from([root]);
+
+    while let Some(node) = queue.pop_front() {
+        // `neighbors` yields the most recently added edge first; sort for a stable order
+        let mut neighbors: Vec<_> = graph.neighbors(node).collect();
+        neighbors.sort();
+        for next in neighbors {
+            if !depth.contains_key(&next) {
+                depth.insert(next, depth[&node] + 1);
+                children.entry(node).or_default().push(next);
+                queue.push_back(next);
+            }
+        }
+    }
+    (depth, children)
+}
+
+/// Number of leaves under `node` in the BFS tree, counting a leaf as one
+fn leaf_count(
+    node: NodeIndex,
+    children: &HashMap<NodeIndex, Vec<NodeIndex>>,
+    counts: &mut HashMap<NodeIndex, usize>,
+) -> usize {
+    if let Some(&count) = counts.get(&node) {
+        return count;
+    }
+    let count = match children.get(&node) {
+        Some(kids) => kids
+            .iter()
+            .map(|&child| leaf_count(child, children, counts))
+            .sum(),
+        None => 1,
+    };
+    counts.insert(node, count);
+    count
+}
+
+impl Layout for RadialLayout {
+    fn layout(&self, graph: &Graph<(), ()>) -> HashMap<NodeIndex, (f32, f32)> {
+        let Some(root) = self
+            .root
+            .filter(|&root| root.index() < graph.node_count())
+            .or_else(|| highest_betweenness(graph))
+        else {
+            return HashMap::new();
+        };
+
+        let (depth, children) = bfs_tree(graph, root);
+        let mut leaves = HashMap::new();
+        leaf_count(root, &children, &mut leaves);
+
+        let spacing = self.config.ring_spacing;
+        let mut positions = HashMap::from([(root, (0.0, 0.0))]);
+
+        // Hand each child a slice of its parent's wedge, breadth first
+        let mut queue = VecDeque::from([(root, 0.0, 2.0 * PI)]);
+        while let Some((node, start, sweep)) = queue.pop_front() {
+            let Some(kids) = children.get(&node) else {
+                continue;
+            };
+            let total = leaves[&node] as f32;
+            let mut angle = start;
+            for &child in kids {
+                let child_sweep = sweep * leaves[&child] as f32 / total;
+                let middle = angle + child_sweep / 2.0;
+                let radius = depth[&child] as f32 * spacing;
+                positions.insert(child, (radius * middle.cos(), radius * middle.sin()));
+                queue.push_back((child, angle, child_sweep));
+                angle += child_sweep;
+            }
+        }
+
+        let unreached: Vec<_> = graph
+            .node_indices()
+            .filter(|node| !depth.contains_key(node))
+            .collect();
+        if !unreached.is_empty() {
+            let max_depth = depth.values().copied().max().unwrap_or(0);
+            let radius = (max_depth + 1) as f32 * spacing;
+            let step = 2.0 * PI / unreached.len() as f32;
+            for (i, node) in unreached.into_iter().enumerate() {
+                let angle = i as f32 * step;
+                positions.insert(node, (radius * angle.cos(), radius * angle.sin()));
+            }
+        }
+        positions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn radius((x, y): (f32, f32)) -> f32 {
+        (x * x + y * y).sqrt()
+    }
+
+    #[test]
+    fn test_ring_radius_grows_with_depth() {
+        // 0 -> 1, 2; 1 -> 3, 4; 2 -> 5; 5 -> 6; plus a back edge and an isolated node
+        let mut graph = Graph::new();
+        let nodes: Vec<_> = (0..8).map(|_| graph.add_node(())).collect();
+        for (from, to) in [(0, 1), (0, 2), (1, 3), (1, 4), (2, 5), (5, 6), (6, 0)] {
+            graph.add_edge(nodes[from], nodes[to], ());
+        }
+
+        let positions = RadialLayout::new(RadialConfig::default())
+            .with_root(nodes[0])
+            .layout(&graph);
+        assert_eq!(positions.len(), graph.node_count());
+        assert_eq!(positions[&nodes[0]], (0.0, 0.0));
+
+        let (depth, _) = bfs_tree(&graph, nodes[0]);
+        assert_eq!(depth[&nodes[6]], 3);
+        for (&a, &depth_a) in &depth {
+            for (&b, &depth_b) in &depth {
+                let (radius_a, radius_b) = (radius(positions[&a]), radius(positions[&b]));
+                if depth_a < depth_b {
+                    assert!(radius_a < radius_b, "{a:?} is not inside {b:?}");
+                } else if depth_a == depth_b {
+                    assert!((radius_a - radius_b).abs() < 1e-3);
+                }
+            }
+        }
+
+        // The unreachable node sits outside the deepest ring
+        assert!(radius(positions[&nodes[7]]) > radius(positions[&nodes[6]]));
+    }
+
+    #[test]
+    fn test_default_root_is_highest_betweenness() {
+        // Everything flows through node 1
+        let mut graph = Graph::new();
+        let nodes: Vec<_> = (0..4).map(|_| graph.add_node(())).collect();
+        for (from, to) in [(0, 1), (1, 2), (1, 3)] {
+            graph.add_edge(nodes[from], nodes[to], ());
+        }
+
+        let positions = RadialLayout::new(RadialConfig::default()).layout(&graph);
+        assert_eq!(positions[&nodes[1]], (0.0, 0.0));
+    }
+}
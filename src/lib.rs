@@ -0,0 +1,383 @@
+pub mod analysis;
+pub mod cache;
+pub mod config;
+pub mod core;
+pub mod diff;
+pub mod export;
+pub mod gui;
+pub mod layers;
+pub mod layout;
+pub mod log;
+pub mod parsers;
+pub mod profile;
+pub mod remote;
+pub mod watch;
+
+pub use analysis::GraphAnalysis;
+pub use core::defs::{FileNode, GraphNode, Import, Language};
+pub use core::resolvers::GraphBuilder;
+
+use crate::cache::ParseCache;
+use crate::core::defs::is_generated_file;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use parsers::{
+    cpp::parse_cpp_file, elixir::parse_elixir_file, go::parse_go_file, python::parse_python_file,
+    ruby::parse_ruby_file, rust::parse_rust_file, typescript::parse_typescript_file,
+};
+use profile::Profile;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Parse a single file with the parser for its language
+pub fn parse_file(file_path: &Path, lang: Language) -> Option<FileNode> {
+    match lang {
+        Language::Python => parse_python_file(file_path),
+        Language::Rust => parse_rust_file(file_path),
+        Language::TypeScript => parse_typescript_file(file_path),
+        Language::Cpp => parse_cpp_file(file_path),
+        Language::Go => parse_go_file(file_path),
+        Language::Elixir => parse_elixir_file(file_path),
+        Language::Ruby => parse_ruby_file(file_path),
+    }
+}
+
+/// Parse all files in parallel, keeping the ones that parsed successfully.
+/// Each parser call builds its own tree-sitter `Parser`, so no state is shared across threads.
+pub fn parse_files(language_files: &HashMap<PathBuf, Language>) -> HashMap<PathBuf, FileNode> {
+    language_files
+        .par_iter()
+        .filter_map(|(file_path, &lang)| {
+            let node = parse_file(file_path, lang)?;
+            detail!("Parsed {} file: {}", lang.to_string(), file_path.display());
+            Some((file_path.clone(), node))
+        })
+        .collect()
+}
+
+/// Compile `--exclude` patterns into a single matcher
+pub fn build_exclude_set(patterns: &[String]) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob =
+            Glob::new(pattern).map_err(|e| format!("Invalid exclude pattern {pattern:?}: {e}"))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to compile exclude patterns: {e}"))
+}
+
+/// Collect every file under `path`, honoring ignore files unless `no_gitignore` is set.
+/// Paths matching an `exclude` glob, relative to `path`, are skipped. With `max_depth`,
+/// only files at most that many directories below `path` are collected, so `Some(0)`
+/// means the files directly inside it.
+pub fn walk_directory(
+    path: &Path,
+    no_gitignore: bool,
+    exclude: &[String],
+    max_depth: Option<usize>,
+) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    let mut builder = WalkBuilder::new(path);
+    // The walker counts `path` itself as depth 0 and the files inside it as depth 1
+    builder.max_depth(max_depth.map(|depth| depth + 1));
+    if no_gitignore {
+        builder
+            .git_ignore(false)
+            .git_exclude(false)
+            .git_global(false)
+            .ignore(false);
+    } else {
+        // The walker only reads `.gitignore` files inside a git repository by default, which
+        // misses projects that aren't checked out from git (and tempfile-based tests). Reading
+        // them everywhere applies nested `.gitignore` files to their own subdirectory too.
+        builder.require_git(false);
+    }
+
+    let exclude_set = build_exclude_set(exclude).unwrap_or_else(|msg| {
+        eprintln!("Error: {msg}");
+        GlobSet::empty()
+    });
+    if !exclude_set.is_empty() {
+        let root = path.to_path_buf();
+        // Filtering entries prunes excluded directories instead of walking into them
+        builder.filter_entry(move |entry| {
+            let relative = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+            !exclude_set.is_match(relative)
+        });
+    }
+
+    for result in builder.build() {
+        match result {
+            Ok(entry) => {
+                if let Some(file_type) = entry.file_type()
+                    && file_type.is_file()
+                {
+                    paths.push(entry.path().to_path_buf());
+                }
+            }
+            Err(msg) => eprintln!("Error reading entry: {msg}"),
+        }
+    }
+
+    paths
+}
+
+/// Record the language of `target_file`, if it has a supported extension
+pub fn detect_file_language(
+    target_file: PathBuf,
+    extension_map: &HashMap<String, Language>,
+    language_files: &mut HashMap<PathBuf, Language>,
+    detected_langs: &mut HashSet<Language>,
+) {
+    if let Some(file_language) =
+        Language::from_file_with_config(target_file.to_str().unwrap(), extension_map)
+    {
+        language_files.insert(target_file.clone(), file_language);
+        detected_langs.insert(file_language);
+    }
+}
+
+/// Map each supported file to its language, returning the set of languages found,
+/// or `None` if there are no supported files
+pub fn detect_project_languages(
+    files_to_process: &[PathBuf],
+    extension_map: &HashMap<String, Language>,
+    language_files: &mut HashMap<PathBuf, Language>,
+) -> Option<HashSet<Language>> {
+    let mut detected: HashSet<Language> = HashSet::new();
+    files_to_process.iter().for_each(|entry| {
+        detect_file_language(
+            entry.to_path_buf(),
+            extension_map,
+            language_files,
+            &mut detected,
+        )
+    });
+
+    if detected.is_empty() {
+        None
+    } else {
+        Some(detected)
+    }
+}
+
+/// Options for [`analyze_project`]; the defaults analyze every supported file
+/// not ignored by `.gitignore`
+#[derive(Debug, Clone, Default)]
+pub struct AnalyzeOptions {
+    /// Do not respect `.gitignore` files
+    pub no_gitignore: bool,
+    /// Globs, relative to the project, of files to skip
+    pub exclude: Vec<String>,
+    /// How many directories below the project root to descend; `Some(0)` is the root only
+    pub max_depth: Option<usize>,
+    /// Languages left out of the analysis entirely
+    pub hidden_languages: HashSet<Language>,
+    /// When not empty, the only languages analyzed
+    pub included_languages: HashSet<Language>,
+    /// Skip files that look generated, such as protobuf or thrift output
+    pub exclude_generated: bool,
+    /// Leave type-only imports (e.g. TypeScript `import type`) out of the graph
+    pub skip_type_imports: bool,
+    /// Re-export hops to follow past an imported barrel file, or `None` for
+    /// [`core::resolvers::DEFAULT_REEXPORT_DEPTH`]
+    pub reexport_depth: Option<usize>,
+    /// Extra file extensions, mapped to the language that handles them
+    pub extension_map: HashMap<String, Language>,
+    /// Extensions each language's resolver tries first for extensionless imports
+    pub extension_priority: HashMap<Language, Vec<String>>,
+    /// Return an empty graph instead of an error when no supported files are found
+    pub allow_empty: bool,
+    /// File to keep parsed files in between runs, so only changed files are parsed
+    /// again; `None` parses every file
+    pub cache_file: Option<PathBuf>,
+    /// Files or directories under the project root to walk instead of the whole root,
+    /// e.g. `src` and `tests` only; imports between them still resolve against the root.
+    /// `exclude` and `max_depth` apply relative to each of them.
+    pub include_paths: Vec<PathBuf>,
+}
+
+impl AnalyzeOptions {
+    /// Whether files of `language` pass both the included and the hidden language filters
+    pub fn analyzes_language(&self, language: Language) -> bool {
+        (self.included_languages.is_empty() || self.included_languages.contains(&language))
+            && !self.hidden_languages.contains(&language)
+    }
+}
+
+/// Everything produced while building a project's dependency graph
+pub struct ProjectGraph {
+    pub graph_nodes: Vec<GraphNode>,
+    /// Languages of the analyzed files
+    pub detected_languages: HashSet<Language>,
+    /// Parsed files, keyed by path
+    pub node_map: HashMap<PathBuf, FileNode>,
+    /// The builder that resolved imports, for asking how an import resolved
+    pub graph_builder: GraphBuilder,
+    /// Time spent walking, detecting, parsing (per language) and building edges
+    pub profile: Profile,
+}
+
+impl ProjectGraph {
+    /// Bring the graph up to date after the `changed` files of the project at `path` were
+    /// edited or deleted. Only they are parsed again, with `parse` (normally [`parse_file`]),
+    /// and only their edges and the edges of files depending on them are rebuilt.
+    /// Returns `false`, changing nothing, when a changed file is new to the graph, since
+    /// only a full [`build_project`] walk can tell whether it belongs to the project.
+    pub fn update_files(
+        &mut self,
+        path: &Path,
+        changed: &HashSet<PathBuf>,
+        parse: impl Fn(&Path, Language) -> Option<FileNode>,
+    ) -> bool {
+        if changed
+            .iter()
+            .any(|file| file.exists() && !self.node_map.contains_key(file))
+        {
+            return false;
+        }
+
+        for file in changed {
+            let Some(language) = self.node_map.get(file).map(|node| *node.language()) else {
+                continue;
+            };
+            // A file that no longer parses drops out of the graph like a deleted one
+            match file.exists().then(|| parse(file, language)).flatten() {
+                Some(node) => self.node_map.insert(file.clone(), node),
+                None => self.node_map.remove(file),
+            };
+        }
+        let languages: HashSet<Language> = self
+            .node_map
+            .values()
+            .map(|node| *node.language())
+            .collect();
+        self.detected_languages
+            .retain(|lang| languages.contains(lang));
+
+        self.graph_builder
+            .update_graph_edges(&self.node_map, path, &mut self.graph_nodes, changed);
+        true
+    }
+}
+
+/// Walk, detect, parse, and resolve the project at `path` into a dependency graph
+pub fn build_project(path: &Path, options: &AnalyzeOptions) -> Result<ProjectGraph, String> {
+    let mut profile = Profile::default();
+
+    // Detect languages in file/project
+    let mut language_files: HashMap<PathBuf, Language> = HashMap::new();
+    let walk_roots = if options.include_paths.is_empty() {
+        vec![path.to_path_buf()]
+    } else {
+        options.include_paths.clone()
+    };
+    let files_to_process = profile.time("walk", || {
+        // An included path may not exist in an older checkout of the project
+        let mut files: Vec<PathBuf> = walk_roots
+            .iter()
+            .filter(|root| root.exists())
+            .flat_map(|root| {
+                walk_directory(
+                    root,
+                    options.no_gitignore,
+                    &options.exclude,
+                    options.max_depth,
+                )
+            })
+            .collect();
+        // Overlapping paths, like `src` and `src/core`, would list files twice
+        files.sort();
+        files.dedup();
+        files
+    });
+    let mut detected_languages = profile
+        .time("detect", || {
+            detect_project_languages(
+                &files_to_process,
+                &options.extension_map,
+                &mut language_files,
+            )
+        })
+        .unwrap_or_default();
+
+    // Drop filtered languages and generated files entirely, before any parsing happens.
+    // Excluded paths never made it out of the walk, so every filter must pass.
+    language_files.retain(|_, lang| options.analyzes_language(*lang));
+    if options.exclude_generated {
+        // Only the part inside the project counts, so a `gen-` directory above it doesn't
+        // mark every file as generated
+        language_files
+            .retain(|file, _| !is_generated_file(file.strip_prefix(path).unwrap_or(file)));
+    }
+    detected_languages.retain(|lang| options.analyzes_language(*lang));
+    if detected_languages.is_empty() && !options.allow_empty {
+        return Err("No supported language files found in the project".to_string());
+    }
+
+    // Parse files and collect Nodes, indexed by file path, one language at a time so
+    // each gets its own timing; files of a language are still parsed in parallel
+    let mut languages: Vec<Language> = language_files.values().copied().collect();
+    languages.sort_by_key(|language| language.to_string());
+    languages.dedup();
+    let mut cache = options.cache_file.as_deref().map(|cache_file| {
+        let mut cache = ParseCache::load(cache_file);
+        cache.set_project_files(&language_files);
+        cache
+    });
+    let mut node_map = HashMap::new();
+    for language in languages {
+        let files: HashMap<PathBuf, Language> = language_files
+            .iter()
+            .filter(|(_, lang)| **lang == language)
+            .map(|(path, lang)| (path.clone(), *lang))
+            .collect();
+        let parsed = profile.time(
+            &format!("parse[{}]", language.to_string()),
+            || match &mut cache {
+                Some(cache) => cache.parse_files(&files, parse_file),
+                None => parse_files(&files),
+            },
+        );
+        node_map.extend(parsed);
+    }
+    if let (Some(cache), Some(cache_file)) = (&mut cache, &options.cache_file) {
+        cache.retain_files(&language_files);
+        // A cache that can't be written only costs the next run some parsing
+        if let Err(msg) = cache.save(cache_file) {
+            warn!("failed to write the parse cache: {msg}");
+        }
+    }
+
+    // Build GraphNodes with multi-language support
+    let mut graph_builder = GraphBuilder::new();
+    graph_builder.set_skip_type_imports(options.skip_type_imports);
+    if let Some(depth) = options.reexport_depth {
+        graph_builder.set_reexport_depth(depth);
+    }
+    for (language, extensions) in &options.extension_priority {
+        graph_builder.set_extension_priority(*language, extensions);
+    }
+    let graph_nodes = profile.time("build edges", || {
+        graph_builder.build_graph_edges(&node_map, path)
+    });
+
+    Ok(ProjectGraph {
+        graph_nodes,
+        detected_languages,
+        node_map,
+        graph_builder,
+        profile,
+    })
+}
+
+/// Analyze the project at `path` (a directory or a single file) and return its
+/// files with their resolved dependencies, without printing or writing anything
+/// beyond the per-file detail printed at [`log::LogLevel::Verbose`]
+pub fn analyze_project(path: &Path, options: AnalyzeOptions) -> Result<Vec<GraphNode>, String> {
+    build_project(path, &options).map(|project| project.graph_nodes)
+}
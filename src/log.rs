@@ -0,0 +1,89 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much the command line tool prints to stdout. Errors go to stderr at every level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum LogLevel {
+    /// Nothing but the reports that were asked for, e.g. `--stats`
+    Quiet,
+    /// High-level milestones, such as cloning a repository or writing the export
+    #[default]
+    Normal,
+    /// Per-file detail on top of the milestones
+    Verbose,
+}
+
+impl LogLevel {
+    /// The level picked by the `--quiet` and `--verbose` flags
+    pub fn from_flags(quiet: bool, verbose: bool) -> Self {
+        if quiet {
+            LogLevel::Quiet
+        } else if verbose {
+            LogLevel::Verbose
+        } else {
+            LogLevel::Normal
+        }
+    }
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Normal as u8);
+
+/// Set the level for the rest of the process
+pub fn set_level(level: LogLevel) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn level() -> LogLevel {
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => LogLevel::Quiet,
+        1 => LogLevel::Normal,
+        _ => LogLevel::Verbose,
+    }
+}
+
+/// Whether messages meant for `level` are printed
+pub fn enabled(level: LogLevel) -> bool {
+    self::level() >= level
+}
+
+/// Print a milestone, unless `--quiet` is set
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::LogLevel::Normal) {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Print a warning to stderr, unless `--quiet` is set
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::LogLevel::Normal) {
+            eprintln!("Warning: {}", format_args!($($arg)*));
+        }
+    };
+}
+
+/// Print per-file detail, only with `--verbose`
+#[macro_export]
+macro_rules! detail {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::LogLevel::Verbose) {
+            println!($($arg)*);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_from_flags() {
+        assert_eq!(LogLevel::from_flags(false, false), LogLevel::Normal);
+        assert_eq!(LogLevel::from_flags(false, true), LogLevel::Verbose);
+        assert_eq!(LogLevel::from_flags(true, false), LogLevel::Quiet);
+        assert!(LogLevel::Quiet < LogLevel::Normal && LogLevel::Normal < LogLevel::Verbose);
+    }
+}
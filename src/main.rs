@@ -1,52 +1,220 @@
-mod gui;
-use gui::run_gui;
-
-mod analysis;
-mod core;
-mod export;
-mod layout;
-mod parsers;
-
-use clap::{Parser, crate_name, crate_version};
-use core::defs::{FileNode, Language};
-use core::resolvers::GraphBuilder;
-use ignore::WalkBuilder;
-use parsers::{
-    cpp::parse_cpp_file, python::parse_python_file, rust::parse_rust_file,
-    typescript::parse_typescript_file,
+use clap::{Parser, Subcommand, crate_name, crate_version};
+use petgraph::graph::NodeIndex;
+use seiri_cli::analysis::{
+    GraphAnalysis, average_import_locality, build_dependency_graph, build_edge_list,
+    directory_cohesion,
+};
+use seiri_cli::cache::project_cache_file;
+use seiri_cli::config::{
+    language_extension_map, load_language_extensions, parse_extension_priority,
+};
+use seiri_cli::core::defs::{FileNode, GraphNode, Import, Language};
+use seiri_cli::core::resolvers::GraphBuilder;
+use seiri_cli::diff::{graph_diff, overlay_nodes};
+use seiri_cli::export::{self, ExportOptions};
+use seiri_cli::gui::{ColorBy, GuiOptions, run_gui, run_gui_with_updates};
+use seiri_cli::layers::LayerRules;
+use seiri_cli::layout::LayoutType;
+use seiri_cli::log::{self, LogLevel};
+use seiri_cli::parsers::explain_import_classification;
+use seiri_cli::profile::Profile;
+use seiri_cli::remote::{checkout_revision, clone_remote, is_remote_url};
+use seiri_cli::watch::{DEFAULT_DEBOUNCE, ProjectWatcher};
+use seiri_cli::{
+    AnalyzeOptions, ProjectGraph, build_exclude_set, build_project, detail,
+    detect_project_languages, info, parse_file, walk_directory,
 };
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-
-#[derive(Parser)]
+use std::process::Command;
+use std::str::FromStr;
+use std::sync::mpsc::channel;
+use std::thread;
+use std::time::Instant;
+
+#[derive(Parser, Default)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
-    /// Path to the project directory or file to parse
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+    /// Path to the project directory or file to parse, or a git URL to clone and analyze
     project_path: Option<PathBuf>,
-    /// Name of desired output file
-    #[arg(value_name = "gui | *.png | *.svg")]
+    /// Another file or directory to analyze along with the project path, as one graph (may be repeated)
+    #[arg(long = "path", value_name = "PATH")]
+    paths: Vec<PathBuf>,
+    /// Name of desired output file; `<stem>.csv` writes two tables, `<stem>.nodes.csv` and
+    /// `<stem>.edges.csv`
+    #[arg(value_name = "gui | *.png | *.svg | *.dot | *.gexf | *.json | *.mmd | *.html | *.csv")]
     output_filename: Option<String>,
     /// Enable verbose output
     #[arg(short, long)]
     verbose: bool,
+    /// Only print errors, and the reports asked for with flags like --stats
+    #[arg(short, long, conflicts_with = "verbose")]
+    quiet: bool,
     /// Show version information
     #[arg(short = 'V', long = "version")]
     version: bool,
     /// Ignore .gitignore files
     #[arg(long)]
     no_gitignore: bool,
+    /// Only analyze files staged in git (and the files they depend on)
+    #[arg(long)]
+    staged: bool,
+    /// Draw edges as curves in SVG exports
+    #[arg(long)]
+    curved_edges: bool,
+    /// Omit languages with fewer than this many files from the export legend
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    legend_min_count: usize,
+    /// How to place nodes in the GUI and SVG/PNG exports: circular, sugiyama, force, grid or radial
+    #[arg(long, value_name = "LAYOUT")]
+    layout: Option<String>,
+    /// Exclude files of this language from the analysis (may be repeated)
+    #[arg(long, value_name = "LANG")]
+    hide_language: Vec<String>,
+    /// Only analyze files of these languages, comma-separated (may be repeated)
+    #[arg(long, value_name = "LANGS", value_delimiter = ',')]
+    include_languages: Vec<String>,
+    /// Explain why an import was classified as local or external
+    #[arg(long, value_name = "IMPORT")]
+    explain_import: Option<String>,
+    /// Keep running and rebuild the graph whenever a source file changes
+    #[arg(long)]
+    watch: bool,
+    /// Skip files whose path relative to the project matches this glob (may be repeated)
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+    /// Only descend this many directories below the project root (0 is the root only)
+    #[arg(long, value_name = "N")]
+    max_depth: Option<usize>,
+    /// Truncate node labels longer than this many characters, in the GUI and exports
+    #[arg(long, value_name = "N")]
+    max_label_len: Option<usize>,
+    /// Skip files that look generated, such as protobuf or thrift output
+    #[arg(long)]
+    exclude_generated: bool,
+    /// JSON file mapping language names to extra file extensions, e.g. {"python": ["pyi"]}
+    #[arg(long, value_name = "FILE")]
+    language_config: Option<PathBuf>,
+    /// Print summary metrics and exit instead of launching the GUI or exporting
+    #[arg(long)]
+    stats: bool,
+    /// Show each file's number of outgoing dependencies in a badge on its node
+    #[arg(long)]
+    show_degree: bool,
+    /// Draw edges touching a node with more than this many edges without arrowheads
+    #[arg(long, value_name = "N")]
+    max_arrow_degree: Option<usize>,
+    /// Draw the files and dependencies added (green) or removed (red) since a git revision into the SVG output
+    #[arg(long, value_name = "REF")]
+    diff_visual: Option<String>,
+    /// Color dependencies that only test code has (test files, #[cfg(test)] modules) differently
+    #[arg(long)]
+    highlight_test_edges: bool,
+    /// What node colors stand for in the GUI: language or community
+    #[arg(long, value_name = "COLORING")]
+    color_by: Option<String>,
+    /// Heading drawn above the graph in SVG/PNG exports (none by default; the project name
+    /// when only --subtitle is given)
+    #[arg(long, value_name = "TEXT")]
+    title: Option<String>,
+    /// Line drawn under the title in SVG/PNG exports, e.g. a date or commit
+    #[arg(long, value_name = "TEXT")]
+    subtitle: Option<String>,
+    /// Multiply the size of PNG exports, e.g. 2 for a sharper image on high-DPI screens
+    #[arg(long, value_name = "FACTOR")]
+    scale: Option<f32>,
+    /// Push overlapping nodes apart after the layout, in the GUI and SVG/PNG exports
+    #[arg(long)]
+    no_overlap: bool,
+    /// Draw the directory tree as nested boxes behind the graph, in the GUI and SVG exports
+    #[arg(long)]
+    show_tree: bool,
+    /// Leave type-only imports (e.g. TypeScript `import type`) out of the graph
+    #[arg(long)]
+    no_type_imports: bool,
+    /// How many re-exports to follow past an imported barrel file (default 2, 0 to disable)
+    #[arg(long, value_name = "N")]
+    reexport_depth: Option<usize>,
+    /// Print files ranked by git churn times betweenness centrality and exit
+    #[arg(long)]
+    hotspots: bool,
+    /// Print directories ranked by the share of their dependencies that stay inside them and exit
+    #[arg(long)]
+    cohesion: bool,
+    /// Build the graph and report what would be exported or shown, without writing anything
+    #[arg(long)]
+    dry_run: bool,
+    /// Extensions to try first when resolving extensionless imports, e.g. ts=tsx,ts (may be repeated)
+    #[arg(long, value_name = "LANG=EXTS")]
+    extension_priority: Vec<String>,
+    /// Report dependencies that break the layer rules in this JSON file, failing if any do
+    #[arg(long, value_name = "FILE")]
+    check_layers: Option<PathBuf>,
+    /// Label DOT nodes with a table of the file name, LOC, and function/container counts
+    #[arg(long)]
+    rich_labels: bool,
+    /// Keep the temporary checkout when analyzing a git URL instead of deleting it
+    #[arg(long)]
+    keep_clone: bool,
+    /// Treat a project without supported files as an empty graph instead of an error
+    #[arg(long)]
+    allow_empty: bool,
+    /// Print how long each phase of the run took when it finishes
+    #[arg(long)]
+    profile: bool,
+    /// Parse every file instead of reusing unchanged ones from the parse cache
+    #[arg(long)]
+    no_cache: bool,
+    /// Delete the project's parse cache before analyzing it
+    #[arg(long)]
+    clear_cache: bool,
+}
+
+#[derive(Subcommand)]
+enum CliCommand {
+    /// Print what a single import resolves to, without building the whole graph
+    Resolve {
+        /// File the import is written in
+        from_file: PathBuf,
+        /// Import path as it appears in the file, e.g. crate::util or ./components/button
+        import_path: String,
+        /// Project root to build the module maps from
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        project: PathBuf,
+        /// JSON file mapping language names to extra file extensions, like the main command's
+        #[arg(long, value_name = "FILE")]
+        language_config: Option<PathBuf>,
+    },
 }
 
 impl Cli {
     fn validate(&self) -> Result<(), String> {
-        // Validate project path exists if provided
+        // Validate project path exists if provided; git URLs are checked when cloning
         if let Some(ref project_path) = self.project_path
             && !project_path.exists()
+            && !project_path.to_str().is_some_and(is_remote_url)
         {
             return Err(format!(
                 "The specified project path does not exist: {:?}",
                 project_path
             ));
         }
+        for path in &self.paths {
+            if !path.exists() {
+                return Err(format!("The specified path does not exist: {:?}", path));
+            }
+        }
+        if !self.paths.is_empty()
+            && self
+                .project_path
+                .as_deref()
+                .and_then(Path::to_str)
+                .is_some_and(is_remote_url)
+        {
+            return Err("--path cannot be combined with a git URL".into());
+        }
 
         // Validate output filename if provided
         if let Some(name) = &self.output_filename {
@@ -56,6 +224,44 @@ impl Cli {
             if name.contains(std::path::MAIN_SEPARATOR) {
                 return Err("Output filename cannot contain path separators".into());
             }
+            if name != "gui" {
+                OutputFormat::from_path(Path::new(name))?;
+            }
+        }
+
+        build_exclude_set(&self.exclude)?;
+
+        for name in self.hide_language.iter().chain(&self.include_languages) {
+            if Language::from_name(name).is_none() {
+                return Err(format!("Unknown language: {name}"));
+            }
+        }
+
+        if let Some(layout) = &self.layout {
+            LayoutType::from_str(layout)?;
+        }
+
+        if let Some(color_by) = &self.color_by {
+            ColorBy::from_str(color_by)?;
+        }
+
+        if self
+            .scale
+            .is_some_and(|scale| !scale.is_finite() || scale <= 0.0)
+        {
+            return Err("--scale must be a positive number".into());
+        }
+
+        if self.diff_visual.is_some()
+            && !self.output_filename.as_deref().is_some_and(|name| {
+                OutputFormat::from_path(Path::new(name)) == Ok(OutputFormat::Svg)
+            })
+        {
+            return Err("--diff-visual needs an .svg output path".into());
+        }
+
+        for spec in &self.extension_priority {
+            parse_extension_priority(spec)?;
         }
 
         Ok(())
@@ -70,14 +276,8 @@ fn main() {
         std::process::exit(1);
     }
 
-    let verbose = args.verbose;
-
     match run(args) {
-        Ok(_) => {
-            if verbose {
-                println!("Operation completed successfully.");
-            }
-        }
+        Ok(_) => detail!("Operation completed successfully."),
         Err(msg) => {
             eprintln!("Error: {msg}");
             std::process::exit(1);
@@ -85,24 +285,81 @@ fn main() {
     }
 }
 
-fn detect_file_language(
-    target_file: PathBuf,
-    language_files: &mut HashMap<PathBuf, Language>,
-    detected_langs: &mut HashSet<Language>,
-) {
-    if let Some(file_language) = Language::from_file(target_file.to_str().unwrap()) {
-        language_files.insert(target_file.clone(), file_language);
-        detected_langs.insert(file_language);
+fn run(args: Cli) -> Result<(), String> {
+    log::set_level(LogLevel::from_flags(args.quiet, args.verbose));
+
+    if let Some(CliCommand::Resolve {
+        from_file,
+        import_path,
+        project,
+        language_config,
+    }) = &args.command
+    {
+        let extension_map = load_extension_map(language_config.as_deref())?;
+        println!(
+            "{}",
+            resolve_single_import(project, from_file, import_path, &extension_map)?
+        );
+        return Ok(());
     }
+
+    let show_profile = args.profile;
+    let mut profile = Profile::default();
+    let result = run_profiled(args, &mut profile);
+    if show_profile && result.is_ok() {
+        print!("{}", profile.report());
+    }
+    result
 }
 
-fn run(args: Cli) -> Result<(), String> {
+/// Do the work of `run`, recording the time spent in each phase into `profile`
+fn run_profiled(args: Cli, profile: &mut Profile) -> Result<(), String> {
     let Cli {
+        command: _,
         project_path: provided_path,
+        paths,
         output_filename: output,
-        verbose,
+        verbose: _,
+        quiet: _,
         version,
         no_gitignore,
+        staged,
+        curved_edges,
+        legend_min_count,
+        layout,
+        hide_language,
+        include_languages,
+        explain_import,
+        watch,
+        exclude,
+        max_depth,
+        max_label_len,
+        exclude_generated,
+        language_config,
+        stats,
+        show_degree,
+        max_arrow_degree,
+        diff_visual,
+        highlight_test_edges,
+        color_by,
+        title,
+        subtitle,
+        scale,
+        no_overlap,
+        show_tree,
+        no_type_imports,
+        reexport_depth,
+        hotspots,
+        cohesion,
+        dry_run,
+        extension_priority,
+        check_layers,
+        rich_labels,
+        keep_clone,
+        allow_empty,
+        profile: _,
+        no_cache,
+        clear_cache,
     } = args;
 
     if version {
@@ -110,8 +367,28 @@ fn run(args: Cli) -> Result<(), String> {
         return Ok(());
     }
 
+    // Clone remote repositories first; the checkout is cleaned up when `run` returns
+    let remote_url = provided_path
+        .as_deref()
+        .and_then(Path::to_str)
+        .filter(|target| is_remote_url(target));
+    let checkout = match remote_url {
+        Some(url) => {
+            info!("Cloning {url}");
+            Some(clone_remote(url, keep_clone)?)
+        }
+        None => None,
+    };
+    if keep_clone && let Some(checkout) = &checkout {
+        info!("Keeping clone at {}", checkout.path().display());
+    }
+
     // Get the project path, using current directory as default
-    let project_path = match provided_path {
+    let project_path = match checkout
+        .as_ref()
+        .map(|c| c.path())
+        .or(provided_path.as_deref())
+    {
         Some(path) => path
             .canonicalize()
             .map_err(|e| format!("Failed to canonicalize path: {e}"))?,
@@ -120,60 +397,365 @@ fn run(args: Cli) -> Result<(), String> {
         }
     };
 
-    if verbose {
-        println!("Processing path: {}", project_path.display());
+    // Extra paths are analyzed together with the project path, resolving imports
+    // against the directory that contains all of them
+    let include_paths = if paths.is_empty() {
+        Vec::new()
+    } else {
+        std::iter::once(Ok(project_path.clone()))
+            .chain(paths.iter().map(|path| {
+                path.canonicalize()
+                    .map_err(|e| format!("Failed to canonicalize path: {e}"))
+            }))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    let project_path = common_ancestor(&include_paths).unwrap_or(project_path);
+
+    detail!("Processing path: {}", project_path.display());
+
+    // A subtitle alone still gets a heading, naming the project
+    let title = title.or_else(|| {
+        subtitle
+            .as_ref()
+            .map(|_| project_name(remote_url, &project_path))
+    });
+
+    let cache_file = project_cache_file(&project_path);
+    if clear_cache && let Some(cache_file) = &cache_file {
+        match std::fs::remove_file(cache_file) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to clear the parse cache: {e}")),
+        }
+    }
+    // Temporary clones live at a new path every run, so caching them only leaves litter
+    let cache_file = cache_file.filter(|_| !no_cache && checkout.is_none());
+
+    let extension_map = load_extension_map(language_config.as_deref())?;
+    let extension_priority = extension_priority
+        .iter()
+        .map(|spec| parse_extension_priority(spec))
+        .collect::<Result<_, _>>()?;
+    let layer_rules = check_layers.as_deref().map(LayerRules::load).transpose()?;
+    let layout = layout
+        .as_deref()
+        .map(LayoutType::from_str)
+        .transpose()?
+        .unwrap_or_default();
+    let color_by = color_by
+        .as_deref()
+        .map(ColorBy::from_str)
+        .transpose()?
+        .unwrap_or_default();
+
+    let settings = BuildSettings {
+        project_path,
+        staged,
+        explain_import,
+        options: AnalyzeOptions {
+            no_gitignore,
+            exclude,
+            max_depth,
+            hidden_languages: hide_language
+                .iter()
+                .filter_map(|name| Language::from_name(name))
+                .collect(),
+            included_languages: include_languages
+                .iter()
+                .filter_map(|name| Language::from_name(name))
+                .collect(),
+            exclude_generated,
+            include_paths,
+            skip_type_imports: no_type_imports,
+            reexport_depth,
+            extension_map,
+            extension_priority,
+            allow_empty,
+            cache_file,
+        },
+    };
+    let project = analyze(&settings, profile)?;
+    let (graph_nodes, detected_languages) = project_graph_nodes(&settings, &project)?;
+
+    if stats {
+        let report = profile.time("analysis", || {
+            format_stats(&graph_nodes, &settings.project_path)
+        });
+        print!("{report}");
+        return Ok(());
     }
 
-    // Detect languages in file/project
-    let mut language_files: HashMap<PathBuf, Language> = HashMap::new();
-    let files_to_process = walk_directory(&project_path, no_gitignore);
-    let detected_languages = detect_project_languages(&files_to_process, &mut language_files)
-        .ok_or_else(|| "No supported language files found in the project".to_string())?;
+    if hotspots {
+        let churn = get_file_churn(&settings.project_path)?;
+        let report = profile.time("analysis", || {
+            format_hotspots(&graph_nodes, &settings.project_path, &churn)
+        });
+        print!("{report}");
+        return Ok(());
+    }
 
-    // Parse files and collect Nodes, indexed by file path
-    let mut node_map: HashMap<PathBuf, FileNode> = HashMap::new();
-    for (file_path, lang) in &language_files {
-        match lang {
-            Language::Python => {
-                if let Some(node) = parse_python_file(file_path) {
-                    if verbose {
-                        println!("Parsed Python file: {}", file_path.display());
-                    }
-                    node_map.insert(file_path.clone(), node);
-                }
-            }
-            Language::Rust => {
-                if let Some(node) = parse_rust_file(file_path) {
-                    if verbose {
-                        println!("Parsed Rust file: {}", file_path.display());
+    if cohesion {
+        let report = profile.time("analysis", || {
+            format_cohesion(&graph_nodes, &settings.project_path)
+        });
+        print!("{report}");
+        return Ok(());
+    }
+
+    if let Some(rules) = layer_rules {
+        return profile.time("analysis", || {
+            check_layer_rules(&rules, &graph_nodes, &settings.project_path)
+        });
+    }
+
+    if dry_run {
+        print!("{}", describe_dry_run(output.as_deref(), &graph_nodes)?);
+        return Ok(());
+    }
+
+    if let (Some(rev), Some(filename)) = (diff_visual, output.as_deref()) {
+        let export_options = ExportOptions {
+            curved_edges,
+            legend_min_count,
+            max_label_len,
+            show_degree,
+            layout,
+            max_arrow_degree,
+            highlight_test_edges,
+            title,
+            subtitle,
+            scale,
+            no_overlap,
+            show_tree,
+            ..Default::default()
+        };
+        return export_diff_visual(
+            &rev,
+            filename,
+            &settings,
+            &graph_nodes,
+            detected_languages,
+            export_options,
+        );
+    }
+
+    // Start watching before handing off to the GUI or exporter so no edits are missed
+    let watcher = if watch {
+        let watch_root = if settings.project_path.is_file() {
+            settings
+                .project_path
+                .parent()
+                .unwrap_or(&settings.project_path)
+        } else {
+            &settings.project_path
+        };
+        Some(ProjectWatcher::new(
+            watch_root,
+            DEFAULT_DEBOUNCE,
+            &settings.options.extension_map,
+        )?)
+    } else {
+        None
+    };
+    // Only explain imports once, not on every rebuild
+    let rebuild_settings = BuildSettings {
+        explain_import: None,
+        ..settings
+    };
+
+    // Outside a git repository nothing counts as modified
+    let modified_files = get_modified_files(&rebuild_settings.project_path).unwrap_or_default();
+    let mut export_options = ExportOptions {
+        curved_edges,
+        legend_min_count,
+        max_label_len,
+        show_degree,
+        rich_labels,
+        modified_files: modified_files.clone(),
+        layout,
+        max_arrow_degree,
+        diff: None,
+        highlight_test_edges,
+        title,
+        subtitle,
+        scale,
+        no_overlap,
+        show_tree,
+    };
+    let gui_options = GuiOptions {
+        max_label_len,
+        show_degree,
+        modified_files,
+        layout,
+        max_arrow_degree,
+        highlight_test_edges,
+        color_by,
+        no_overlap,
+        show_tree,
+    };
+
+    // launch the visualization or export if specified
+    match output.as_deref() {
+        Some(filename) if filename != "gui" => {
+            export_graph(
+                filename,
+                &graph_nodes,
+                detected_languages,
+                &export_options,
+                profile,
+            )?;
+
+            if let Some(watcher) = watcher {
+                info!("Watching for changes, press Ctrl+C to stop");
+                let mut project = project;
+                loop {
+                    let changed = watcher.wait_for_change()?;
+                    // Keep watching through transient errors, e.g. a half-saved file
+                    let result = rebuild_graph(
+                        &rebuild_settings,
+                        &mut project,
+                        &changed,
+                        &mut export_options.modified_files,
+                    )
+                    .and_then(|(nodes, langs)| {
+                        export_graph(
+                            filename,
+                            &nodes,
+                            langs,
+                            &export_options,
+                            &mut Profile::default(),
+                        )
+                    });
+                    match result {
+                        Ok(()) => info!("Rebuilt {filename}"),
+                        Err(msg) => eprintln!("Error: {msg}"),
                     }
-                    node_map.insert(file_path.clone(), node);
                 }
             }
-            Language::TypeScript => {
-                if let Some(node) = parse_typescript_file(file_path) {
-                    if verbose {
-                        println!("Parsed TypeScript file: {}", file_path.display());
-                    }
-                    node_map.insert(file_path.clone(), node);
-                }
+        }
+        gui => {
+            // Default to GUI if no output specified
+            if gui.is_none() && cfg!(test) {
+                return Ok(());
             }
-            Language::Cpp => {
-                if let Some(node) = parse_cpp_file(file_path) {
-                    if verbose {
-                        println!("Parsed C++ file: {}", file_path.display());
-                    }
-                    node_map.insert(file_path.clone(), node);
+
+            match watcher {
+                Some(watcher) => {
+                    let (sender, updates) = channel();
+                    let mut project = project;
+                    let mut modified_files = gui_options.modified_files.clone();
+                    thread::spawn(move || {
+                        while let Ok(changed) = watcher.wait_for_change() {
+                            match rebuild_graph(
+                                &rebuild_settings,
+                                &mut project,
+                                &changed,
+                                &mut modified_files,
+                            ) {
+                                Ok((nodes, _)) => {
+                                    if sender.send((nodes, modified_files.clone())).is_err() {
+                                        break; // GUI was closed
+                                    }
+                                }
+                                Err(msg) => eprintln!("Error: {msg}"),
+                            }
+                        }
+                    });
+                    run_gui_with_updates(graph_nodes, &gui_options, updates);
                 }
+                None => run_gui(graph_nodes, &gui_options),
             }
         }
     }
 
-    // Build GraphNodes with multi-language support
-    let mut graph_builder = GraphBuilder::new();
-    let graph_nodes = graph_builder.build_graph_edges(&node_map, &project_path);
+    Ok(())
+}
+
+/// Settings that control how the project graph is built, kept around so watch mode can rebuild it
+struct BuildSettings {
+    project_path: PathBuf,
+    staged: bool,
+    explain_import: Option<String>,
+    /// How the library walks, parses, and resolves the project
+    options: AnalyzeOptions,
+}
+
+/// Detect, parse, and resolve the project into graph nodes
+fn build_graph(settings: &BuildSettings) -> Result<(Vec<GraphNode>, HashSet<Language>), String> {
+    build_graph_profiled(settings, &mut Profile::default())
+}
 
-    if verbose {
+/// Like `build_graph`, recording the time spent in each phase into `profile`
+fn build_graph_profiled(
+    settings: &BuildSettings,
+    profile: &mut Profile,
+) -> Result<(Vec<GraphNode>, HashSet<Language>), String> {
+    project_graph_nodes(settings, &analyze(settings, profile)?)
+}
+
+/// Detect, parse, and resolve the project, answering `--explain-import` along the way
+fn analyze(settings: &BuildSettings, profile: &mut Profile) -> Result<ProjectGraph, String> {
+    let project = build_project(&settings.project_path, &settings.options)?;
+    profile.merge(&project.profile);
+
+    if let Some(import_path) = &settings.explain_import {
+        for line in
+            explain_import_resolution(import_path, &project.node_map, &project.graph_builder)
+        {
+            println!("{line}");
+        }
+    }
+    Ok(project)
+}
+
+/// Bring `project` up to date after the `changed` files were written, for watch mode. Only
+/// those files are parsed again, unless a new one appeared and the project must be walked.
+/// `modified_files` is refreshed too, since the write changed what git reports.
+fn rebuild_graph(
+    settings: &BuildSettings,
+    project: &mut ProjectGraph,
+    changed: &HashSet<PathBuf>,
+    modified_files: &mut HashSet<PathBuf>,
+) -> Result<(Vec<GraphNode>, HashSet<Language>), String> {
+    *modified_files = get_modified_files(&settings.project_path).unwrap_or_default();
+    if project.update_files(&settings.project_path, changed, parse_file) {
+        for file in changed {
+            detail!("Updated changed file: {}", file.display());
+        }
+    } else {
+        *project = analyze(settings, &mut Profile::default())?;
+    }
+    project_graph_nodes(settings, project)
+}
+
+/// The graph nodes of `project` to show: only staged files and their dependencies with
+/// `--staged`, printed in detail with `--verbose`
+fn project_graph_nodes(
+    settings: &BuildSettings,
+    project: &ProjectGraph,
+) -> Result<(Vec<GraphNode>, HashSet<Language>), String> {
+    let BuildSettings {
+        project_path,
+        staged,
+        ..
+    } = settings;
+    let ProjectGraph {
+        graph_nodes,
+        detected_languages,
+        graph_builder,
+        ..
+    } = project;
+    let mut graph_nodes = graph_nodes.clone();
+    let detected_languages = detected_languages.clone();
+
+    // Narrow the graph down to staged files, resolved against the full working tree
+    if *staged {
+        let staged_files = get_staged_files(project_path, &settings.options.extension_map)?;
+        info!("Found {} staged file(s)", staged_files.len());
+        graph_nodes = filter_to_staged(graph_nodes, &staged_files);
+    }
+
+    if log::enabled(LogLevel::Verbose) {
         println!("\nResolved {} nodes with connections:", graph_nodes.len());
         for gnode in &graph_nodes {
             println!(
@@ -194,135 +776,890 @@ fn run(args: Cli) -> Result<(), String> {
             }
             println!();
         }
-    }
 
-    // launch the visualization or export if specified
-    if let Some(filename) = output {
-        match filename.as_str() {
-            "gui" => {
-                run_gui(graph_nodes);
-                return Ok(());
-            }
-            filename if filename.ends_with(".svg") => {
-                if verbose {
-                    println!("Exporting graph to SVG: {filename}");
-                }
-                export::export_graph_as_svg(
-                    &graph_nodes,
-                    &PathBuf::from(filename),
-                    detected_languages,
-                )
-                .map_err(|e| format!("Failed to export SVG: {e}"))?;
-                if verbose {
-                    println!("Successfully exported to {filename}");
+        // Local imports that vanished because no file could be found for them
+        let mut unresolved: Vec<_> = graph_builder.unresolved_imports().iter().collect();
+        unresolved.sort();
+        if !unresolved.is_empty() {
+            println!("Unresolved local imports:");
+            for (file, imports) in unresolved {
+                println!("  {}:", file.display());
+                for import in imports {
+                    println!("    ? {import}");
                 }
             }
-            filename if filename.ends_with(".png") => {
-                if verbose {
-                    println!("Exporting graph to PNG: {filename}");
-                }
-                export::export_graph_as_png(
-                    &graph_nodes,
-                    &PathBuf::from(filename),
-                    detected_languages,
+            println!();
+        }
+    }
+
+    Ok((graph_nodes, detected_languages))
+}
+
+/// Build the `--stats` report: one `key: value` line per metric, in a fixed order
+fn format_stats(graph_nodes: &[GraphNode], project_path: &Path) -> String {
+    // Sort by path so node indices, and every ordering built from them, are stable across runs
+    let mut graph_nodes = graph_nodes.to_vec();
+    graph_nodes.sort_by(|a, b| a.data().file().cmp(b.data().file()));
+    let graph_nodes = graph_nodes.as_slice();
+
+    let root = if project_path.is_file() {
+        project_path.parent().unwrap_or(project_path)
+    } else {
+        project_path
+    };
+    let display_path = |i: usize| {
+        let file = graph_nodes[i].data().file();
+        file.strip_prefix(root)
+            .unwrap_or(file)
+            .display()
+            .to_string()
+    };
+
+    let mut lines = vec![format!("files: {}", graph_nodes.len())];
+
+    let mut language_counts: HashMap<&str, usize> = HashMap::new();
+    for node in graph_nodes {
+        *language_counts
+            .entry(node.data().language().to_string())
+            .or_default() += 1;
+    }
+    let mut language_counts: Vec<_> = language_counts.into_iter().collect();
+    language_counts.sort();
+    for (language, count) in language_counts {
+        lines.push(format!("files[{language}]: {count}"));
+    }
+
+    let loc: u64 = graph_nodes.iter().map(|n| n.data().loc() as u64).sum();
+    lines.push(format!("loc: {loc}"));
+
+    let edges = build_edge_list(graph_nodes);
+    lines.push(format!("edges: {}", edges.len()));
+
+    let analysis = GraphAnalysis::analyze_graph(&build_dependency_graph(graph_nodes));
+    lines.push(format!("sccs: {}", analysis.scc_sizes.len()));
+    lines.push(format!("largest_scc: {}", analysis.largest_scc_size));
+    let mut scc_sizes: Vec<_> = analysis
+        .sccs_by_size
+        .iter()
+        .map(|(size, sccs)| (*size, sccs.len()))
+        .collect();
+    scc_sizes.sort_by_key(|&(size, _)| std::cmp::Reverse(size));
+    let scc_sizes: Vec<String> = scc_sizes
+        .iter()
+        .map(|(size, count)| format!("{size}x{count}"))
+        .collect();
+    lines.push(format!("scc_sizes: {}", scc_sizes.join(", ")));
+    lines.push(format!(
+        "avg_clustering: {:.3}",
+        analysis.average_clustering
+    ));
+    lines.push(format!("density: {:.3}", analysis.graph_density()));
+    lines.push(format!("communities: {}", analysis.community_count));
+    lines.push(format!("modularity: {:.3}", analysis.modularity));
+    if let Some(locality) = average_import_locality(graph_nodes) {
+        lines.push(format!("avg_import_locality: {locality:.3}"));
+    }
+
+    // Ties are broken by path so the output doesn't depend on hash order
+    let mut betweenness: Vec<(usize, f64)> = analysis
+        .betweenness_centrality
+        .iter()
+        .map(|(idx, score)| (idx.index(), *score))
+        .filter(|&(_, score)| score > 0.0)
+        .collect();
+    betweenness.sort_by(|a, b| {
+        b.1.total_cmp(&a.1)
+            .then_with(|| display_path(a.0).cmp(&display_path(b.0)))
+    });
+    for (rank, (i, score)) in betweenness.iter().take(5).enumerate() {
+        lines.push(format!(
+            "top_betweenness[{}]: {} {score:.3}",
+            rank + 1,
+            display_path(*i)
+        ));
+    }
+
+    for (name, degrees) in [
+        ("fan_in", &analysis.in_degree),
+        ("fan_out", &analysis.out_degree),
+    ] {
+        let mut degrees: Vec<(usize, usize)> = degrees
+            .iter()
+            .map(|(idx, &count)| (idx.index(), count))
+            .filter(|&(_, count)| count > 0)
+            .collect();
+        degrees.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| display_path(a.0).cmp(&display_path(b.0)))
+        });
+        for (rank, (i, count)) in degrees.iter().take(5).enumerate() {
+            lines.push(format!(
+                "top_{name}[{}]: {} {count}",
+                rank + 1,
+                display_path(*i)
+            ));
+        }
+    }
+
+    let order = match analysis.topological_order() {
+        Some(order) => order
+            .iter()
+            .map(|node| display_path(node.index()))
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => "none (the graph has cycles)".to_string(),
+    };
+    lines.push(format!("suggested_order: {order}"));
+
+    lines.join("\n") + "\n"
+}
+
+/// Print every dependency that breaks the layer rules, failing if there are any
+fn check_layer_rules(
+    rules: &LayerRules,
+    graph_nodes: &[GraphNode],
+    project_path: &Path,
+) -> Result<(), String> {
+    let root = if project_path.is_file() {
+        project_path.parent().unwrap_or(project_path)
+    } else {
+        project_path
+    };
+
+    let violations = rules.find_violations(graph_nodes, root);
+    if violations.is_empty() {
+        println!("No layering violations found");
+        return Ok(());
+    }
+
+    for violation in &violations {
+        println!(
+            "{} ({}) -> {} ({})",
+            violation
+                .from
+                .strip_prefix(root)
+                .unwrap_or(&violation.from)
+                .display(),
+            violation.from_layer,
+            violation
+                .to
+                .strip_prefix(root)
+                .unwrap_or(&violation.to)
+                .display(),
+            violation.to_layer,
+        );
+    }
+    Err(format!("{} layering violation(s) found", violations.len()))
+}
+
+/// Output formats supported by `export_graph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Svg,
+    Png,
+    Dot,
+    Json,
+    Gexf,
+    Mermaid,
+    Html,
+    Csv,
+}
+
+impl OutputFormat {
+    /// File extensions recognized for each format, in the order they are listed in errors
+    const EXTENSIONS: &[(&str, OutputFormat)] = &[
+        ("svg", OutputFormat::Svg),
+        ("png", OutputFormat::Png),
+        ("dot", OutputFormat::Dot),
+        ("json", OutputFormat::Json),
+        ("gexf", OutputFormat::Gexf),
+        ("mmd", OutputFormat::Mermaid),
+        ("mermaid", OutputFormat::Mermaid),
+        ("html", OutputFormat::Html),
+        ("csv", OutputFormat::Csv),
+    ];
+
+    /// Pick the format from the output file's extension, ignoring case
+    fn from_path(path: &Path) -> Result<Self, String> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        Self::EXTENSIONS
+            .iter()
+            .find(|(ext, _)| extension.as_deref() == Some(*ext))
+            .map(|&(_, format)| format)
+            .ok_or_else(|| {
+                let supported: Vec<String> = Self::EXTENSIONS
+                    .iter()
+                    .map(|(ext, _)| format!(".{ext}"))
+                    .collect();
+                format!(
+                    "Unsupported output format: {} (supported extensions: {})",
+                    path.display(),
+                    supported.join(", ")
                 )
-                .map_err(|e| format!("Failed to export PNG: {e}"))?;
-                if verbose {
-                    println!("Successfully exported to {filename}");
-                }
+            })
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::Svg => "SVG",
+            OutputFormat::Png => "PNG",
+            OutputFormat::Dot => "DOT",
+            OutputFormat::Json => "JSON",
+            OutputFormat::Gexf => "GEXF",
+            OutputFormat::Mermaid => "Mermaid",
+            OutputFormat::Html => "HTML",
+            OutputFormat::Csv => "CSV",
+        }
+    }
+}
+
+/// Write the graph to `filename`, picking the format from its extension
+fn export_graph(
+    filename: &str,
+    graph_nodes: &[GraphNode],
+    detected_languages: HashSet<Language>,
+    export_options: &ExportOptions,
+    profile: &mut Profile,
+) -> Result<(), String> {
+    let path = PathBuf::from(filename);
+    let format = OutputFormat::from_path(&path)?;
+    detail!("Exporting graph to {}: {filename}", format.name());
+
+    // Lay SVG/PNG exports out up front, so the layout is timed on its own
+    let positions = matches!(format, OutputFormat::Svg | OutputFormat::Png).then(|| {
+        profile.time("layout", || {
+            export::auto_positions(graph_nodes, export_options.layout)
+        })
+    });
+
+    let start = Instant::now();
+    let result = match format {
+        OutputFormat::Svg => export::export_graph_as_svg(
+            graph_nodes,
+            &path,
+            detected_languages,
+            export_options,
+            positions.as_ref(),
+        ),
+        OutputFormat::Png => export::export_graph_as_png(
+            graph_nodes,
+            &path,
+            detected_languages,
+            export_options,
+            positions.as_ref(),
+        ),
+        OutputFormat::Dot => {
+            export::export_graph_as_dot(graph_nodes, &path, detected_languages, export_options)
+        }
+        OutputFormat::Json => export::export_graph_as_json(graph_nodes, &path),
+        OutputFormat::Gexf => export::export_graph_as_gexf(graph_nodes, &path),
+        OutputFormat::Mermaid => {
+            export::export_graph_as_mermaid(graph_nodes, &path, detected_languages)
+        }
+        OutputFormat::Html => {
+            export::export_graph_as_html(graph_nodes, &path, detected_languages, export_options)
+        }
+        OutputFormat::Csv => export::export_graph_as_csv(graph_nodes, &path),
+    };
+    profile.record("export", start.elapsed());
+    result.map_err(|e| format!("Failed to export {}: {e}", format.name()))?;
+
+    if format == OutputFormat::Csv {
+        let (nodes, edges) = export::csv_output_paths(&path);
+        info!(
+            "Successfully exported to {} and {}",
+            nodes.display(),
+            edges.display()
+        );
+    } else {
+        info!("Successfully exported to {filename}");
+    }
+    Ok(())
+}
+
+/// Build the project as of git revision `rev`, and write an SVG of the current graph with
+/// the files and dependencies added since then in green and the removed ones ghosted in red
+fn export_diff_visual(
+    rev: &str,
+    filename: &str,
+    settings: &BuildSettings,
+    graph_nodes: &[GraphNode],
+    mut detected_languages: HashSet<Language>,
+    mut export_options: ExportOptions,
+) -> Result<(), String> {
+    let project_dir = if settings.project_path.is_file() {
+        settings
+            .project_path
+            .parent()
+            .unwrap_or(&settings.project_path)
+    } else {
+        &settings.project_path
+    };
+    let repo_root = PathBuf::from(run_git(project_dir, &["rev-parse", "--show-toplevel"])?.trim())
+        .canonicalize()
+        .map_err(|e| format!("Failed to canonicalize path: {e}"))?;
+    let relative = settings
+        .project_path
+        .strip_prefix(&repo_root)
+        .map_err(|_| "The project is not inside its git repository".to_string())?;
+
+    info!("Checking out {rev} to compare against");
+    let checkout = checkout_revision(&repo_root, rev)?;
+    let old_root = checkout
+        .path()
+        .canonicalize()
+        .map_err(|e| format!("Failed to canonicalize path: {e}"))?
+        .join(relative);
+    let old_settings = BuildSettings {
+        project_path: old_root.clone(),
+        staged: false,
+        explain_import: None,
+        options: AnalyzeOptions {
+            // The old revision may have no supported files yet
+            allow_empty: true,
+            // Its checkout is temporary, like a clone's
+            cache_file: None,
+            include_paths: settings
+                .options
+                .include_paths
+                .iter()
+                .filter_map(|path| path.strip_prefix(&settings.project_path).ok())
+                .map(|path| old_root.join(path))
+                .collect(),
+            ..settings.options.clone()
+        },
+    };
+    let (old_nodes, old_languages) = build_graph(&old_settings)?;
+
+    let diff = graph_diff(&old_nodes, &old_root, graph_nodes, &settings.project_path);
+    info!(
+        "Since {rev}: +{} -{} files, +{} -{} dependencies",
+        diff.added_files.len(),
+        diff.removed_files.len(),
+        diff.added_edges.len(),
+        diff.removed_edges.len()
+    );
+    let overlay = overlay_nodes(
+        &old_nodes,
+        &old_root,
+        graph_nodes,
+        &settings.project_path,
+        &diff,
+    );
+    detected_languages.extend(old_languages);
+    export_options.diff = Some(diff);
+
+    export::export_graph_as_svg(
+        &overlay,
+        Path::new(filename),
+        detected_languages,
+        &export_options,
+        None,
+    )
+    .map_err(|e| format!("Failed to export SVG: {e}"))?;
+    info!("Successfully exported to {filename}");
+    Ok(())
+}
+
+/// Deepest directory containing every one of `paths`, or `None` if there are none
+fn common_ancestor(paths: &[PathBuf]) -> Option<PathBuf> {
+    let dir = |path: &PathBuf| {
+        if path.is_file() {
+            path.parent().unwrap_or(path).to_path_buf()
+        } else {
+            path.clone()
+        }
+    };
+    let (first, rest) = paths.split_first()?;
+    let mut ancestor = dir(first);
+    for path in rest {
+        while !path.starts_with(&ancestor) {
+            if !ancestor.pop() {
+                break;
             }
-            _ => {
-                return Err(format!("Unsupported output format: {filename}"));
+        }
+    }
+    Some(ancestor)
+}
+
+/// Name of the analyzed project: its directory name, or the repository name of a git URL
+fn project_name(remote_url: Option<&str>, project_path: &Path) -> String {
+    let name = match remote_url {
+        Some(url) => url.trim_end_matches('/').rsplit(['/', ':']).next(),
+        None => project_path.file_name().and_then(|name| name.to_str()),
+    };
+    let name = name.unwrap_or_default();
+    name.strip_suffix(".git").unwrap_or(name).to_string()
+}
+
+/// Describe what a run would produce for `output`, for `--dry-run`
+fn describe_dry_run(output: Option<&str>, graph_nodes: &[GraphNode]) -> Result<String, String> {
+    let action = match output {
+        Some(filename) if filename != "gui" => {
+            let format = OutputFormat::from_path(Path::new(filename))?;
+            format!("Would export {} to {filename}", format.name())
+        }
+        _ => "Would launch the GUI".to_string(),
+    };
+    Ok(format!(
+        "Dry run, nothing was written\n{action}\nnodes: {}\nedges: {}\n",
+        graph_nodes.len(),
+        build_edge_list(graph_nodes).len()
+    ))
+}
+
+/// Describe how every occurrence of `import_path` in the project was classified and resolved
+fn explain_import_resolution(
+    import_path: &str,
+    node_map: &HashMap<PathBuf, FileNode>,
+    graph_builder: &GraphBuilder,
+) -> Vec<String> {
+    let mut files: Vec<&PathBuf> = node_map.keys().collect();
+    files.sort();
+
+    let mut lines = Vec::new();
+    for file in files {
+        let node = &node_map[file];
+        // Python stores relative imports without their leading dots
+        let Some(import) = node.imports().iter().find(|import| {
+            import.path() == import_path || import.path() == import_path.trim_start_matches('.')
+        }) else {
+            continue;
+        };
+
+        let language = *node.language();
+        let mut line = format!(
+            "{} imports `{}`: {}",
+            file.display(),
+            import_path,
+            explain_import_classification(language, import, file)
+        );
+        if import.is_local() {
+            match graph_builder.resolve_import(language, import.path(), file) {
+                Some(target) => line.push_str(&format!(", resolved to {}", target.display())),
+                None => line.push_str(", but no matching file was found"),
             }
         }
+        lines.push(line);
+    }
+
+    if lines.is_empty() {
+        lines.push(format!("No file imports `{import_path}`"));
+    }
+    lines
+}
+
+/// Extra file extensions from `--language-config`, if given
+fn load_extension_map(language_config: Option<&Path>) -> Result<HashMap<String, Language>, String> {
+    match language_config {
+        Some(path) => Ok(language_extension_map(&load_language_extensions(path)?)),
+        None => Ok(HashMap::new()),
+    }
+}
+
+/// Resolve `import_path`, as written in `from_file`, with the resolver graph building
+/// would use, for the `resolve` subcommand. Only the module maps are built from the
+/// project's file paths; nothing is parsed.
+fn resolve_single_import(
+    project_root: &Path,
+    from_file: &Path,
+    import_path: &str,
+    extension_map: &HashMap<String, Language>,
+) -> Result<String, String> {
+    // Resolvers match files by path, so both sides must be spelled the same way
+    let canonical = |path: &Path| {
+        path.canonicalize()
+            .map_err(|e| format!("Cannot read {}: {e}", path.display()))
+    };
+    let project_root = canonical(project_root)?;
+    let from_file = canonical(from_file)?;
+    let language = from_file
+        .to_str()
+        .and_then(|path| Language::from_file_with_config(path, extension_map))
+        .ok_or_else(|| format!("Unsupported file type: {}", from_file.display()))?;
+
+    let mut language_files = HashMap::new();
+    let files = walk_directory(&project_root, false, &[], None);
+    detect_project_languages(&files, extension_map, &mut language_files);
+    language_files.insert(from_file.clone(), language);
+    let mut files_by_language: HashMap<Language, Vec<PathBuf>> = HashMap::new();
+    for (file, language) in language_files {
+        files_by_language.entry(language).or_default().push(file);
+    }
+    let mut graph_builder = GraphBuilder::new();
+    graph_builder.build_module_maps(&files_by_language, &project_root);
+
+    // Mirror the parsers: relative Python imports lose their dots, quoted C++ includes are local
+    let import = match language {
+        Language::Python if import_path.starts_with('.') => {
+            Import::new(import_path.trim_start_matches('.').to_string(), true)
+        }
+        Language::Cpp => Import::new(import_path.to_string(), true),
+        _ => Import::new(import_path.to_string(), false),
+    };
+    let mut line = format!(
+        "{} imports `{}`: {}",
+        from_file.display(),
+        import_path,
+        explain_import_classification(language, &import, &from_file)
+    );
+    match graph_builder.resolve_import(language, import.path(), &from_file) {
+        Some(target) => line.push_str(&format!(", resolved to {}", target.display())),
+        None => line.push_str(&format!(
+            ", but no matching file was found among {} {} files",
+            files_by_language[&language].len(),
+            language.to_string()
+        )),
+    }
+    Ok(line)
+}
+
+/// Run git in `repo_dir` and return its stdout
+fn run_git(repo_dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// How far back `--hotspots` looks when counting commits per file
+const CHURN_WINDOW: &str = "1 year ago";
+
+/// Count the commits within `CHURN_WINDOW` that touched each file of the git repository
+/// containing `path`, keyed by absolute path. Files that no longer exist are skipped.
+fn get_file_churn(path: &Path) -> Result<HashMap<PathBuf, usize>, String> {
+    let repo_dir = if path.is_file() {
+        path.parent().unwrap_or(path)
     } else {
-        // Default to GUI if no output specified
-        #[cfg(not(test))]
-        {
-            run_gui(graph_nodes);
+        path
+    };
+
+    let repo_root = PathBuf::from(run_git(repo_dir, &["rev-parse", "--show-toplevel"])?.trim());
+    let since = format!("--since={CHURN_WINDOW}");
+    let log = run_git(repo_dir, &["log", &since, "--name-only", "--format="])?;
+
+    let mut churn = HashMap::new();
+    for line in log.lines().filter(|line| !line.is_empty()) {
+        if let Ok(file) = repo_root.join(line).canonicalize() {
+            *churn.entry(file).or_default() += 1;
         }
-        return Ok(());
     }
+    Ok(churn)
+}
 
-    Ok(())
+/// Combine per-node churn and betweenness into hotspot scores (their product), ranked
+/// highest first with ties broken by node index. Nodes scoring zero are left out.
+fn rank_hotspots(churn: &[usize], betweenness: &[f64]) -> Vec<(usize, f64)> {
+    let mut scores: Vec<(usize, f64)> = churn
+        .iter()
+        .zip(betweenness)
+        .map(|(&commits, &centrality)| commits as f64 * centrality)
+        .enumerate()
+        .filter(|&(_, score)| score > 0.0)
+        .collect();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    scores
 }
 
-fn detect_project_languages(
-    files_to_process: &[PathBuf],
-    language_files: &mut HashMap<PathBuf, Language>,
-) -> Option<HashSet<Language>> {
-    let mut detected: HashSet<Language> = HashSet::new();
-    files_to_process
+/// Build the `--hotspots` report: files ranked by git churn times betweenness centrality
+fn format_hotspots(
+    graph_nodes: &[GraphNode],
+    project_path: &Path,
+    churn: &HashMap<PathBuf, usize>,
+) -> String {
+    let root = if project_path.is_file() {
+        project_path.parent().unwrap_or(project_path)
+    } else {
+        project_path
+    };
+
+    let analysis = GraphAnalysis::analyze_graph(&build_dependency_graph(graph_nodes));
+    let commits: Vec<usize> = graph_nodes
         .iter()
-        .for_each(|entry| detect_file_language(entry.to_path_buf(), language_files, &mut detected));
+        .map(|node| churn.get(node.data().file()).copied().unwrap_or(0))
+        .collect();
+    let betweenness: Vec<f64> = (0..graph_nodes.len())
+        .map(|i| {
+            analysis
+                .get_betweenness_centrality(NodeIndex::new(i))
+                .unwrap_or(0.0)
+        })
+        .collect();
+
+    let mut report = String::new();
+    for (rank, (i, score)) in rank_hotspots(&commits, &betweenness).iter().enumerate() {
+        let file = graph_nodes[*i].data().file();
+        report.push_str(&format!(
+            "{}. {} {score:.3} (commits: {}, betweenness: {:.3})\n",
+            rank + 1,
+            file.strip_prefix(root).unwrap_or(file).display(),
+            commits[*i],
+            betweenness[*i],
+        ));
+    }
+    if report.is_empty() {
+        report
+            .push_str("No hotspots: no file has both recent commits and betweenness above zero\n");
+    }
+    report
+}
 
-    if detected.is_empty() {
-        None
+/// Build the `--cohesion` report: directories ranked by the share of their edges that stay inside them
+fn format_cohesion(graph_nodes: &[GraphNode], project_path: &Path) -> String {
+    let root = if project_path.is_file() {
+        project_path.parent().unwrap_or(project_path)
     } else {
-        Some(detected)
+        project_path
+    };
+
+    let mut report = String::new();
+    for (rank, dir) in directory_cohesion(graph_nodes).iter().enumerate() {
+        let relative = dir.directory.strip_prefix(root).unwrap_or(&dir.directory);
+        let name = if relative.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            relative
+        };
+        report.push_str(&format!(
+            "{}. {} {:.3} ({}/{} edges internal)\n",
+            rank + 1,
+            name.display(),
+            dir.ratio(),
+            dir.internal_edges,
+            dir.total_edges,
+        ));
     }
+    if report.is_empty() {
+        report.push_str("No cohesion to report: the graph has no edges\n");
+    }
+    report
 }
 
-fn walk_directory(path: &Path, no_gitignore: bool) -> Vec<PathBuf> {
-    let mut paths = Vec::new();
+/// Get the files staged in the git repository containing `path`, as absolute paths
+fn get_staged_files(
+    path: &Path,
+    extension_map: &HashMap<String, Language>,
+) -> Result<HashSet<PathBuf>, String> {
+    let repo_dir = if path.is_file() {
+        path.parent().unwrap_or(path)
+    } else {
+        path
+    };
+
+    // `git diff --name-only` reports paths relative to the repository root
+    let repo_root = PathBuf::from(run_git(repo_dir, &["rev-parse", "--show-toplevel"])?.trim());
+    let staged = run_git(repo_dir, &["diff", "--cached", "--name-only"])?;
+
+    Ok(staged
+        .lines()
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| repo_root.join(line).canonicalize().ok())
+        .filter(|file| {
+            Language::from_file_with_config(file.to_str().unwrap_or_default(), extension_map)
+                .is_some()
+        })
+        .collect())
+}
 
-    let mut builder = WalkBuilder::new(path);
-    if no_gitignore {
-        builder
-            .git_ignore(false)
-            .git_exclude(false)
-            .git_global(false)
-            .ignore(false);
+/// Get the files with uncommitted changes (including untracked files) in the git
+/// repository containing `path`, as absolute paths
+fn get_modified_files(path: &Path) -> Result<HashSet<PathBuf>, String> {
+    let repo_dir = if path.is_file() {
+        path.parent().unwrap_or(path)
     } else {
-        // for most/all projects, gitignore and other ignore files will be automatically detected by ignore crate
-        // but they don't when using tempfile and/or when running tests
-        let gitignore_path = path.join(".gitignore");
-        if gitignore_path.exists() {
-            builder.add_ignore(gitignore_path);
+        path
+    };
+
+    // Porcelain output is `XY path`, or `XY old -> new` for renames, relative to the root
+    let repo_root = PathBuf::from(run_git(repo_dir, &["rev-parse", "--show-toplevel"])?.trim());
+    let status = run_git(
+        repo_dir,
+        &["status", "--porcelain", "--untracked-files=all"],
+    )?;
+
+    Ok(status
+        .lines()
+        .filter_map(|line| line.get(3..))
+        .map(|entry| {
+            entry
+                .rsplit(" -> ")
+                .next()
+                .unwrap_or(entry)
+                .trim_matches('"')
+        })
+        .filter_map(|file| repo_root.join(file).canonicalize().ok())
+        .collect())
+}
+
+/// Keep only staged files and the files they directly depend on.
+/// Edges between the remaining nodes are preserved, edges leaving the subset are dropped.
+fn filter_to_staged(
+    graph_nodes: Vec<GraphNode>,
+    staged_files: &HashSet<PathBuf>,
+) -> Vec<GraphNode> {
+    let mut keep: HashSet<PathBuf> = HashSet::new();
+    for node in &graph_nodes {
+        if staged_files.contains(node.data().file()) {
+            keep.insert(node.data().file().clone());
+            keep.extend(node.edges().iter().cloned());
         }
     }
 
-    for result in builder.build() {
-        match result {
-            Ok(entry) => {
-                if let Some(file_type) = entry.file_type()
-                    && file_type.is_file()
-                {
-                    paths.push(entry.path().to_path_buf());
-                }
+    graph_nodes
+        .into_iter()
+        .filter(|node| keep.contains(node.data().file()))
+        .map(|node| {
+            let edges = node
+                .edges()
+                .iter()
+                .filter(|edge| keep.contains(*edge))
+                .cloned()
+                .collect();
+            GraphNode::new(node.data().clone(), edges)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use seiri_cli::layout::{self, Layout};
+    use seiri_cli::parsers::{cpp::parse_cpp_file, rust::parse_rust_file};
+    use seiri_cli::{detect_file_language, detect_project_languages, parse_files, walk_directory};
+    use std::fs;
+    use std::time::Duration;
+    use std::{fs::File, path::Path};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_non_existent_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let non_existent = temp_dir.path().join("non_existent_dir_12345");
+
+        let args = Cli {
+            project_path: Some(non_existent.clone()),
+            output_filename: Some("output.txt".to_string()),
+            ..Default::default()
+        };
+
+        let result = args.validate();
+
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("does not exist"));
+
+        let args = Cli {
+            project_path: Some(PathBuf::from("https://github.com/user/repo.git")),
+            ..Default::default()
+        };
+        assert!(args.validate().is_ok());
+    }
+
+    #[test]
+    fn test_output_format_from_path() {
+        assert_eq!(
+            OutputFormat::from_path(Path::new("graph.svg")),
+            Ok(OutputFormat::Svg)
+        );
+        assert_eq!(
+            OutputFormat::from_path(Path::new("graph.PNG")),
+            Ok(OutputFormat::Png)
+        );
+        assert_eq!(
+            OutputFormat::from_path(Path::new("graph.mermaid")),
+            Ok(OutputFormat::Mermaid)
+        );
+
+        for filename in ["graph.txt", "graph", "svg"] {
+            let err = OutputFormat::from_path(Path::new(filename)).unwrap_err();
+            assert!(err.contains(&format!("Unsupported output format: {filename}")));
+            for ext in [
+                ".svg", ".png", ".dot", ".json", ".gexf", ".mmd", ".mermaid", ".html", ".csv",
+            ] {
+                assert!(err.contains(ext), "{err} does not list {ext}");
             }
-            Err(msg) => eprintln!("Error reading entry: {msg}"),
         }
+
+        // Rejected before any analysis runs
+        let args = Cli {
+            output_filename: Some("graph.txt".to_string()),
+            ..Default::default()
+        };
+        assert!(args.validate().unwrap_err().contains(".gexf"));
     }
 
-    paths
-}
+    #[test]
+    fn test_layout_flag() {
+        let args = Cli::try_parse_from(["seiri", "--layout", "sugiyama"]).unwrap();
+        assert!(args.validate().is_ok());
+        assert_eq!(
+            LayoutType::from_str(args.layout.as_deref().unwrap()),
+            Ok(LayoutType::Sugiyama)
+        );
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::layout::Layout;
-    use std::fs;
-    use std::{fs::File, path::Path};
-    use tempfile::TempDir;
+        let args = Cli {
+            layout: Some("spiral".to_string()),
+            ..Default::default()
+        };
+        assert!(
+            args.validate()
+                .unwrap_err()
+                .contains("Unknown layout: spiral")
+        );
+    }
 
     #[test]
-    fn test_non_existent_path() {
-        let temp_dir = TempDir::new().unwrap();
-        let non_existent = temp_dir.path().join("non_existent_dir_12345");
+    fn test_color_by_flag() {
+        let args = Cli::try_parse_from(["seiri", "--color-by", "community"]).unwrap();
+        assert!(args.validate().is_ok());
+        assert_eq!(
+            ColorBy::from_str(args.color_by.as_deref().unwrap()),
+            Ok(ColorBy::Community)
+        );
 
         let args = Cli {
-            project_path: Some(non_existent.clone()),
-            output_filename: Some("output.txt".to_string()),
-            verbose: false,
-            version: false,
-            no_gitignore: false,
+            color_by: Some("rainbow".to_string()),
+            ..Default::default()
         };
+        assert!(
+            args.validate()
+                .unwrap_err()
+                .contains("Unknown coloring: rainbow")
+        );
+    }
 
-        let result = args.validate();
+    #[test]
+    fn test_hide_language() {
+        let args = Cli {
+            hide_language: vec!["cobol".to_string()],
+            ..Default::default()
+        };
+        assert!(args.validate().unwrap_err().contains("Unknown language"));
 
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("does not exist"));
+        // Hiding the only language in the project leaves nothing to analyze
+        let temp_dir = TempDir::new().unwrap();
+        let temp_file = temp_dir.path().join("test.rs");
+        File::create(&temp_file).unwrap();
+        let args = Cli {
+            project_path: Some(temp_file),
+            hide_language: vec!["rust".to_string()],
+            no_cache: true,
+            ..Default::default()
+        };
+        assert!(args.validate().is_ok());
+        assert!(run(args).is_err());
     }
 
     #[test]
@@ -334,9 +1671,8 @@ mod tests {
         let args = Cli {
             project_path: Some(temp_file),
             output_filename: None,
-            verbose: false,
-            version: false,
-            no_gitignore: false,
+            no_cache: true,
+            ..Default::default()
         };
 
         let result = run(args);
@@ -351,9 +1687,8 @@ mod tests {
         let args = Cli {
             project_path: Some(temp_dir.path().to_path_buf()),
             output_filename: None,
-            verbose: false,
-            version: false,
-            no_gitignore: false,
+            no_cache: true,
+            ..Default::default()
         };
         let result = run(args);
         // we expect an error since the directory is empty
@@ -363,14 +1698,116 @@ mod tests {
         let args = Cli {
             project_path: None,
             output_filename: None,
-            verbose: false,
-            version: false,
-            no_gitignore: false,
+            no_cache: true,
+            ..Default::default()
         };
         let result = run(args);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_multiple_paths_form_one_graph() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        for (file, content) in [
+            (
+                "app/main.ts",
+                "import { run } from '../lib/helpers';\n\nrun();\n",
+            ),
+            ("lib/helpers.ts", "export function run() {}\n"),
+            ("scripts/unused.ts", "export const unused = 1;\n"),
+        ] {
+            fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            fs::write(root.join(file), content).unwrap();
+        }
+        let output = root.join("graph.json");
+
+        run(Cli {
+            project_path: Some(root.join("app")),
+            paths: vec![root.join("lib")],
+            output_filename: Some(output.to_string_lossy().into_owned()),
+            no_cache: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let export: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        let files = export["files"].as_array().unwrap();
+        assert_eq!(files.len(), 2);
+        let main = files
+            .iter()
+            .find(|file| file["file"].as_str().unwrap().ends_with("main.ts"))
+            .unwrap();
+        assert_eq!(
+            main["edges"],
+            serde_json::json!([root.join("lib/helpers.ts")])
+        );
+    }
+
+    #[test]
+    fn test_paths_must_exist() {
+        let temp_dir = TempDir::new().unwrap();
+        let args = Cli::try_parse_from([
+            "seiri",
+            temp_dir.path().to_str().unwrap(),
+            "--path",
+            "does/not/exist",
+        ])
+        .unwrap();
+        assert!(
+            args.validate()
+                .unwrap_err()
+                .contains("path does not exist: \"does/not/exist\"")
+        );
+    }
+
+    #[test]
+    fn test_common_ancestor() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_path_buf();
+        fs::create_dir_all(root.join("src/core")).unwrap();
+        fs::write(root.join("src/core/defs.rs"), "").unwrap();
+        fs::create_dir_all(root.join("tests")).unwrap();
+
+        assert_eq!(common_ancestor(&[]), None);
+        assert_eq!(
+            common_ancestor(&[root.join("src/core/defs.rs")]),
+            Some(root.join("src/core"))
+        );
+        assert_eq!(
+            common_ancestor(&[root.join("src/core"), root.join("tests")]),
+            Some(root.clone())
+        );
+        assert_eq!(
+            common_ancestor(&[root.join("src"), root.join("src/core/defs.rs")]),
+            Some(root.join("src"))
+        );
+    }
+
+    #[test]
+    fn test_allow_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("graph.svg");
+        let args = |allow_empty| Cli {
+            project_path: Some(temp_dir.path().to_path_buf()),
+            output_filename: Some(output.to_string_lossy().into_owned()),
+            allow_empty,
+            no_cache: true,
+            ..Default::default()
+        };
+
+        let result = run(args(false));
+        assert!(result.unwrap_err().contains("No supported language files"));
+        assert!(!output.exists());
+
+        // The export still happens, just with nothing in it
+        run(args(true)).unwrap();
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(content.contains("<svg"));
+        assert!(!content.contains("<circle"));
+    }
+
     #[test]
     fn test_verbose_output() {
         let temp_dir = TempDir::new().unwrap();
@@ -381,8 +1818,8 @@ mod tests {
             project_path: Some(temp_file),
             output_filename: None,
             verbose: true,
-            version: false,
-            no_gitignore: false,
+            no_cache: true,
+            ..Default::default()
         };
 
         let result = run(args);
@@ -399,6 +1836,7 @@ mod tests {
         let mut detected_languages = HashSet::new();
         detect_file_language(
             current_file.to_path_buf(),
+            &HashMap::new(),
             &mut language_files,
             &mut detected_languages,
         );
@@ -416,6 +1854,7 @@ mod tests {
         let mut detected_languages = HashSet::new();
         detect_file_language(
             current_file.to_path_buf(),
+            &HashMap::new(),
             &mut language_files,
             &mut detected_languages,
         );
@@ -429,8 +1868,9 @@ mod tests {
         assert!(current_dir.try_exists().is_ok());
 
         let mut language_files: HashMap<PathBuf, Language> = HashMap::new();
-        let files_to_process = walk_directory(&current_dir, false);
-        let result = detect_project_languages(&files_to_process, &mut language_files);
+        let files_to_process = walk_directory(&current_dir, false, &[], None);
+        let result =
+            detect_project_languages(&files_to_process, &HashMap::new(), &mut language_files);
 
         assert!(&result.is_some());
 
@@ -447,10 +1887,30 @@ mod tests {
 
         fs::write(dir.path().join(".gitignore"), "ignored.txt\n").unwrap();
 
-        let files = walk_directory(dir.path(), false);
+        let files = walk_directory(dir.path(), false, &[], None);
         assert!(!files.iter().any(|p| p.ends_with("ignored.txt")));
     }
 
+    #[test]
+    fn respects_nested_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("sub")).unwrap();
+        File::create(dir.path().join("sub/skip.rs")).unwrap();
+        File::create(dir.path().join("sub/keep.rs")).unwrap();
+        File::create(dir.path().join("skip.rs")).unwrap();
+
+        fs::write(dir.path().join("sub/.gitignore"), "skip.rs\n").unwrap();
+
+        let files = walk_directory(dir.path(), false, &[], None);
+        assert!(!files.iter().any(|p| p.ends_with("sub/skip.rs")));
+        assert!(files.iter().any(|p| p.ends_with("sub/keep.rs")));
+        // Rules in a nested `.gitignore` don't reach outside its directory
+        assert!(files.contains(&dir.path().join("skip.rs")));
+
+        let files = walk_directory(dir.path(), true, &[], None);
+        assert!(files.iter().any(|p| p.ends_with("sub/skip.rs")));
+    }
+
     #[test]
     fn ignores_no_gitignore_flag() {
         let dir = tempfile::tempdir().unwrap();
@@ -459,10 +1919,766 @@ mod tests {
 
         fs::write(dir.path().join(".gitignore"), "ignored.txt\n").unwrap();
 
-        let files = walk_directory(dir.path(), true);
+        let files = walk_directory(dir.path(), true, &[], None);
         assert!(files.iter().any(|p| p.ends_with("ignored.txt")));
     }
 
+    #[test]
+    fn test_exclude_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/generated")).unwrap();
+        File::create(dir.path().join("src/generated/schema.rs")).unwrap();
+        File::create(dir.path().join("src/lib.rs")).unwrap();
+        File::create(dir.path().join("src/generated.rs")).unwrap();
+
+        let files = walk_directory(dir.path(), false, &["**/generated/**".to_string()], None);
+        assert!(!files.iter().any(|p| p.ends_with("schema.rs")));
+        assert!(files.iter().any(|p| p.ends_with("src/lib.rs")));
+        assert!(files.iter().any(|p| p.ends_with("src/generated.rs")));
+
+        // Combined with gitignore rules
+        fs::write(dir.path().join(".gitignore"), "lib.rs\n").unwrap();
+        let files = walk_directory(dir.path(), false, &["**/generated/**".to_string()], None);
+        assert!(!files.iter().any(|p| p.ends_with("lib.rs")));
+        assert!(files.iter().any(|p| p.ends_with("src/generated.rs")));
+
+        let args = Cli {
+            exclude: vec!["[".to_string()],
+            ..Default::default()
+        };
+        assert!(
+            args.validate()
+                .unwrap_err()
+                .contains("Invalid exclude pattern")
+        );
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/core/resolvers")).unwrap();
+        File::create(dir.path().join("build.rs")).unwrap();
+        File::create(dir.path().join("src/lib.rs")).unwrap();
+        File::create(dir.path().join("src/core/defs.rs")).unwrap();
+        File::create(dir.path().join("src/core/resolvers/rust.rs")).unwrap();
+
+        let file_names = |files: &[PathBuf]| {
+            let mut names: Vec<_> = files
+                .iter()
+                .map(|p| p.file_name().unwrap().to_str().unwrap().to_string())
+                .collect();
+            names.sort();
+            names
+        };
+
+        let files = walk_directory(dir.path(), false, &[], Some(0));
+        assert_eq!(file_names(&files), vec!["build.rs"]);
+
+        let files = walk_directory(dir.path(), false, &[], Some(1));
+        assert_eq!(file_names(&files), vec!["build.rs", "lib.rs"]);
+
+        let files = walk_directory(dir.path(), false, &[], Some(2));
+        assert_eq!(file_names(&files), vec!["build.rs", "defs.rs", "lib.rs"]);
+
+        let files = walk_directory(dir.path(), false, &[], None);
+        assert_eq!(files.len(), 4);
+
+        // Composes with excludes and gitignore rules
+        fs::write(dir.path().join(".gitignore"), "build.rs\n").unwrap();
+        let files = walk_directory(dir.path(), false, &["src/core/**".to_string()], Some(2));
+        assert_eq!(file_names(&files), vec!["lib.rs"]);
+    }
+
+    #[test]
+    fn test_parse_files_in_parallel() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut language_files = HashMap::new();
+        for i in 0..200 {
+            let file_path = temp_dir.path().join(format!("module_{i}.rs"));
+            fs::write(
+                &file_path,
+                format!("mod module_{};\nfn function_{i}() {{}}\n", (i + 1) % 200),
+            )
+            .unwrap();
+            language_files.insert(file_path, Language::Rust);
+        }
+
+        let node_map = parse_files(&language_files);
+        assert_eq!(node_map.len(), 200);
+        for (file_path, node) in &node_map {
+            assert_eq!(node.file(), file_path);
+            assert_eq!(node.functions().len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_explain_import_resolution() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("main.rs"),
+            "mod util;\nmod missing;\nuse serde::Serialize;\n",
+        )
+        .unwrap();
+        fs::write(root.join("util.rs"), "").unwrap();
+
+        let mut language_files = HashMap::new();
+        language_files.insert(root.join("main.rs"), Language::Rust);
+        language_files.insert(root.join("util.rs"), Language::Rust);
+        let node_map = parse_files(&language_files);
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder.build_graph_edges(&node_map, root);
+
+        let lines = explain_import_resolution("util", &node_map, &graph_builder);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].ends_with(&format!("resolved to {}", root.join("util.rs").display())));
+
+        let lines = explain_import_resolution("missing", &node_map, &graph_builder);
+        assert!(lines[0].ends_with("but no matching file was found"));
+
+        let lines = explain_import_resolution("serde::Serialize", &node_map, &graph_builder);
+        assert!(lines[0].contains("external: didn't start with `crate::`"));
+
+        let lines = explain_import_resolution("tokio", &node_map, &graph_builder);
+        assert_eq!(lines, vec!["No file imports `tokio`".to_string()]);
+    }
+
+    #[test]
+    fn test_project_name() {
+        assert_eq!(project_name(None, Path::new("/home/me/seiri")), "seiri");
+        assert_eq!(
+            project_name(
+                Some("https://github.com/tarolling/seiri.git"),
+                Path::new("/tmp/x")
+            ),
+            "seiri"
+        );
+        assert_eq!(
+            project_name(Some("git@github.com:tarolling/seiri"), Path::new("/tmp/x")),
+            "seiri"
+        );
+    }
+
+    #[test]
+    fn test_resolve_subcommand() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("src/core")).unwrap();
+        fs::write(root.join("src/main.rs"), "mod core;\n").unwrap();
+        fs::write(root.join("src/core/mod.rs"), "pub mod defs;\n").unwrap();
+        fs::write(root.join("src/core/defs.rs"), "pub struct FileNode;\n").unwrap();
+
+        let args = Cli::try_parse_from([
+            "seiri",
+            "resolve",
+            root.join("src/main.rs").to_str().unwrap(),
+            "crate::core::defs::FileNode",
+            "--project",
+            root.to_str().unwrap(),
+        ])
+        .unwrap();
+        let Some(CliCommand::Resolve {
+            from_file,
+            import_path,
+            project,
+            ..
+        }) = &args.command
+        else {
+            panic!("expected the resolve subcommand");
+        };
+
+        let line = resolve_single_import(project, from_file, import_path, &HashMap::new()).unwrap();
+        assert!(line.contains("local: starts with `crate::`"), "{line}");
+        assert!(line.ends_with(&format!(
+            "resolved to {}",
+            root.join("src/core/defs.rs").display()
+        )));
+
+        let line = resolve_single_import(
+            &root,
+            &root.join("src/main.rs"),
+            "crate::missing",
+            &HashMap::new(),
+        )
+        .unwrap();
+        assert!(line.ends_with("but no matching file was found among 3 Rust files"));
+
+        // Files with extensions from a language config are analyzed
+        let scripts = root.join("scripts");
+        fs::create_dir(&scripts).unwrap();
+        fs::write(scripts.join("a.pyx"), "import b\n").unwrap();
+        fs::write(scripts.join("b.pyx"), "x = 1\n").unwrap();
+        fs::write(root.join("languages.json"), r#"{"python": ["pyx"]}"#).unwrap();
+        let args = Cli::try_parse_from([
+            "seiri",
+            "resolve",
+            scripts.join("a.pyx").to_str().unwrap(),
+            "b",
+            "--project",
+            scripts.to_str().unwrap(),
+            "--language-config",
+            root.join("languages.json").to_str().unwrap(),
+        ])
+        .unwrap();
+        let Some(CliCommand::Resolve {
+            language_config, ..
+        }) = &args.command
+        else {
+            panic!("expected the resolve subcommand");
+        };
+        let extension_map = load_extension_map(language_config.as_deref()).unwrap();
+        let line =
+            resolve_single_import(&scripts, &scripts.join("a.pyx"), "b", &extension_map).unwrap();
+        assert!(line.ends_with("among 2 Python files"), "{line}");
+
+        // The graph arguments still parse without a subcommand
+        let args = Cli::try_parse_from(["seiri", root.to_str().unwrap(), "graph.svg"]).unwrap();
+        assert!(args.command.is_none());
+    }
+
+    #[test]
+    fn test_staged_files_only() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&root)
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {:?} failed", args);
+        };
+
+        // main.rs depends on util.rs, other.rs is unrelated
+        git(&["init", "-q"]);
+        fs::write(root.join("main.rs"), "mod util;\n").unwrap();
+        fs::write(root.join("util.rs"), "pub fn helper() {}\n").unwrap();
+        fs::write(root.join("other.rs"), "pub fn unrelated() {}\n").unwrap();
+        git(&["add", "main.rs"]);
+
+        let staged = get_staged_files(&root, &HashMap::new()).unwrap();
+        assert_eq!(staged.len(), 1);
+        assert!(staged.contains(&root.join("main.rs")));
+
+        // Resolve against the whole working tree, then narrow down
+        let mut language_files: HashMap<PathBuf, Language> = HashMap::new();
+        let files_to_process = walk_directory(&root, false, &[], None);
+        detect_project_languages(&files_to_process, &HashMap::new(), &mut language_files);
+        let node_map: HashMap<PathBuf, FileNode> = language_files
+            .keys()
+            .filter_map(|path| parse_rust_file(path).map(|node| (path.clone(), node)))
+            .collect();
+        let graph_nodes = GraphBuilder::new().build_graph_edges(&node_map, &root);
+        assert_eq!(graph_nodes.len(), 3);
+
+        let filtered = filter_to_staged(graph_nodes, &staged);
+        let files: HashSet<_> = filtered.iter().map(|n| n.data().file().clone()).collect();
+        assert_eq!(files.len(), 2);
+        assert!(files.contains(&root.join("main.rs")));
+        assert!(files.contains(&root.join("util.rs")));
+        assert!(!files.contains(&root.join("other.rs")));
+    }
+
+    #[test]
+    fn test_modified_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&root)
+                .args([
+                    "-c",
+                    "user.name=seiri",
+                    "-c",
+                    "user.email=seiri@example.com",
+                ])
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {:?} failed", args);
+        };
+
+        git(&["init", "-q"]);
+        fs::write(root.join("main.rs"), "mod util;\n").unwrap();
+        fs::write(root.join("util.rs"), "").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "init"]);
+        assert!(get_modified_files(&root).unwrap().is_empty());
+
+        fs::write(root.join("util.rs"), "pub fn helper() {}\n").unwrap();
+        fs::create_dir(root.join("new")).unwrap();
+        fs::write(root.join("new/mod.rs"), "").unwrap();
+        let modified = get_modified_files(&root).unwrap();
+        assert_eq!(
+            modified,
+            HashSet::from([root.join("util.rs"), root.join("new/mod.rs")])
+        );
+    }
+
+    #[test]
+    fn test_diff_visual() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&root)
+                .args([
+                    "-c",
+                    "user.name=seiri",
+                    "-c",
+                    "user.email=seiri@example.com",
+                ])
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {:?} failed", args);
+        };
+
+        git(&["init", "-q"]);
+        fs::write(root.join("main.rs"), "mod util;\nmod legacy;\n").unwrap();
+        fs::write(root.join("util.rs"), "").unwrap();
+        fs::write(root.join("legacy.rs"), "").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "init"]);
+
+        // Replace `legacy` with `config` in the working tree only
+        fs::remove_file(root.join("legacy.rs")).unwrap();
+        fs::write(root.join("main.rs"), "mod util;\nmod config;\n").unwrap();
+        fs::write(root.join("config.rs"), "").unwrap();
+
+        let output = root.join("diff.svg");
+        let args = Cli {
+            project_path: Some(root.clone()),
+            output_filename: Some(output.to_string_lossy().to_string()),
+            diff_visual: Some("HEAD".to_string()),
+            no_cache: true,
+            ..Default::default()
+        };
+        run(args).unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        let document = roxmltree::Document::parse(&content).unwrap();
+        let classes = |tag: &str| -> Vec<&str> {
+            document
+                .descendants()
+                .filter(|n| n.has_tag_name(tag))
+                .filter_map(|n| n.attribute("class"))
+                .collect()
+        };
+        assert_eq!(classes("line"), vec!["added", "removed"]);
+        assert_eq!(classes("circle"), vec!["added", "removed"]);
+        // The working tree is left as it was
+        assert!(!root.join("legacy.rs").exists());
+
+        let args = Cli {
+            output_filename: Some("diff.png".to_string()),
+            diff_visual: Some("HEAD".to_string()),
+            ..Default::default()
+        };
+        assert!(args.validate().unwrap_err().contains(".svg"));
+    }
+
+    #[test]
+    fn test_diff_visual_of_project_in_repo_subdirectory() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let project = root.join("app");
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&root)
+                .args([
+                    "-c",
+                    "user.name=seiri",
+                    "-c",
+                    "user.email=seiri@example.com",
+                ])
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {:?} failed", args);
+        };
+
+        git(&["init", "-q"]);
+        fs::create_dir(&project).unwrap();
+        fs::write(project.join("main.rs"), "mod legacy;\n").unwrap();
+        fs::write(project.join("legacy.rs"), "").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "init"]);
+
+        fs::remove_file(project.join("legacy.rs")).unwrap();
+        fs::write(project.join("main.rs"), "mod config;\n").unwrap();
+        fs::write(project.join("config.rs"), "").unwrap();
+
+        let output = root.join("diff.svg");
+        let args = Cli {
+            project_path: Some(project.clone()),
+            output_filename: Some(output.to_string_lossy().to_string()),
+            diff_visual: Some("HEAD".to_string()),
+            no_cache: true,
+            ..Default::default()
+        };
+        run(args).unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        let document = roxmltree::Document::parse(&content).unwrap();
+        let circles: Vec<&str> = document
+            .descendants()
+            .filter(|n| n.has_tag_name("circle"))
+            .filter_map(|n| n.attribute("class"))
+            .collect();
+        assert_eq!(circles, vec!["added", "removed"]);
+    }
+
+    #[test]
+    fn test_watch_rebuilds_on_file_write() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::write(root.join("main.rs"), "fn main() {}\n").unwrap();
+
+        let settings = BuildSettings {
+            project_path: root.clone(),
+            staged: false,
+            explain_import: None,
+            options: AnalyzeOptions::default(),
+        };
+        let (graph_nodes, _) = build_graph(&settings).unwrap();
+        assert_eq!(graph_nodes.len(), 1);
+
+        let watcher =
+            ProjectWatcher::new(&root, Duration::from_millis(50), &HashMap::new()).unwrap();
+        fs::write(root.join("main.rs"), "mod util;\nfn main() {}\n").unwrap();
+        fs::write(root.join("util.rs"), "pub fn helper() {}\n").unwrap();
+
+        let changed = watcher
+            .wait_for_change_timeout(Duration::from_secs(5))
+            .unwrap()
+            .expect("no change reported");
+        assert!(!changed.is_empty());
+
+        let (graph_nodes, _) = build_graph(&settings).unwrap();
+        assert_eq!(graph_nodes.len(), 2);
+        let main = graph_nodes
+            .iter()
+            .find(|n| n.data().file() == &root.join("main.rs"))
+            .unwrap();
+        assert_eq!(main.edges(), &vec![root.join("util.rs")]);
+    }
+
+    #[test]
+    fn test_rebuild_refreshes_modified_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&root)
+                .args([
+                    "-c",
+                    "user.name=seiri",
+                    "-c",
+                    "user.email=seiri@example.com",
+                ])
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {:?} failed", args);
+        };
+
+        git(&["init", "-q"]);
+        fs::write(root.join("main.rs"), "mod util;\n").unwrap();
+        fs::write(root.join("util.rs"), "").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "init"]);
+
+        let settings = BuildSettings {
+            project_path: root.clone(),
+            staged: false,
+            explain_import: None,
+            options: AnalyzeOptions::default(),
+        };
+        let mut project = analyze(&settings, &mut Profile::default()).unwrap();
+        let mut modified_files = get_modified_files(&root).unwrap();
+        assert!(modified_files.is_empty());
+
+        fs::write(root.join("util.rs"), "pub fn helper() {}\n").unwrap();
+        let changed = HashSet::from([root.join("util.rs")]);
+        rebuild_graph(&settings, &mut project, &changed, &mut modified_files).unwrap();
+        assert_eq!(modified_files, HashSet::from([root.join("util.rs")]));
+    }
+
+    #[test]
+    fn test_exclude_generated() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::write(root.join("main.rs"), "mod service;\n").unwrap();
+        fs::write(root.join("service.pb.rs"), "pub struct Request {}\n").unwrap();
+
+        let mut settings = BuildSettings {
+            project_path: root.clone(),
+            staged: false,
+            explain_import: None,
+            options: AnalyzeOptions::default(),
+        };
+        let (graph_nodes, _) = build_graph(&settings).unwrap();
+        assert_eq!(graph_nodes.len(), 2);
+        assert!(graph_nodes.iter().any(|n| n.data().is_generated()));
+
+        settings.options.exclude_generated = true;
+        let (graph_nodes, _) = build_graph(&settings).unwrap();
+        assert_eq!(graph_nodes.len(), 1);
+        assert!(graph_nodes[0].data().file().ends_with("main.rs"));
+    }
+
+    #[test]
+    fn test_exclude_generated_ignores_directories_above_the_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir
+            .path()
+            .canonicalize()
+            .unwrap()
+            .join("gen-out/project");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("main.rs"), "mod service;\n").unwrap();
+        fs::write(root.join("service.pb.rs"), "pub struct Request {}\n").unwrap();
+
+        let mut settings = BuildSettings {
+            project_path: root.clone(),
+            staged: false,
+            explain_import: None,
+            options: AnalyzeOptions::default(),
+        };
+        settings.options.exclude_generated = true;
+        let (graph_nodes, _) = build_graph(&settings).unwrap();
+        assert_eq!(graph_nodes.len(), 1);
+        assert!(graph_nodes[0].data().file().ends_with("main.rs"));
+    }
+
+    #[test]
+    fn test_profile_records_each_phase() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::write(root.join("main.rs"), "mod util;\n").unwrap();
+        fs::write(root.join("util.rs"), "pub fn helper() {}\n").unwrap();
+        fs::write(root.join("script.py"), "import os\n").unwrap();
+
+        let settings = BuildSettings {
+            project_path: root.clone(),
+            staged: false,
+            explain_import: None,
+            options: AnalyzeOptions::default(),
+        };
+        let mut profile = Profile::default();
+        let (graph_nodes, languages) = build_graph_profiled(&settings, &mut profile).unwrap();
+        let output = root.join("graph.svg");
+        export_graph(
+            output.to_str().unwrap(),
+            &graph_nodes,
+            languages,
+            &ExportOptions::default(),
+            &mut profile,
+        )
+        .unwrap();
+
+        let phases: Vec<&str> = profile
+            .phases()
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(
+            phases,
+            vec![
+                "walk",
+                "detect",
+                "parse[Python]",
+                "parse[Rust]",
+                "build edges",
+                "layout",
+                "export"
+            ]
+        );
+        for (name, elapsed) in profile.phases() {
+            assert!(!elapsed.is_zero(), "{name} took no time");
+        }
+    }
+
+    #[test]
+    fn test_include_languages_with_exclude() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("src/core")).unwrap();
+        fs::create_dir_all(root.join("src/parsers")).unwrap();
+        fs::write(root.join("src/main.rs"), "mod core;\nmod parsers;\n").unwrap();
+        fs::write(root.join("src/core/defs.rs"), "").unwrap();
+        fs::write(root.join("src/parsers/rust.rs"), "").unwrap();
+        fs::write(root.join("src/parsers/helper.py"), "").unwrap();
+        fs::write(root.join("src/core/tool.py"), "").unwrap();
+
+        let mut settings = BuildSettings {
+            project_path: root.clone(),
+            staged: false,
+            explain_import: None,
+            options: AnalyzeOptions {
+                included_languages: HashSet::from([Language::Rust]),
+                exclude: vec!["src/core/**".to_string()],
+                ..Default::default()
+            },
+        };
+        let (graph_nodes, languages) = build_graph(&settings).unwrap();
+        let files: HashSet<_> = graph_nodes
+            .iter()
+            .map(|n| n.data().file().strip_prefix(&root).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(
+            files,
+            HashSet::from([
+                PathBuf::from("src/main.rs"),
+                PathBuf::from("src/parsers/rust.rs")
+            ])
+        );
+        assert_eq!(languages, HashSet::from([Language::Rust]));
+
+        // Hiding the only included language leaves nothing
+        settings.options.hidden_languages = HashSet::from([Language::Rust]);
+        assert!(build_graph(&settings).is_err());
+
+        let args = Cli {
+            include_languages: vec!["rust".to_string(), "klingon".to_string()],
+            ..Default::default()
+        };
+        assert!(args.validate().unwrap_err().contains("Unknown language"));
+    }
+
+    #[test]
+    fn test_check_layers_fails_on_violation() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("ui")).unwrap();
+        fs::create_dir_all(root.join("core")).unwrap();
+        fs::write(root.join("main.rs"), "mod ui;\nmod core;\n").unwrap();
+        fs::write(root.join("ui/mod.rs"), "use crate::core;\n").unwrap();
+        fs::write(root.join("core/mod.rs"), "").unwrap();
+        let config = root.join("layers.json");
+        fs::write(
+            &config,
+            r#"{ "layers": { "ui": ["ui"], "core": ["core"] }, "allow": ["ui -> core"] }"#,
+        )
+        .unwrap();
+
+        let args = |layer_config: &Path| Cli {
+            project_path: Some(root.to_path_buf()),
+            check_layers: Some(layer_config.to_path_buf()),
+            no_cache: true,
+            ..Default::default()
+        };
+        assert!(run(args(&config)).is_ok());
+
+        // Now core reaches back up into ui
+        fs::write(root.join("core/mod.rs"), "use crate::ui;\n").unwrap();
+        let result = run(args(&config));
+        assert_eq!(result.unwrap_err(), "1 layering violation(s) found");
+    }
+
+    #[test]
+    fn test_dry_run_writes_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("main.rs"), "mod util;\n").unwrap();
+        fs::write(root.join("util.rs"), "pub fn helper() {}\n").unwrap();
+        let output = root.join("graph.svg");
+
+        let args = Cli {
+            project_path: Some(root.to_path_buf()),
+            output_filename: Some(output.to_string_lossy().into_owned()),
+            dry_run: true,
+            no_cache: true,
+            ..Default::default()
+        };
+        assert!(run(args).is_ok());
+        assert!(!output.exists());
+
+        let graph_nodes = vec![
+            GraphNode::new(
+                parse_rust_file(root.join("main.rs")).unwrap(),
+                vec![root.join("util.rs")],
+            ),
+            GraphNode::new(parse_rust_file(root.join("util.rs")).unwrap(), vec![]),
+        ];
+        let report = describe_dry_run(Some("graph.svg"), &graph_nodes).unwrap();
+        assert!(report.contains("Would export SVG to graph.svg"));
+        assert!(report.contains("nodes: 2"));
+        assert!(report.contains("edges: 1"));
+
+        assert!(
+            describe_dry_run(None, &graph_nodes)
+                .unwrap()
+                .contains("GUI")
+        );
+        assert!(describe_dry_run(Some("graph.txt"), &graph_nodes).is_err());
+    }
+
+    #[test]
+    fn test_rank_hotspots() {
+        let churn = [10, 3, 0, 6, 2];
+        let betweenness = [0.0, 0.5, 0.9, 0.25, 0.75];
+
+        // 1: 1.5, 3: 1.5, 4: 1.5; node 0 has no centrality and node 2 no commits
+        let ranked = rank_hotspots(&churn, &betweenness);
+        let order: Vec<usize> = ranked.iter().map(|&(i, _)| i).collect();
+        assert_eq!(order, vec![1, 3, 4]);
+        assert!((ranked[0].1 - 1.5).abs() < 1e-9);
+
+        let ranked = rank_hotspots(&[1, 8, 4], &[0.2, 0.1, 0.5]);
+        let order: Vec<usize> = ranked.iter().map(|&(i, _)| i).collect();
+        assert_eq!(order, vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn test_stats_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        // main.rs -> other.rs -> util.rs
+        fs::write(root.join("main.rs"), "mod other;\n").unwrap();
+        fs::write(root.join("other.rs"), "use crate::util;\n").unwrap();
+        fs::write(root.join("util.rs"), "pub fn helper() {}\n").unwrap();
+        fs::write(root.join("script.py"), "print('hi')\n").unwrap();
+
+        let settings = BuildSettings {
+            project_path: root.clone(),
+            staged: false,
+            explain_import: None,
+            options: AnalyzeOptions::default(),
+        };
+        let (graph_nodes, _) = build_graph(&settings).unwrap();
+        let report = format_stats(&graph_nodes, &root);
+        let lines: Vec<&str> = report.lines().collect();
+
+        assert_eq!(lines[0], "files: 4");
+        assert_eq!(lines[1], "files[Python]: 1");
+        assert_eq!(lines[2], "files[Rust]: 3");
+        assert!(lines.contains(&"edges: 2"));
+        assert!(lines.contains(&"sccs: 4"));
+        assert!(lines.contains(&"top_fan_in[1]: other.rs 1"));
+        assert!(lines.contains(&"top_fan_in[2]: util.rs 1"));
+        assert!(lines.contains(&"top_fan_out[1]: main.rs 1"));
+        assert!(lines.contains(&"top_fan_out[2]: other.rs 1"));
+        assert!(lines.contains(&"top_betweenness[1]: other.rs 0.167"));
+        assert!(lines.contains(&"avg_clustering: 0.000"));
+        assert!(lines.contains(&"density: 0.167"));
+        // The import chain forms one community and script.py another
+        assert!(lines.contains(&"communities: 2"));
+        assert!(lines.contains(&"modularity: 0.000"));
+        // Both files with imports only import project modules
+        assert!(lines.contains(&"avg_import_locality: 1.000"));
+        assert!(lines.contains(&"suggested_order: script.py, util.rs, other.rs, main.rs"));
+
+        // Stable across runs
+        assert_eq!(report, format_stats(&graph_nodes, &root));
+    }
+
     /// Test T019: Verify C++ nodes work with layout algorithms
     /// Creates a simple C++ project and tests both Sugiyama and Circular layouts
     #[test]
@@ -504,8 +2720,8 @@ mod tests {
 
         // Parse all C++ files
         let mut language_files: HashMap<PathBuf, Language> = HashMap::new();
-        let files_to_process = walk_directory(temp_dir.path(), true);
-        detect_project_languages(&files_to_process, &mut language_files);
+        let files_to_process = walk_directory(temp_dir.path(), true, &[], None);
+        detect_project_languages(&files_to_process, &HashMap::new(), &mut language_files);
 
         // Only process C++ files
         let cpp_files: Vec<_> = language_files
@@ -627,9 +2843,10 @@ mod tests {
 
         // Parse files
         let mut language_files: HashMap<PathBuf, Language> = HashMap::new();
-        let files_to_process = walk_directory(temp_dir.path(), true);
-        let detected_languages = detect_project_languages(&files_to_process, &mut language_files)
-            .expect("Should detect languages");
+        let files_to_process = walk_directory(temp_dir.path(), true, &[], None);
+        let detected_languages =
+            detect_project_languages(&files_to_process, &HashMap::new(), &mut language_files)
+                .expect("Should detect languages");
 
         let mut node_map: HashMap<PathBuf, FileNode> = HashMap::new();
         for (file_path, lang) in &language_files {
@@ -651,8 +2868,13 @@ mod tests {
         );
 
         // Test SVG export
-        let svg_result =
-            export::export_graph_as_svg(&graph_nodes, &output_svg, detected_languages.clone());
+        let svg_result = export::export_graph_as_svg(
+            &graph_nodes,
+            &output_svg,
+            detected_languages.clone(),
+            &ExportOptions::default(),
+            None,
+        );
         assert!(
             svg_result.is_ok(),
             "SVG export should succeed, got: {:?}",
@@ -666,7 +2888,13 @@ mod tests {
         assert!(svg_content.len() > 100, "SVG content should be substantial");
 
         // Test PNG export
-        let png_result = export::export_graph_as_png(&graph_nodes, &output_png, detected_languages);
+        let png_result = export::export_graph_as_png(
+            &graph_nodes,
+            &output_png,
+            detected_languages,
+            &ExportOptions::default(),
+            None,
+        );
         assert!(
             png_result.is_ok(),
             "PNG export should succeed, got: {:?}",
@@ -1,4 +1,143 @@
 pub mod cpp;
+pub mod elixir;
+pub mod go;
 pub mod python;
+pub mod ruby;
 pub mod rust;
 pub mod typescript;
+
+use crate::core::defs::{Import, Language};
+use crate::warn;
+use std::fs;
+use std::path::Path;
+
+/// Read a source file for parsing. A UTF-8 byte order mark is dropped, and a file that
+/// is not valid UTF-8 (e.g. Latin-1) is decoded lossily, with a warning, rather than skipped.
+pub fn read_source(path: &Path) -> Option<String> {
+    let (code, lossy) = decode_source(fs::read(path).ok()?);
+    if lossy {
+        warn!(
+            "{} is not valid UTF-8; invalid bytes were replaced",
+            path.display()
+        );
+    }
+    Some(code)
+}
+
+/// Decode `bytes` as UTF-8 without a byte order mark, and whether any invalid bytes
+/// had to be replaced
+fn decode_source(bytes: Vec<u8>) -> (String, bool) {
+    let bytes = match bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        Some(rest) => rest.to_vec(),
+        None => bytes,
+    };
+    match String::from_utf8(bytes) {
+        Ok(code) => (code, false),
+        Err(e) => (String::from_utf8_lossy(e.as_bytes()).into_owned(), true),
+    }
+}
+
+/// Explain why a parsed import was classified as local or external
+pub fn explain_import_classification(language: Language, import: &Import, file: &Path) -> String {
+    let (is_local, reason) = match language {
+        Language::Python => python::classify_import(import.path(), file),
+        Language::Rust => rust::classify_import(import.path(), file),
+        Language::TypeScript => typescript::classify_import(import.path()),
+        // C++ includes are classified by their syntax when parsed
+        Language::Cpp if import.is_local() => (true, "included with quotes"),
+        Language::Cpp => (false, "included with angle brackets"),
+        Language::Go => go::classify_import(import.path(), file),
+        Language::Elixir => elixir::classify_import(import.path(), file),
+        // Ruby requires are classified by the method that loads them when parsed
+        Language::Ruby if import.is_local() => (true, "loaded with `require_relative`"),
+        Language::Ruby => (false, "loaded with `require`"),
+    };
+
+    // Some imports are known to be local from their syntax alone, when parsed
+    let (is_local, reason) = match language {
+        _ if is_local || !import.is_local() => (is_local, reason),
+        // Python strips the leading dots from relative imports when parsing
+        Language::Python => (true, "relative import (starts with `.`)"),
+        Language::Rust => (true, "declared with `mod`"),
+        _ => (true, "classified as local when parsed"),
+    };
+
+    let kind = if is_local { "local" } else { "external" };
+    format!("{kind}: {reason}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_bom_prefixed_file_is_parsed() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("lib.rs");
+        fs::write(&file, b"\xEF\xBB\xBFuse crate::util;\nfn helper() {}\n").unwrap();
+
+        let node = crate::parse_file(&file, Language::Rust).unwrap();
+        assert!(node.functions().contains("helper"));
+        assert_eq!(node.imports().len(), 1);
+        assert!(!read_source(&file).unwrap().starts_with('\u{feff}'));
+    }
+
+    #[test]
+    fn test_latin1_file_is_parsed_with_warning() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("main.rs");
+        // "café" in Latin-1, where `é` is the single byte 0xE9
+        let code = b"// caf\xE9\nfn main() {}\n".to_vec();
+        fs::write(&file, &code).unwrap();
+
+        let (decoded, lossy) = decode_source(code);
+        assert!(lossy);
+        assert!(decoded.starts_with("// caf\u{fffd}\n"));
+        let node = crate::parse_file(&file, Language::Rust).unwrap();
+        assert!(node.functions().contains("main"));
+
+        assert_eq!(
+            decode_source(b"fn main() {}".to_vec()),
+            ("fn main() {}".to_string(), false)
+        );
+    }
+
+    #[test]
+    fn test_explain_misclassified_python_import() {
+        // `utils.py` lives at the project root, but `import utils` from a subpackage
+        // only looks next to the importing file, so it is classified as external
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("app")).unwrap();
+        fs::write(root.join("utils.py"), "").unwrap();
+        fs::write(root.join("app/main.py"), "import utils\n").unwrap();
+
+        let import = Import::new("utils".to_string(), false);
+        let explanation =
+            explain_import_classification(Language::Python, &import, &root.join("app/main.py"));
+        assert_eq!(
+            explanation,
+            "external: didn't start with `.` and no matching file found in package structure next to the importing file"
+        );
+    }
+
+    #[test]
+    fn test_explain_rust_import() {
+        let import = Import::new("serde::Serialize".to_string(), false);
+        let explanation =
+            explain_import_classification(Language::Rust, &import, Path::new("src/main.rs"));
+        assert!(explanation.starts_with("external: didn't start with `crate::`"));
+
+        let import = Import::new("crate::core::defs".to_string(), true);
+        let explanation =
+            explain_import_classification(Language::Rust, &import, Path::new("src/main.rs"));
+        assert!(explanation.starts_with("local: "));
+
+        // `mod util;` is local, but isn't a relative import like Python's
+        let import = Import::new("util".to_string(), true);
+        let explanation =
+            explain_import_classification(Language::Rust, &import, Path::new("src/main.rs"));
+        assert_eq!(explanation, "local: declared with `mod`");
+    }
+}
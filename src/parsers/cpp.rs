@@ -1,6 +1,6 @@
-use crate::core::defs::{FileNode, Import, Language};
+use crate::core::defs::{FileNode, Import, Language, count_loc};
+use crate::parsers::read_source;
 use std::collections::HashSet;
-use std::fs;
 use std::path::Path;
 use tree_sitter::Parser;
 use tree_sitter_cpp as ts_cpp;
@@ -176,9 +176,65 @@ fn extract_macro_includes(code: &str) -> HashSet<Import> {
     includes
 }
 
+/// Qualify a method defined inside a class body with its enclosing classes
+/// (e.g. `Outer::Inner::method`). Overloads of the same method share one name.
+fn qualified_function_name(node: tree_sitter::Node, name: String, code: &str) -> String {
+    let mut qualified = name;
+    let mut current = node.parent();
+    while let Some(ancestor) = current {
+        if matches!(ancestor.kind(), "class_specifier" | "struct_specifier")
+            && let Some(class_name) = ancestor.child_by_field_name("name")
+        {
+            qualified = format!("{}::{qualified}", get_text(class_name, code));
+        }
+        current = ancestor.parent();
+    }
+    qualified
+}
+
+/// The `function_declarator` of a function declaration or definition, looking through
+/// the pointer and reference declarators of functions returning `T*` or `T&`
+fn function_declarator(node: tree_sitter::Node) -> Option<tree_sitter::Node> {
+    let mut declarator = node.child_by_field_name("declarator")?;
+    loop {
+        match declarator.kind() {
+            "function_declarator" => return Some(declarator),
+            // A reference declarator's inner declarator has no field name
+            "pointer_declarator" | "reference_declarator" => {
+                declarator = declarator
+                    .child_by_field_name("declarator")
+                    .or_else(|| declarator.named_children(&mut declarator.walk()).last())?;
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Name of the function declared or defined by `node`, qualified by its class. Out-of-line
+/// definitions (`void Foo::bar() {}`) are already qualified; function pointers have no name.
+fn function_name(node: tree_sitter::Node, code: &str) -> Option<String> {
+    let name = function_declarator(node)?.child_by_field_name("declarator")?;
+    match name.kind() {
+        "identifier" | "field_identifier" | "destructor_name" | "operator_name" => {
+            Some(qualified_function_name(node, get_text(name, code), code))
+        }
+        "qualified_identifier" => Some(get_text(name, code)),
+        _ => None,
+    }
+}
+
+/// Whether a declaration sits directly in a class body, possibly as a member template
+fn in_class_body(node: tree_sitter::Node) -> bool {
+    let parent = node
+        .parent()
+        .filter(|parent| parent.kind() == "template_declaration")
+        .map_or(node.parent(), |template| template.parent());
+    parent.is_some_and(|parent| parent.kind() == "field_declaration_list")
+}
+
 pub fn parse_cpp_file<P: AsRef<Path>>(path: P) -> Option<FileNode> {
-    let code = fs::read_to_string(&path).ok()?;
-    let loc = code.matches('\n').count() as u32 + 1;
+    let code = read_source(path.as_ref())?;
+    let loc = count_loc(&code);
 
     let mut parser = Parser::new();
     parser
@@ -193,7 +249,6 @@ pub fn parse_cpp_file<P: AsRef<Path>>(path: P) -> Option<FileNode> {
     let external_references = HashSet::new();
 
     // Traverse the syntax tree
-    let mut cursor = root_node.walk();
     let mut stack = vec![root_node];
 
     while let Some(node) = stack.pop() {
@@ -210,39 +265,23 @@ pub fn parse_cpp_file<P: AsRef<Path>>(path: P) -> Option<FileNode> {
                     imports.insert(Import::new(include_path, is_local));
                 }
             }
+            // `void foo() {}`, a method defined inside its class body, or `void Foo::bar() {}`
             "function_definition" => {
-                // Extract function name
-                if let Some(declarator_node) = node
-                    .children(&mut cursor)
-                    .find(|n| n.kind() == "function_declarator")
-                {
-                    let mut decl_cursor = declarator_node.walk();
-                    for child in declarator_node.children(&mut decl_cursor) {
-                        if child.kind() == "identifier" {
-                            functions.insert(get_text(child, &code));
-                            break;
-                        }
-                    }
+                if let Some(name) = function_name(node, &code) {
+                    functions.insert(name);
                 }
             }
-            "class_specifier" | "struct_specifier" | "union_specifier" => {
-                // Extract class/struct/union name
-                let mut spec_cursor = node.walk();
-                for child in node.children(&mut spec_cursor) {
-                    if child.kind() == "identifier" {
-                        containers.insert(get_text(child, &code));
-                        break;
-                    }
+            // Methods only declared in their class body, e.g. `void bar();`; constructors
+            // and destructors parse as plain declarations there
+            "field_declaration" | "declaration" if in_class_body(node) => {
+                if let Some(name) = function_name(node, &code) {
+                    functions.insert(name);
                 }
             }
-            "enum_specifier" => {
-                // Extract enum name
-                let mut enum_cursor = node.walk();
-                for child in node.children(&mut enum_cursor) {
-                    if child.kind() == "identifier" {
-                        containers.insert(get_text(child, &code));
-                        break;
-                    }
+            "class_specifier" | "struct_specifier" | "union_specifier" | "enum_specifier" => {
+                // Forward declarations and anonymous types have no body or no name
+                if let Some(name) = node.child_by_field_name("name") {
+                    containers.insert(get_text(name, &code));
                 }
             }
             _ => {}
@@ -288,6 +327,99 @@ void hello_world() {
         assert!(result.is_some());
     }
 
+    #[test]
+    fn test_methods_qualified_by_class() {
+        let content = r#"
+class Reader {
+public:
+    void open() {}
+    void open(int mode) {}
+};
+
+struct Writer {
+    void open() {}
+};
+
+void Reader::close() {}
+
+void open() {}
+"#;
+        let temp_file = create_test_file(content);
+        let result = parse_cpp_file(temp_file.path()).expect("Failed to parse");
+        let functions = result.functions();
+
+        assert!(functions.contains("Reader::open"));
+        assert!(functions.contains("Writer::open"));
+        assert!(functions.contains("Reader::close"));
+        assert!(functions.contains("open"));
+        assert_eq!(functions.len(), 4);
+    }
+
+    #[test]
+    fn test_methods_declared_in_class_body() {
+        let content = r#"
+namespace io {
+class Reader {
+public:
+    Reader();
+    ~Reader();
+    void close();
+    const std::string& name() const;
+    static Reader* create(int mode);
+    virtual void reset() = 0;
+    template <typename T> T read();
+    bool operator==(const Reader& other) const;
+    void (*callback)(int);
+    int size_;
+};
+}
+"#;
+        let temp_file = create_test_file(content);
+        let result = parse_cpp_file(temp_file.path()).expect("Failed to parse");
+        let mut functions: Vec<&str> = result.functions().iter().map(String::as_str).collect();
+        functions.sort();
+
+        assert_eq!(
+            functions,
+            vec![
+                "Reader::Reader",
+                "Reader::close",
+                "Reader::create",
+                "Reader::name",
+                "Reader::operator==",
+                "Reader::read",
+                "Reader::reset",
+                "Reader::~Reader",
+            ]
+        );
+        assert!(result.containers().contains("Reader"));
+    }
+
+    #[test]
+    fn test_out_of_line_method_definitions() {
+        let content = r#"
+class Reader {
+public:
+    void close();
+    const std::string& name() const;
+};
+
+void Reader::close() {}
+const std::string& Reader::name() const { return name_; }
+int* io::Buffer::data() { return nullptr; }
+"#;
+        let temp_file = create_test_file(content);
+        let result = parse_cpp_file(temp_file.path()).expect("Failed to parse");
+        let mut functions: Vec<&str> = result.functions().iter().map(String::as_str).collect();
+        functions.sort();
+
+        // A declaration and its out-of-line definition are one function
+        assert_eq!(
+            functions,
+            vec!["Reader::close", "Reader::name", "io::Buffer::data"]
+        );
+    }
+
     #[test]
     fn test_extract_system_include() {
         let content = r#"#include <vector>"#;
@@ -0,0 +1,263 @@
+use crate::core::defs::{FileNode, Import, Language, count_loc};
+use crate::core::resolvers::elixir::{find_mix_project, module_file_candidates};
+use crate::parsers::read_source;
+use std::collections::HashSet;
+use std::path::Path;
+use tree_sitter::Parser;
+use tree_sitter_elixir as ts_elixir;
+
+/// Get node text
+fn get_text(n: tree_sitter::Node, code: &str) -> String {
+    n.utf8_text(code.as_bytes()).unwrap_or("").to_string()
+}
+
+/// Local imports are modules whose conventional file exists under the project's `lib/`
+fn is_local_import(import_path: &str, file_path: &Path) -> bool {
+    classify_import(import_path, file_path).0
+}
+
+/// Classify an import as local or external, along with the rule that decided it
+pub fn classify_import(import_path: &str, file_path: &Path) -> (bool, &'static str) {
+    let Some(project_root) = file_path.parent().and_then(find_mix_project) else {
+        return (false, "no mix.exs found above the importing file");
+    };
+    if module_file_candidates(import_path, &project_root)
+        .iter()
+        .any(|candidate| candidate.is_file())
+    {
+        (true, "module maps to a file under lib/")
+    } else {
+        (false, "no file under lib/ matches the module name")
+    }
+}
+
+/// Module names brought in by the first argument of `alias`/`import`/`require`/`use`.
+/// `alias MyApp.{Foo, Bar}` expands to `MyApp.Foo` and `MyApp.Bar`.
+fn directive_modules(argument: tree_sitter::Node, code: &str) -> Vec<String> {
+    match argument.kind() {
+        "alias" => vec![get_text(argument, code)],
+        "dot" => {
+            let (Some(left), Some(right)) = (
+                argument.child_by_field_name("left"),
+                argument.child_by_field_name("right"),
+            ) else {
+                return Vec::new();
+            };
+            if left.kind() != "alias" || right.kind() != "tuple" {
+                return Vec::new();
+            }
+            let prefix = get_text(left, code);
+            let mut cursor = right.walk();
+            right
+                .named_children(&mut cursor)
+                .filter(|child| child.kind() == "alias")
+                .map(|child| format!("{prefix}.{}", get_text(child, code)))
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Name of the function defined by `def`/`defp`, from its first argument:
+/// `name`, `name(args)`, or either of those followed by a `when` guard
+fn definition_name(argument: tree_sitter::Node, code: &str) -> Option<String> {
+    match argument.kind() {
+        "identifier" => Some(get_text(argument, code)),
+        "call" => argument
+            .child_by_field_name("target")
+            .map(|target| get_text(target, code)),
+        "binary_operator" => argument
+            .child_by_field_name("left")
+            .and_then(|left| definition_name(left, code)),
+        _ => None,
+    }
+}
+
+pub fn parse_elixir_file<P: AsRef<Path>>(path: P) -> Option<FileNode> {
+    let code = read_source(path.as_ref())?;
+    let loc = count_loc(&code);
+
+    let mut parser = Parser::new();
+    parser
+        .set_language(&ts_elixir::LANGUAGE.into())
+        .expect("Error loading Elixir grammar");
+    let tree = parser.parse(&code, None)?;
+    let root_node = tree.root_node();
+
+    let mut imports = HashSet::new();
+    let mut functions = HashSet::new();
+    let mut containers = HashSet::new();
+    let external_references = HashSet::new();
+
+    let mut stack = vec![root_node];
+
+    while let Some(node) = stack.pop() {
+        // Directives and definitions are all calls, e.g. `alias MyApp.Foo` or `def hello(x)`
+        if node.kind() == "call"
+            && let Some(target) = node.child_by_field_name("target")
+            && let Some(argument) = node
+                .named_children(&mut node.walk())
+                .find(|child| child.kind() == "arguments")
+                .and_then(|arguments| arguments.named_child(0))
+        {
+            match get_text(target, &code).as_str() {
+                // `alias MyApp.Foo`, `import Ecto.Query`, `require Logger`, `use GenServer`
+                "alias" | "import" | "require" | "use" => {
+                    for module in directive_modules(argument, &code) {
+                        let is_local = is_local_import(&module, path.as_ref());
+                        imports.insert(Import::new(module, is_local));
+                    }
+                }
+
+                // `defmodule MyApp.Foo do ... end`
+                "defmodule" if argument.kind() == "alias" => {
+                    containers.insert(get_text(argument, &code));
+                }
+
+                // `def hello(x)`, `defp secret do ... end`
+                "def" | "defp" => {
+                    if let Some(name) = definition_name(argument, &code) {
+                        functions.insert(name);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        let mut child_cursor = node.walk();
+        for child in node.children(&mut child_cursor) {
+            stack.push(child);
+        }
+    }
+
+    Some(FileNode::new(
+        path.as_ref().to_path_buf(),
+        loc,
+        Language::Elixir,
+        imports,
+        functions,
+        containers,
+        external_references,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn create_test_file(dir: &TempDir, filename: &str, content: &str) -> std::path::PathBuf {
+        let file_path = dir.path().join(filename);
+        fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+        let mut file = File::create(&file_path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file_path
+    }
+
+    #[test]
+    fn test_alias_directives() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_file(&temp_dir, "mix.exs", "defmodule MyApp.MixProject do\nend\n");
+        create_test_file(
+            &temp_dir,
+            "lib/my_app/foo.ex",
+            "defmodule MyApp.Foo do\nend\n",
+        );
+        create_test_file(
+            &temp_dir,
+            "lib/my_app/bar.ex",
+            "defmodule MyApp.Bar do\nend\n",
+        );
+        let content = r#"
+defmodule MyApp.Server do
+  alias MyApp.Foo
+  alias MyApp.{Bar, Missing}
+  require Logger
+  use GenServer
+end
+"#;
+        let file_path = create_test_file(&temp_dir, "lib/my_app/server.ex", content);
+
+        let result = parse_elixir_file(&file_path).unwrap();
+        let imports = result.imports();
+
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "MyApp.Foo" && i.is_local())
+        );
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "MyApp.Bar" && i.is_local())
+        );
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "MyApp.Missing" && !i.is_local())
+        );
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "Logger" && !i.is_local())
+        );
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "GenServer" && !i.is_local())
+        );
+        assert_eq!(imports.len(), 5);
+    }
+
+    #[test]
+    fn test_defmodule_and_functions() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r#"
+defmodule MyApp.Foo do
+  def hello(name), do: "hi #{name}"
+
+  def positive?(x) when x > 0, do: true
+
+  defp secret do
+    :ok
+  end
+
+  defmodule Inner do
+    def nested, do: 1
+  end
+end
+"#;
+        let file_path = create_test_file(&temp_dir, "foo.ex", content);
+
+        let result = parse_elixir_file(&file_path).unwrap();
+        let functions = result.functions();
+        let containers = result.containers();
+
+        assert!(containers.contains(&"MyApp.Foo".to_string()));
+        assert!(containers.contains(&"Inner".to_string()));
+        assert_eq!(containers.len(), 2);
+
+        assert!(functions.contains(&"hello".to_string()));
+        assert!(functions.contains(&"positive?".to_string()));
+        assert!(functions.contains(&"secret".to_string()));
+        assert!(functions.contains(&"nested".to_string()));
+        assert_eq!(functions.len(), 4);
+    }
+
+    #[test]
+    fn test_no_mix_project_is_external() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = create_test_file(&temp_dir, "script.exs", "alias MyApp.Foo\n");
+
+        let result = parse_elixir_file(&file_path).unwrap();
+        assert!(
+            result
+                .imports()
+                .iter()
+                .all(|i| i.path() == "MyApp.Foo" && !i.is_local())
+        );
+    }
+}
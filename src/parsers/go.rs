@@ -0,0 +1,219 @@
+use crate::core::defs::{FileNode, Import, Language, count_loc};
+use crate::core::resolvers::go::find_go_module;
+use crate::parsers::read_source;
+use std::collections::HashSet;
+use std::path::Path;
+use tree_sitter::Parser;
+use tree_sitter_go as ts_go;
+
+/// Get node text
+fn get_text(n: tree_sitter::Node, code: &str) -> String {
+    n.utf8_text(code.as_bytes()).unwrap_or("").to_string()
+}
+
+/// Local imports are packages inside the module declared by the nearest go.mod
+fn is_local_import(import_path: &str, file_path: &Path) -> bool {
+    classify_import(import_path, file_path).0
+}
+
+/// Classify an import as local or external, along with the rule that decided it
+pub fn classify_import(import_path: &str, file_path: &Path) -> (bool, &'static str) {
+    if import_path.starts_with('.') {
+        return (true, "relative import (starts with `.`)");
+    }
+
+    let module_path = file_path
+        .parent()
+        .and_then(find_go_module)
+        .map(|(_, module_path)| module_path);
+    match module_path {
+        Some(module_path)
+            if import_path == module_path
+                || import_path.starts_with(&format!("{module_path}/")) =>
+        {
+            (true, "starts with the module path from go.mod")
+        }
+        Some(_) => (false, "didn't start with the module path from go.mod"),
+        None => (false, "no go.mod found above the importing file"),
+    }
+}
+
+/// Get the receiver's type name of a method, e.g. `Server` for `func (s *Server) Run()`
+fn receiver_type_name(receiver: tree_sitter::Node, code: &str) -> Option<String> {
+    let mut stack = vec![receiver];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "type_identifier" {
+            return Some(get_text(node, code));
+        }
+        let mut cursor = node.walk();
+        // push in reverse so the leftmost type is found first
+        let children: Vec<_> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    None
+}
+
+pub fn parse_go_file<P: AsRef<Path>>(path: P) -> Option<FileNode> {
+    let code = read_source(path.as_ref())?;
+    let loc = count_loc(&code);
+
+    let mut parser = Parser::new();
+    parser
+        .set_language(&ts_go::LANGUAGE.into())
+        .expect("Error loading Go grammar");
+    let tree = parser.parse(&code, None)?;
+    let root_node = tree.root_node();
+
+    let mut imports = HashSet::new();
+    let mut functions = HashSet::new();
+    let mut containers = HashSet::new();
+    let external_references = HashSet::new();
+
+    let mut stack = vec![root_node];
+
+    while let Some(node) = stack.pop() {
+        match node.kind() {
+            // `import "fmt"` and each line of `import ( ... )`
+            "import_spec" => {
+                if let Some(path_node) = node.child_by_field_name("path") {
+                    let import_path = get_text(path_node, &code)
+                        .trim_matches(|c| c == '"' || c == '`')
+                        .to_string();
+                    let is_local = is_local_import(&import_path, path.as_ref());
+                    imports.insert(Import::new(import_path, is_local));
+                }
+            }
+
+            // `func hello() {}`
+            "function_declaration" => {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    functions.insert(get_text(name_node, &code));
+                }
+            }
+
+            // `func (s *Server) Run() {}`, qualified by the receiver type
+            "method_declaration" => {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    let name = get_text(name_node, &code);
+                    match node
+                        .child_by_field_name("receiver")
+                        .and_then(|receiver| receiver_type_name(receiver, &code))
+                    {
+                        Some(receiver) => functions.insert(format!("{receiver}.{name}")),
+                        None => functions.insert(name),
+                    };
+                }
+            }
+
+            // `type Server struct {}`, `type Store interface {}`
+            "type_spec" => {
+                if let Some(type_node) = node.child_by_field_name("type")
+                    && matches!(type_node.kind(), "struct_type" | "interface_type")
+                    && let Some(name_node) = node.child_by_field_name("name")
+                {
+                    containers.insert(get_text(name_node, &code));
+                }
+            }
+
+            _ => {}
+        }
+
+        let mut child_cursor = node.walk();
+        for child in node.children(&mut child_cursor) {
+            stack.push(child);
+        }
+    }
+
+    Some(FileNode::new(
+        path.as_ref().to_path_buf(),
+        loc,
+        Language::Go,
+        imports,
+        functions,
+        containers,
+        external_references,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_grouped_imports() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("go.mod"), "module example.com/app\n").unwrap();
+        let content = r#"
+package main
+
+import "fmt"
+
+import (
+    "net/http"
+    store "example.com/app/internal/store"
+    _ "example.com/app/internal/migrations"
+)
+        "#;
+        let file_path = temp_dir.path().join("main.go");
+        fs::write(&file_path, content).unwrap();
+
+        let result = parse_go_file(&file_path).unwrap();
+        let imports = result.imports();
+
+        assert!(imports.iter().any(|i| i.path() == "fmt" && !i.is_local()));
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "net/http" && !i.is_local())
+        );
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "example.com/app/internal/store" && i.is_local())
+        );
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "example.com/app/internal/migrations" && i.is_local())
+        );
+        assert_eq!(imports.len(), 4);
+    }
+
+    #[test]
+    fn test_functions_and_types() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r#"
+package store
+
+type Store interface {
+    Get(key string) string
+}
+
+type Server struct {
+    store Store
+}
+
+type ID int
+
+func New() *Server {
+    return &Server{}
+}
+
+func (s *Server) Run() {}
+        "#;
+        let file_path = temp_dir.path().join("store.go");
+        fs::write(&file_path, content).unwrap();
+
+        let result = parse_go_file(&file_path).unwrap();
+
+        assert!(result.functions().contains("New"));
+        assert!(result.functions().contains("Server.Run"));
+        assert_eq!(result.functions().len(), 2);
+
+        assert!(result.containers().contains("Store"));
+        assert!(result.containers().contains("Server"));
+        assert_eq!(result.containers().len(), 2);
+    }
+}
@@ -1,6 +1,7 @@
-use crate::core::defs::{FileNode, Import, Language};
+use crate::core::defs::{FileNode, Import, Language, count_loc};
+use crate::parsers::read_source;
+use serde_json::Value;
 use std::collections::HashSet;
-use std::fs;
 use std::path::Path;
 use tree_sitter::Parser;
 use tree_sitter_python as ts_python;
@@ -12,10 +13,15 @@ fn get_text(n: tree_sitter::Node, code: &str) -> String {
 
 /// Determine if an import is local
 fn is_local_import(import_path: &str, file_path: &Path) -> bool {
+    classify_import(import_path, file_path).0
+}
+
+/// Classify an import as local or external, along with the rule that decided it
+pub fn classify_import(import_path: &str, file_path: &Path) -> (bool, &'static str) {
     // In Python, local imports are typically relative (starting with .) or
     // match the project's package structure
     if import_path.starts_with('.') {
-        return true;
+        return (true, "relative import (starts with `.`)");
     }
 
     // Check if the import matches the current directory structure
@@ -27,17 +33,24 @@ fn is_local_import(import_path: &str, file_path: &Path) -> bool {
             current_dir.push(part);
             if current_dir.with_extension("py").exists() || current_dir.join("__init__.py").exists()
             {
-                return true;
+                return (
+                    true,
+                    "matches a module or package next to the importing file",
+                );
             }
             current_dir.pop();
         }
     }
 
-    false
+    (
+        false,
+        "didn't start with `.` and no matching file found in package structure next to the importing file",
+    )
 }
 
-/// Extract import path from an import statement
-fn extract_import_path(node: tree_sitter::Node, code: &str) -> Vec<String> {
+/// Extract import paths from an import statement, along with the names a
+/// `from ... import` statement brings in
+fn extract_import_path(node: tree_sitter::Node, code: &str) -> Vec<(String, Vec<String>)> {
     let mut imports = Vec::new();
     let mut cursor = node.walk();
 
@@ -55,7 +68,7 @@ fn extract_import_path(node: tree_sitter::Node, code: &str) -> Vec<String> {
                             }
                         }
                         if !path.is_empty() {
-                            imports.push(path.join("."));
+                            imports.push((path.join("."), Vec::new()));
                         }
                     }
                     "aliased_import" => {
@@ -68,7 +81,7 @@ fn extract_import_path(node: tree_sitter::Node, code: &str) -> Vec<String> {
                                 }
                             }
                             if !path.is_empty() {
-                                imports.push(path.join("."));
+                                imports.push((path.join("."), Vec::new()));
                             }
                         }
                     }
@@ -77,54 +90,20 @@ fn extract_import_path(node: tree_sitter::Node, code: &str) -> Vec<String> {
             }
         }
         "import_from_statement" => {
-            // Handle "from x.y.z import a, b, c" and "from . import x"
-            let mut from_path = String::new();
-            let mut relative_dots = 0;
-
-            for child in node.children(&mut cursor) {
-                match child.kind() {
-                    "dotted_name" => {
-                        let mut parts = Vec::new();
-                        let mut name_cursor = child.walk();
-                        for name_part in child.children(&mut name_cursor) {
-                            if name_part.kind() == "identifier" {
-                                parts.push(get_text(name_part, code));
-                            }
-                        }
-                        if !parts.is_empty() {
-                            from_path = parts.join(".");
-                        }
-                        imports.push(get_text(child, code));
-                    }
-                    "relative_import" => {
-                        relative_dots = child.child_count();
-                        imports.push(get_text(child, code));
-                    }
-                    "aliased_import" => {
-                        let mut name = String::new();
-                        if let Some(name_node) = child.child_by_field_name("name") {
-                            name = get_text(name_node, code);
-                        }
-
-                        let prefix = if relative_dots > 0 {
-                            ".".repeat(relative_dots)
-                        } else if !from_path.is_empty() {
-                            from_path.clone()
-                        } else {
-                            continue;
-                        };
-
-                        if !name.is_empty() {
-                            imports.push(if relative_dots > 0 {
-                                format!("{prefix}{name}")
-                            } else {
-                                prefix
-                            });
-                        }
-                    }
-                    _ => {}
-                }
-            }
+            // Handle "from x.y.z import a, b as c" and "from . import x", keeping the names
+            let Some(module_node) = node.child_by_field_name("module_name") else {
+                return imports;
+            };
+            let names = node
+                .children_by_field_name("name", &mut cursor)
+                .filter_map(|name_node| match name_node.kind() {
+                    "aliased_import" => name_node
+                        .child_by_field_name("name")
+                        .map(|name| get_text(name, code)),
+                    _ => Some(get_text(name_node, code)),
+                })
+                .collect();
+            imports.push((get_text(module_node, code), names));
         }
         _ => {}
     }
@@ -132,9 +111,69 @@ fn extract_import_path(node: tree_sitter::Node, code: &str) -> Vec<String> {
     imports
 }
 
+/// Qualify a method name with its enclosing classes (e.g. `Outer.Inner.method`),
+/// so methods with the same name in different classes stay distinct
+fn qualified_function_name(node: tree_sitter::Node, name: String, code: &str) -> String {
+    let mut qualified = name;
+    let mut current = node.parent();
+    while let Some(ancestor) = current {
+        if ancestor.kind() == "class_definition"
+            && let Some(class_name) = ancestor.child_by_field_name("name")
+        {
+            qualified = format!("{}.{qualified}", get_text(class_name, code));
+        }
+        current = ancestor.parent();
+    }
+    qualified
+}
+
+/// Whether a function is defined inside another function's body rather than at module
+/// or class level. Methods of classes nested in a function still count as methods.
+fn is_local_function(node: tree_sitter::Node) -> bool {
+    let mut current = node.parent();
+    while let Some(ancestor) = current {
+        match ancestor.kind() {
+            "function_definition" => return true,
+            "class_definition" => return false,
+            _ => current = ancestor.parent(),
+        }
+    }
+    false
+}
+
+/// Concatenate the code cells of a Jupyter notebook into one Python source, one line
+/// per cell line. Markdown and raw cells, outputs, and IPython magics (`%matplotlib`,
+/// `!pip install ...`) are left out.
+fn notebook_code(notebook: &str) -> Option<String> {
+    let notebook: Value = serde_json::from_str(notebook).ok()?;
+    let mut code = String::new();
+    for cell in notebook.get("cells")?.as_array()? {
+        if cell.get("cell_type").and_then(Value::as_str) != Some("code") {
+            continue;
+        }
+        // `source` is either one string or a list of lines that keep their newlines
+        let source = match cell.get("source") {
+            Some(Value::String(source)) => source.clone(),
+            Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect(),
+            _ => continue,
+        };
+        for line in source.lines() {
+            let trimmed = line.trim_start();
+            if !trimmed.starts_with('%') && !trimmed.starts_with('!') {
+                code.push_str(line);
+            }
+            code.push('\n');
+        }
+    }
+    Some(code)
+}
+
 pub fn parse_python_file<P: AsRef<Path>>(path: P) -> Option<FileNode> {
-    let code = fs::read_to_string(&path).ok()?;
-    let loc = code.matches("\n").count() as u32 + 1; // count number of newlines bc code.lines() has failed me
+    let mut code = read_source(path.as_ref())?;
+    if path.as_ref().extension().is_some_and(|ext| ext == "ipynb") {
+        code = notebook_code(&code)?;
+    }
+    let loc = count_loc(&code);
 
     let mut parser = Parser::new();
     parser
@@ -147,6 +186,11 @@ pub fn parse_python_file<P: AsRef<Path>>(path: P) -> Option<FileNode> {
     let mut functions = HashSet::new();
     let mut containers = HashSet::new();
     let mut external_references = HashSet::new();
+    // A package's `__init__.py` hands what it imports from its own modules to its importers
+    let is_package_init = path
+        .as_ref()
+        .file_name()
+        .is_some_and(|name| name == "__init__.py");
 
     // Traverse the syntax tree
     let mut cursor = root_node.walk();
@@ -157,41 +201,34 @@ pub fn parse_python_file<P: AsRef<Path>>(path: P) -> Option<FileNode> {
             "import_statement" | "import_from_statement" => {
                 // Handle both "import foo" and "from foo import bar"
                 let import_paths = extract_import_path(node, &code);
-                for import_path in import_paths {
+                for (import_path, names) in import_paths {
                     let is_local = is_local_import(&import_path, path.as_ref());
-                    if is_local {
-                        imports.insert(Import::new(
-                            import_path.trim_start_matches(".").to_string(),
-                            is_local,
-                        ));
+                    let is_re_export = is_package_init && import_path.starts_with('.');
+                    let import = if is_local {
+                        Import::new(import_path.trim_start_matches(".").to_string(), is_local)
+                    } else {
+                        Import::new(import_path, is_local)
+                    };
+                    let import = import.with_names(names);
+                    if is_re_export {
+                        imports.insert(import.as_re_export());
                     } else {
-                        imports.insert(Import::new(import_path, is_local));
+                        imports.insert(import);
                     }
                 }
             }
             "function_definition" => {
-                // Get function name
-                if let Some(name_node) = node
-                    .children(&mut cursor)
-                    .find(|n| n.kind() == "identifier")
-                {
+                if let Some(name_node) = node.child_by_field_name("name") {
                     let name = get_text(name_node, &code);
-                    let in_function = node.parent().is_some_and(|p| p.kind() == "block")
-                        && node
-                            .parent()
-                            .unwrap()
-                            .parent()
-                            .is_some_and(|p| p.kind() == "function_definition");
-                    if (!name.starts_with('_') || name.starts_with("__")) && !in_function {
-                        functions.insert(name);
+                    if (!name.starts_with('_') || name.starts_with("__"))
+                        && !is_local_function(node)
+                    {
+                        functions.insert(qualified_function_name(node, name, &code));
                     }
                 }
             }
             "class_definition" => {
-                if let Some(name_node) = node
-                    .children(&mut cursor)
-                    .find(|n| n.kind() == "identifier")
-                {
+                if let Some(name_node) = node.child_by_field_name("name") {
                     containers.insert(get_text(name_node, &code));
                 }
             }
@@ -236,6 +273,36 @@ mod tests {
         file_path
     }
 
+    #[test]
+    fn test_notebook_code_cells() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r##"{
+  "cells": [
+    {"cell_type": "markdown", "metadata": {}, "source": ["# import markdown_only\n"]},
+    {
+      "cell_type": "code",
+      "execution_count": 1,
+      "metadata": {},
+      "outputs": [{"output_type": "stream", "name": "stdout", "text": ["import from_output\n"]}],
+      "source": ["%matplotlib inline\n", "import os\n", "\n", "def load(path):\n", "    return os.path.join(path)"]
+    },
+    {"cell_type": "code", "execution_count": null, "metadata": {}, "outputs": [], "source": "from pathlib import Path"}
+  ],
+  "metadata": {},
+  "nbformat": 4,
+  "nbformat_minor": 5
+}"##;
+        let file_path = create_test_file(&temp_dir, "explore.ipynb", content);
+
+        let result = parse_python_file(&file_path).unwrap();
+        assert_eq!(result.file(), &file_path);
+        assert_eq!(result.language(), &Language::Python);
+
+        let imports: HashSet<&str> = result.imports().iter().map(|i| i.path()).collect();
+        assert_eq!(imports, HashSet::from(["os", "pathlib"]));
+        assert!(result.functions().contains(&"load".to_string()));
+    }
+
     #[test]
     fn test_basic_imports() {
         let temp_dir = TempDir::new().unwrap();
@@ -295,6 +362,56 @@ import sys
         assert!(external_imports.contains(&"sys"));
     }
 
+    #[test]
+    fn test_from_import_names() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("api")).unwrap();
+        create_test_file(&temp_dir, "api/__init__.py", "");
+        create_test_file(&temp_dir, "api/routes.py", "");
+
+        let content = r#"
+from api import routes, create_app as make_app
+from . import helpers
+import os
+        "#;
+        let file_path = create_test_file(&temp_dir, "main.py", content);
+
+        let result = parse_python_file(&file_path).unwrap();
+        let names = |path: &str| {
+            result
+                .imports()
+                .iter()
+                .find(|i| i.path() == path)
+                .map(|i| i.names().to_vec())
+                .unwrap()
+        };
+
+        assert_eq!(names("api"), vec!["routes", "create_app"]);
+        assert_eq!(names(""), vec!["helpers"]);
+        assert!(names("os").is_empty());
+        assert_eq!(result.imports().len(), 3);
+        // Only a package's `__init__.py` re-exports what it imports
+        assert!(result.imports().iter().all(|i| !i.is_re_export()));
+    }
+
+    #[test]
+    fn test_package_init_re_exports() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("api")).unwrap();
+        create_test_file(&temp_dir, "api/routes.py", "");
+        let content = "from .routes import router\nimport os\n";
+        let file_path = create_test_file(&temp_dir, "api/__init__.py", content);
+
+        let result = parse_python_file(&file_path).unwrap();
+        let re_exported: Vec<&str> = result
+            .imports()
+            .iter()
+            .filter(|i| i.is_re_export())
+            .map(|i| i.path())
+            .collect();
+        assert_eq!(re_exported, vec!["routes"]);
+    }
+
     #[test]
     fn test_functions_and_classes() {
         let temp_dir = TempDir::new().unwrap();
@@ -337,6 +454,32 @@ class _PrivateClass:
         assert!(result.containers().contains(&"_PrivateClass".to_string()));
     }
 
+    #[test]
+    fn test_same_method_name_in_different_classes() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r#"
+class Reader:
+    def close(self):
+        pass
+
+class Writer:
+    def close(self):
+        pass
+
+def close():
+    pass
+        "#;
+        let file_path = create_test_file(&temp_dir, "test.py", content);
+
+        let result = parse_python_file(&file_path).unwrap();
+        let functions = result.functions();
+
+        assert!(functions.contains("Reader.close"));
+        assert!(functions.contains("Writer.close"));
+        assert!(functions.contains("close"));
+        assert_eq!(functions.len(), 3);
+    }
+
     #[test]
     fn test_complex_imports() {
         let temp_dir = TempDir::new().unwrap();
@@ -381,12 +524,88 @@ class OuterClass:
 
         assert!(result.containers().contains(&"OuterClass".to_string()));
         assert!(result.containers().contains(&"InnerClass".to_string()));
-        assert!(result.functions().contains(&"outer_method".to_string()));
-        assert!(result.functions().contains(&"inner_method".to_string()));
+        assert!(
+            result
+                .functions()
+                .contains(&"OuterClass.outer_method".to_string())
+        );
+        assert!(
+            result
+                .functions()
+                .contains(&"OuterClass.InnerClass.inner_method".to_string())
+        );
         // local_function is not captured as it's a nested function
         assert!(!result.functions().contains(&"local_function".to_string()));
     }
 
+    #[test]
+    fn test_decorated_definitions() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r#"
+@functools.lru_cache(maxsize=None)
+def cached(key: str) -> Config:
+    pass
+
+@dataclass
+class Settings:
+    @staticmethod
+    def load(path: str) -> "Settings":
+        pass
+
+    @property
+    def _secret(self) -> str:
+        pass
+        "#;
+        let file_path = create_test_file(&temp_dir, "test.py", content);
+
+        let result = parse_python_file(&file_path).unwrap();
+        let functions = result.functions();
+
+        assert!(functions.contains("cached"));
+        assert!(functions.contains("Settings.load"));
+        assert_eq!(functions.len(), 2);
+        assert!(result.containers().contains("Settings"));
+        assert_eq!(result.containers().len(), 1);
+    }
+
+    #[test]
+    fn test_annotated_methods_and_closures() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r#"
+class Repository:
+    def find(self, id: int) -> Optional[Record]:
+        def matches(record: Record) -> bool:
+            return record.id == id
+        return next(filter(matches, self.records), None)
+
+    def __len__(self) -> int:
+        pass
+
+def make_handler(prefix: str) -> Callable[[str], str]:
+    class Handler:
+        def handle(self, value: str) -> str:
+            pass
+
+    def handler(value: str) -> str:
+        pass
+    return handler
+        "#;
+        let file_path = create_test_file(&temp_dir, "test.py", content);
+
+        let result = parse_python_file(&file_path).unwrap();
+        let functions = result.functions();
+
+        assert!(functions.contains("Repository.find"));
+        assert!(functions.contains("Repository.__len__"));
+        assert!(functions.contains("make_handler"));
+        // Methods of a class defined inside a function are still methods
+        assert!(functions.contains("Handler.handle"));
+        // Closures are local to the function that defines them
+        assert!(!functions.contains("matches"));
+        assert!(!functions.contains("handler"));
+        assert_eq!(functions.len(), 4);
+    }
+
     #[test]
     fn test_lines_of_code() {
         let temp_dir = TempDir::new().unwrap();
@@ -409,6 +628,6 @@ class ExampleClass:
 
         let result = parse_python_file(&file_path).unwrap();
 
-        assert_eq!(result.loc(), 15);
+        assert_eq!(result.loc(), 14);
     }
 }
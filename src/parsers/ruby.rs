@@ -0,0 +1,177 @@
+use crate::core::defs::{FileNode, Import, Language, count_loc};
+use crate::parsers::read_source;
+use std::collections::HashSet;
+use std::path::Path;
+use tree_sitter::Parser;
+use tree_sitter_ruby as ts_ruby;
+
+/// Get node text
+fn get_text(n: tree_sitter::Node, code: &str) -> String {
+    n.utf8_text(code.as_bytes()).unwrap_or("").to_string()
+}
+
+/// The path loaded by `require 'json'` or `require_relative './foo'`, with whether it
+/// is relative to the requiring file. Paths built by interpolation are skipped.
+fn required_path(call: tree_sitter::Node, code: &str) -> Option<(String, bool)> {
+    if call.child_by_field_name("receiver").is_some() {
+        return None;
+    }
+    let is_relative = match get_text(call.child_by_field_name("method")?, code).as_str() {
+        "require" => false,
+        "require_relative" => true,
+        _ => return None,
+    };
+    let argument = call.child_by_field_name("arguments")?.named_child(0)?;
+    if argument.kind() != "string" || argument.named_child_count() != 1 {
+        return None;
+    }
+    let content = argument.named_child(0)?;
+    (content.kind() == "string_content").then(|| (get_text(content, code), is_relative))
+}
+
+pub fn parse_ruby_file<P: AsRef<Path>>(path: P) -> Option<FileNode> {
+    let code = read_source(path.as_ref())?;
+    let loc = count_loc(&code);
+
+    let mut parser = Parser::new();
+    parser
+        .set_language(&ts_ruby::LANGUAGE.into())
+        .expect("Error loading Ruby grammar");
+    let tree = parser.parse(&code, None)?;
+    let root_node = tree.root_node();
+
+    let mut imports = HashSet::new();
+    let mut functions = HashSet::new();
+    let mut containers = HashSet::new();
+    let external_references = HashSet::new();
+
+    let mut stack = vec![root_node];
+
+    while let Some(node) = stack.pop() {
+        match node.kind() {
+            // `require 'json'` loads a gem or the standard library, while
+            // `require_relative './foo'` loads a file next to this one
+            "call" => {
+                if let Some((import_path, is_relative)) = required_path(node, &code) {
+                    imports.insert(Import::new(import_path, is_relative));
+                }
+            }
+
+            // `def greet`, `def self.build`
+            "method" | "singleton_method" => {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    functions.insert(get_text(name_node, &code));
+                }
+            }
+
+            // `class Foo < Base`, `module Admin::Reports`
+            "class" | "module" => {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    containers.insert(get_text(name_node, &code));
+                }
+            }
+
+            _ => {}
+        }
+
+        let mut child_cursor = node.walk();
+        for child in node.children(&mut child_cursor) {
+            stack.push(child);
+        }
+    }
+
+    Some(FileNode::new(
+        path.as_ref().to_path_buf(),
+        loc,
+        Language::Ruby,
+        imports,
+        functions,
+        containers,
+        external_references,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_require_forms() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r##"
+require 'json'
+require "net/http"
+require_relative './models/user'
+require_relative "helpers"
+require File.join(__dir__, 'dynamic')
+require_relative "#{prefix}/interpolated"
+Kernel.require 'receiver'
+"##;
+        let file_path = temp_dir.path().join("app.rb");
+        fs::write(&file_path, content).unwrap();
+
+        let result = parse_ruby_file(&file_path).unwrap();
+        let imports = result.imports();
+
+        assert!(imports.iter().any(|i| i.path() == "json" && !i.is_local()));
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "net/http" && !i.is_local())
+        );
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "./models/user" && i.is_local())
+        );
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "helpers" && i.is_local())
+        );
+        assert_eq!(imports.len(), 4);
+    }
+
+    #[test]
+    fn test_methods_classes_and_modules() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r#"
+module Admin
+  class Report < Base
+    def self.build(rows)
+      new(rows)
+    end
+
+    def render
+      "report"
+    end
+  end
+end
+
+class Admin::Export
+  def call; end
+end
+
+def helper
+end
+"#;
+        let file_path = temp_dir.path().join("report.rb");
+        fs::write(&file_path, content).unwrap();
+
+        let result = parse_ruby_file(&file_path).unwrap();
+        let functions = result.functions();
+        let containers = result.containers();
+
+        assert!(containers.contains(&"Admin".to_string()));
+        assert!(containers.contains(&"Report".to_string()));
+        assert!(containers.contains(&"Admin::Export".to_string()));
+        assert_eq!(containers.len(), 3);
+
+        for name in ["build", "render", "call", "helper"] {
+            assert!(functions.contains(&name.to_string()), "missing {name}");
+        }
+        assert_eq!(functions.len(), 4);
+    }
+}
@@ -1,6 +1,6 @@
-use crate::core::defs::{FileNode, Import, Language};
+use crate::core::defs::{FileNode, Import, Language, count_loc};
+use crate::parsers::read_source;
 use std::collections::HashSet;
-use std::fs;
 use std::path::Path;
 use tree_sitter::Parser;
 use tree_sitter_rust as ts_rust;
@@ -12,17 +12,29 @@ fn get_text(n: tree_sitter::Node, code: &str) -> String {
 
 /// Determine if an import is local (starts with crate/self/super or current mod)
 fn is_local_import(import_path: &str, file_path: &Path) -> bool {
-    import_path.starts_with("crate::")
+    classify_import(import_path, file_path).0
+}
+
+/// Classify an import as local or external, along with the rule that decided it
+pub fn classify_import(import_path: &str, file_path: &Path) -> (bool, &'static str) {
+    if import_path.starts_with("crate::")
         || import_path.starts_with("self::")
         || import_path.starts_with("super::")
-        || {
-            // Also treat module-relative imports as local (e.g., modname::foo)
-            if let Some(stem) = file_path.file_stem().and_then(|s| s.to_str()) {
-                import_path.starts_with(&format!("{stem}::"))
-            } else {
-                false
-            }
-        }
+    {
+        return (true, "starts with `crate::`, `self::` or `super::`");
+    }
+
+    // Also treat module-relative imports as local (e.g., modname::foo)
+    if let Some(stem) = file_path.file_stem().and_then(|s| s.to_str())
+        && import_path.starts_with(&format!("{stem}::"))
+    {
+        return (true, "starts with the importing file's module name");
+    }
+
+    (
+        false,
+        "didn't start with `crate::`, `self::`, `super::` or the importing file's module name",
+    )
 }
 
 /// Extract all import paths from a use declaration, handling use lists
@@ -32,6 +44,19 @@ fn extract_use_paths(node: tree_sitter::Node, code: &str) -> Vec<String> {
     paths
 }
 
+/// Get the module a glob import pulls from, e.g. `crate::prelude` for `crate::prelude::*`
+fn wildcard_prefix(node: tree_sitter::Node, code: &str) -> Option<String> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .find(|child| {
+            matches!(
+                child.kind(),
+                "crate" | "self" | "super" | "identifier" | "scoped_identifier"
+            )
+        })
+        .map(|child| get_text(child, code))
+}
+
 fn parse_use_list(node: tree_sitter::Node, code: &str, _prefix: &str) -> Vec<String> {
     let mut cursor = node.walk();
     let mut imports: Vec<String> = vec![];
@@ -41,6 +66,9 @@ fn parse_use_list(node: tree_sitter::Node, code: &str, _prefix: &str) -> Vec<Str
             "identifier" | "scoped_identifier" => {
                 imports.push(get_text(child, code));
             }
+            "use_wildcard" => {
+                imports.extend(wildcard_prefix(child, code));
+            }
             "scoped_use_list" => {
                 for s in parse_scoped_use_list(child, code, "") {
                     imports.push(s.to_string());
@@ -97,6 +125,10 @@ fn extract_paths_from_use_clause(node: tree_sitter::Node, code: &str, paths: &mu
                     .iter()
                     .for_each(|s| paths.push(s.to_string()));
             }
+            "use_wildcard" => {
+                // Handle `foo::*` - the import is the module itself
+                paths.extend(wildcard_prefix(child, code));
+            }
             "use_as_clause" => {
                 // Handle `foo as bar` - we want the original name (foo)
                 if let Some(import_path) = child.child(0)
@@ -111,12 +143,39 @@ fn extract_paths_from_use_clause(node: tree_sitter::Node, code: &str, paths: &mu
     }
 }
 
+/// Qualify a function name with the type of its enclosing `impl` or `trait`, if any,
+/// so methods with the same name on different types stay distinct (e.g. `MyStruct::new`)
+fn qualified_function_name(node: tree_sitter::Node, name: String, code: &str) -> String {
+    let Some(container) = node
+        .parent()
+        .filter(|p| p.kind() == "declaration_list")
+        .and_then(|p| p.parent())
+    else {
+        return name;
+    };
+
+    let type_node = match container.kind() {
+        "impl_item" => container.child_by_field_name("type"),
+        "trait_item" => container.child_by_field_name("name"),
+        _ => None,
+    };
+    match type_node {
+        Some(type_node) => {
+            // Drop generic arguments, e.g. `Wrapper<T>` -> `Wrapper`
+            let type_name = get_text(type_node, code);
+            let type_name = type_name.split('<').next().unwrap_or_default();
+            format!("{type_name}::{name}")
+        }
+        None => name,
+    }
+}
+
 fn parser_loop<P: AsRef<Path>>(
     path: P,
     code: &str,
     root_node: tree_sitter::Node<'_>,
 ) -> Option<FileNode> {
-    let loc = code.matches("\n").count() as u32 + 1; // count number of newlines bc code.lines() has failed me
+    let loc = count_loc(code);
 
     let mut imports = HashSet::new();
     let mut functions = HashSet::new();
@@ -124,16 +183,31 @@ fn parser_loop<P: AsRef<Path>>(
     let mut external_references = HashSet::new();
 
     let mut cursor = root_node.walk();
-    let mut stack = vec![root_node];
-
-    while let Some(node) = stack.pop() {
+    // Each node comes with whether it sits inside `#[cfg(test)]` code
+    let mut stack = vec![(root_node, false)];
+
+    while let Some((node, in_test)) = stack.pop() {
+        let in_test = in_test || is_cfg_test_item(node, code);
+        let new_import = |path: String, is_local: bool| {
+            let import = Import::new(path, is_local);
+            if in_test { import.as_test() } else { import }
+        };
         match node.kind() {
             "use_declaration" => {
+                // `pub use` (in any visibility) re-exports the items to this module's importers
+                let is_re_export = node
+                    .children(&mut node.walk())
+                    .any(|child| child.kind() == "visibility_modifier");
                 let import_paths = extract_use_paths(node, code);
                 for import_path in import_paths {
                     if !import_path.is_empty() {
                         let is_local = is_local_import(&import_path, path.as_ref());
-                        imports.insert(Import::new(import_path, is_local));
+                        let import = new_import(import_path, is_local);
+                        imports.insert(if is_re_export {
+                            import.as_re_export()
+                        } else {
+                            import
+                        });
                     }
                 }
             }
@@ -153,7 +227,7 @@ fn parser_loop<P: AsRef<Path>>(
 
                 // Only add as import if it's a declaration (has semicolon)
                 if !mod_name.is_empty() && is_declaration {
-                    imports.insert(Import::new(mod_name, true));
+                    imports.insert(new_import(mod_name, true));
                 }
             }
             "function_item" | "function_signature_item" => {
@@ -162,7 +236,7 @@ fn parser_loop<P: AsRef<Path>>(
                 for child in node.children(&mut cursor) {
                     if child.kind() == "identifier" {
                         let name = get_text(child, code);
-                        functions.insert(name);
+                        functions.insert(qualified_function_name(node, name, code));
                     }
                 }
             }
@@ -175,18 +249,28 @@ fn parser_loop<P: AsRef<Path>>(
                     }
                 }
             }
+            // `macro_rules! foo { ... }`
+            "macro_definition" => {
+                if let Some(name_node) = node.child_by_field_name("name") {
+                    containers.insert(get_text(name_node, code));
+                }
+            }
             // For external references, look for scoped identifiers (e.g., foo::bar)
             "scoped_identifier" => {
                 let text = get_text(node, code);
                 external_references.insert(text);
             }
+            // Macro arguments and bodies are unparsed tokens, so paths have to be pieced together
+            "token_tree" => {
+                external_references.extend(token_tree_paths(node, code));
+            }
             _ => {}
         }
 
         // push children onto stack
         let mut child_cursor = node.walk();
         for child in node.children(&mut child_cursor) {
-            stack.push(child);
+            stack.push((child, in_test));
         }
     }
 
@@ -201,9 +285,71 @@ fn parser_loop<P: AsRef<Path>>(
     ))
 }
 
+/// Whether an item is annotated with `#[cfg(test)]`; its attributes are the
+/// siblings right before it
+fn is_cfg_test_item(node: tree_sitter::Node, code: &str) -> bool {
+    let mut sibling = node.prev_sibling();
+    while let Some(attribute) = sibling.filter(|s| s.kind() == "attribute_item") {
+        let text: String = get_text(attribute, code)
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        if text == "#[cfg(test)]" {
+            return true;
+        }
+        sibling = attribute.prev_sibling();
+    }
+    false
+}
+
+/// Collect `a::b` style paths from the direct children of a macro token tree
+fn token_tree_paths(node: tree_sitter::Node, code: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut segments: Vec<String> = Vec::new();
+    let mut after_separator = false;
+
+    let mut flush = |segments: &mut Vec<String>| {
+        if segments.len() > 1 {
+            paths.push(segments.join("::"));
+        }
+        segments.clear();
+    };
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        let segment = match child.kind() {
+            "identifier" | "crate" | "self" | "super" => Some(get_text(child, code)),
+            // `$crate` refers to the crate the macro was defined in
+            "metavariable" if get_text(child, code) == "$crate" => Some("crate".to_string()),
+            _ => None,
+        };
+
+        match segment {
+            Some(segment) if segments.is_empty() || after_separator => {
+                segments.push(segment);
+                after_separator = false;
+            }
+            Some(segment) => {
+                flush(&mut segments);
+                segments.push(segment);
+            }
+            None if child.kind() == "::" && !segments.is_empty() && !after_separator => {
+                after_separator = true;
+            }
+            None => {
+                flush(&mut segments);
+                after_separator = false;
+            }
+        }
+    }
+    flush(&mut segments);
+
+    paths
+}
+
 /// Parse a Rust file and extract its structure
 pub fn parse_rust_file<P: AsRef<Path>>(path: P) -> Option<FileNode> {
-    let code = fs::read_to_string(&path).ok()?;
+    let code = read_source(path.as_ref())?;
 
     let mut parser = Parser::new();
     parser
@@ -261,6 +407,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cfg_test_imports() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r#"
+use crate::core::defs::FileNode;
+
+#[cfg(test)]
+use crate::fixtures;
+
+#[cfg(test)]
+mod helpers;
+
+#[cfg(test)]
+#[allow(unused)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_node;
+}
+"#;
+        let file_path = create_test_file(&temp_dir, "lib.rs", content);
+
+        let result = parse_rust_file(&file_path).unwrap();
+        let mut imports: Vec<(&str, bool)> = result
+            .imports()
+            .iter()
+            .map(|i| (i.path(), i.is_test()))
+            .collect();
+        imports.sort();
+
+        assert_eq!(
+            imports,
+            vec![
+                ("crate::core::defs::FileNode", false),
+                ("crate::fixtures", true),
+                ("crate::test_utils::create_node", true),
+                ("helpers", true),
+                ("super", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pub_use_re_exports() {
+        let temp_dir = TempDir::new().unwrap();
+        // The visibility modifier comes before the path, in every form it can take
+        let content = r#"
+pub use crate::core::defs::FileNode;
+pub(crate) use crate::export::{ExportOptions, NodeStyle};
+pub(super) use self::inner::*;
+pub(in crate::core) use super::resolvers::GraphBuilder as Builder;
+use crate::log::LogLevel;
+"#;
+        let file_path = create_test_file(&temp_dir, "lib.rs", content);
+
+        let result = parse_rust_file(&file_path).unwrap();
+        let mut imports: Vec<(&str, bool, bool)> = result
+            .imports()
+            .iter()
+            .map(|i| (i.path(), i.is_local(), i.is_re_export()))
+            .collect();
+        imports.sort();
+
+        // A private `use` only imports
+        assert_eq!(
+            imports,
+            vec![
+                ("crate::core::defs::FileNode", true, true),
+                ("crate::export::ExportOptions", true, true),
+                ("crate::export::NodeStyle", true, true),
+                ("crate::log::LogLevel", true, false),
+                ("self::inner", true, true),
+                ("super::resolvers::GraphBuilder", true, true),
+            ]
+        );
+    }
+
     #[test]
     fn test_self_import() {
         let temp_dir = TempDir::new().unwrap();
@@ -314,6 +536,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_wildcard_imports() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r#"
+use crate::foo::*;
+use std::io::*;
+use crate::{bar::*, baz};
+        "#;
+        let file_path = create_test_file(&temp_dir, "test.rs", content);
+
+        let result = parse_rust_file(&file_path).unwrap();
+        let imports: Vec<_> = result.imports().iter().collect();
+
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "crate::foo" && i.is_local())
+        );
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "std::io" && !i.is_local())
+        );
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "crate::bar" && i.is_local())
+        );
+        assert!(imports.iter().all(|i| !i.path().contains('*')));
+    }
+
     #[test]
     fn test_basic_imports() {
         let temp_dir = TempDir::new().unwrap();
@@ -465,8 +718,8 @@ impl MyStruct {
         let functions: Vec<_> = result.functions().iter().collect();
         assert!(functions.iter().any(|f| *f == "public_function"));
         assert!(functions.iter().any(|f| *f == "private_function"));
-        assert!(functions.iter().any(|f| *f == "impl_method"));
-        assert!(functions.iter().any(|f| *f == "trait_method"));
+        assert!(functions.iter().any(|f| *f == "MyStruct::impl_method"));
+        assert!(functions.iter().any(|f| *f == "MyTrait::trait_method"));
 
         // Check containers
         let containers: Vec<_> = result.containers().iter().collect();
@@ -475,6 +728,39 @@ impl MyStruct {
         assert!(containers.iter().any(|c| *c == "MyTrait"));
     }
 
+    #[test]
+    fn test_same_method_name_in_different_impls() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r#"
+struct Foo;
+struct Bar<T>(T);
+
+impl Foo {
+    fn new() -> Self { Foo }
+}
+
+impl<T> Bar<T> {
+    fn new(value: T) -> Self { Bar(value) }
+}
+
+impl Default for Foo {
+    fn default() -> Self { Foo::new() }
+}
+
+fn new() {}
+        "#;
+        let file_path = create_test_file(&temp_dir, "test.rs", content);
+
+        let result = parse_rust_file(&file_path).unwrap();
+        let functions = result.functions();
+
+        assert!(functions.contains("Foo::new"));
+        assert!(functions.contains("Bar::new"));
+        assert!(functions.contains("Foo::default"));
+        assert!(functions.contains("new"));
+        assert_eq!(functions.len(), 4);
+    }
+
     #[test]
     fn test_external_references() {
         let temp_dir = TempDir::new().unwrap();
@@ -494,6 +780,33 @@ fn test_function() {
         assert!(refs.iter().any(|r| *r == "some_module::some_function"));
     }
 
+    #[test]
+    fn test_macros() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r#"
+macro_rules! make_parser {
+    ($name:ident) => {
+        $crate::parsers::Parser::new(stringify!($name))
+    };
+}
+
+fn main() {
+    let value = vec![crate::core::defs::Language::Rust, other::Thing];
+    println!("{}", std::env::args().count());
+}
+        "#;
+        let file_path = create_test_file(&temp_dir, "test.rs", content);
+
+        let result = parse_rust_file(&file_path).unwrap();
+        let refs = result.external_references();
+
+        assert!(result.containers().contains("make_parser"));
+        assert!(refs.contains("crate::core::defs::Language::Rust"));
+        assert!(refs.contains("other::Thing"));
+        assert!(refs.contains("std::env::args"));
+        assert!(refs.contains("crate::parsers::Parser::new"));
+    }
+
     #[test]
     fn test_lines_of_code() {
         let temp_dir = TempDir::new().unwrap();
@@ -535,7 +848,7 @@ fn function2() {
 
         let result = parse_rust_file(&file_path).unwrap();
 
-        assert_eq!(result.loc(), 13);
+        assert_eq!(result.loc(), 12);
     }
 
     #[test]
@@ -1,6 +1,6 @@
-use crate::core::defs::{FileNode, Import, Language};
+use crate::core::defs::{FileNode, Import, Language, count_loc};
+use crate::parsers::read_source;
 use std::collections::HashSet;
-use std::fs;
 use std::path::Path;
 use tree_sitter::Parser;
 use tree_sitter_typescript as ts_typescript;
@@ -12,7 +12,19 @@ fn get_text(n: tree_sitter::Node, code: &str) -> String {
 
 /// Local imports are typically relative paths starting with '.'
 fn is_local_import(import_path: &str) -> bool {
-    import_path.starts_with('.')
+    classify_import(import_path).0
+}
+
+/// Classify an import as local or external, along with the rule that decided it
+pub fn classify_import(import_path: &str) -> (bool, &'static str) {
+    if import_path.starts_with('.') {
+        (true, "relative import (starts with `.`)")
+    } else {
+        (
+            false,
+            "didn't start with `.`, so it is treated as a package",
+        )
+    }
 }
 
 /// Extracts the import path string from an import or export statement
@@ -22,13 +34,31 @@ fn extract_import_path(node: tree_sitter::Node, code: &str) -> Option<String> {
         .map(|path_text| path_text.trim_matches('"').trim_matches('\'').to_string())
 }
 
+/// `import type { X } from '...'` and `export type { X } from '...'` only bring in types
+fn is_type_only_statement(node: tree_sitter::Node) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .any(|child| child.kind() == "type")
+}
+
 pub fn parse_typescript_file<P: AsRef<Path>>(path: P) -> Option<FileNode> {
-    let code = fs::read_to_string(&path).ok()?;
-    let loc = code.matches('\n').count() as u32 + 1;
+    let code = read_source(path.as_ref())?;
+    let loc = count_loc(&code);
+
+    // JSX syntax is only valid with the TSX grammar, which in turn rejects `<T>x` casts
+    let is_jsx = path
+        .as_ref()
+        .extension()
+        .is_some_and(|ext| ext == "tsx" || ext == "jsx");
+    let grammar = if is_jsx {
+        ts_typescript::LANGUAGE_TSX
+    } else {
+        ts_typescript::LANGUAGE_TYPESCRIPT
+    };
 
     let mut parser = Parser::new();
     parser
-        .set_language(&ts_typescript::LANGUAGE_TYPESCRIPT.into())
+        .set_language(&grammar.into())
         .expect("Error loading TypeScript grammar");
     let tree = parser.parse(&code, None)?;
     let root_node = tree.root_node();
@@ -46,7 +76,16 @@ pub fn parse_typescript_file<P: AsRef<Path>>(path: P) -> Option<FileNode> {
             "import_statement" | "export_statement" => {
                 if let Some(import_path) = extract_import_path(node, &code) {
                     let is_local = is_local_import(&import_path);
-                    imports.insert(Import::new(import_path, is_local));
+                    let import = if is_type_only_statement(node) {
+                        Import::new_type_only(import_path, is_local)
+                    } else {
+                        Import::new(import_path, is_local)
+                    };
+                    if node.kind() == "export_statement" {
+                        imports.insert(import.as_re_export());
+                    } else {
+                        imports.insert(import);
+                    }
                 }
             }
 
@@ -190,6 +229,38 @@ class G {
         assert_eq!(containers.len(), 5);
     }
 
+    #[test]
+    fn test_type_only_imports() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r#"
+import type { X } from "./x";
+import { Y } from "./y";
+export type { Z } from "./z";
+export type Alias = string;
+        "#;
+        let file_path = create_test_file(&temp_dir, "test.ts", content);
+
+        let result = parse_typescript_file(&file_path).unwrap();
+        let imports = result.imports();
+
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "./x" && i.is_local() && i.is_type_only())
+        );
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "./y" && i.is_local() && !i.is_type_only())
+        );
+        assert!(
+            imports
+                .iter()
+                .any(|i| i.path() == "./z" && i.is_local() && i.is_type_only())
+        );
+        assert_eq!(imports.len(), 3);
+    }
+
     #[test]
     fn test_re_exports() {
         let temp_dir = TempDir::new().unwrap();
@@ -208,17 +279,18 @@ export * from "lib-b";
                 .any(|i| i.path() == "./local-a" && i.is_local())
         );
         assert!(imports.iter().any(|i| i.path() == "lib-b" && !i.is_local()));
+        assert!(imports.iter().all(|i| i.is_re_export()));
         assert_eq!(imports.len(), 2);
     }
 
     #[test]
     fn test_lines_of_code() {
         let temp_dir = TempDir::new().unwrap();
-        // 3 lines, including the empty line at the end
+        // The trailing newline ends the second line rather than starting a third
         let content = "const x = 1;\nconst y = 2;\n";
         let file_path = create_test_file(&temp_dir, "test.ts", content);
         let result = parse_typescript_file(&file_path).unwrap();
-        assert_eq!(result.loc(), 3);
+        assert_eq!(result.loc(), 2);
     }
 
     #[test]
@@ -227,9 +299,48 @@ export * from "lib-b";
         let content = "";
         let file_path = create_test_file(&temp_dir, "test.ts", content);
         let result = parse_typescript_file(&file_path).unwrap();
-        assert_eq!(result.loc(), 1);
+        assert_eq!(result.loc(), 0);
         assert!(result.imports().is_empty());
         assert!(result.functions().is_empty());
         assert!(result.containers().is_empty());
     }
+
+    #[test]
+    fn test_tsx_component() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r#"
+import { Button } from "./Button";
+import React from "react";
+
+interface Props { title: string }
+
+export function App({ title }: Props) {
+    return (
+        <div className="app">
+            <Button label={title} onClick={() => console.log(title)} />
+        </div>
+    );
+}
+
+const Header = () => <h1>Header</h1>;
+        "#;
+        let file_path = create_test_file(&temp_dir, "App.tsx", content);
+        create_test_file(
+            &temp_dir,
+            "Button.tsx",
+            "export const Button = () => <button />;",
+        );
+
+        let result = parse_typescript_file(&file_path).unwrap();
+
+        assert!(
+            result
+                .imports()
+                .iter()
+                .any(|i| i.path() == "./Button" && i.is_local())
+        );
+        assert!(result.functions().contains(&"App".to_string()));
+        assert!(result.functions().contains(&"Header".to_string()));
+        assert!(result.containers().contains(&"Props".to_string()));
+    }
 }
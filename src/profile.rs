@@ -0,0 +1,99 @@
+use std::time::{Duration, Instant};
+
+/// Wall-clock time spent in each phase of a run, in the order the phases first ran
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    phases: Vec<(String, Duration)>,
+}
+
+impl Profile {
+    /// Add `elapsed` to `phase`, which is appended if it hasn't run before
+    pub fn record(&mut self, phase: &str, elapsed: Duration) {
+        match self.phases.iter_mut().find(|(name, _)| name == phase) {
+            Some((_, total)) => *total += elapsed,
+            None => self.phases.push((phase.to_string(), elapsed)),
+        }
+    }
+
+    /// Run `f`, recording how long it took under `phase`
+    pub fn time<T>(&mut self, phase: &str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.record(phase, start.elapsed());
+        result
+    }
+
+    /// Fold the phases of another profile into this one
+    pub fn merge(&mut self, other: &Profile) {
+        for (phase, elapsed) in &other.phases {
+            self.record(phase, *elapsed);
+        }
+    }
+
+    pub fn phases(&self) -> &[(String, Duration)] {
+        &self.phases
+    }
+
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|(_, elapsed)| *elapsed).sum()
+    }
+
+    /// Format the phases as a table of durations and their share of the total
+    pub fn report(&self) -> String {
+        let total = self.total();
+        let width = self
+            .phases
+            .iter()
+            .map(|(name, _)| name.len())
+            .chain(["phase".len(), "total".len()])
+            .max()
+            .unwrap_or(0);
+        let share = |elapsed: Duration| {
+            if total.is_zero() {
+                0.0
+            } else {
+                elapsed.as_secs_f64() / total.as_secs_f64() * 100.0
+            }
+        };
+
+        let mut report = format!("{:<width$}  {:>10}  {:>6}\n", "phase", "ms", "%");
+        for (name, elapsed) in self.phases.iter().chain([&("total".to_string(), total)]) {
+            report.push_str(&format!(
+                "{:<width$}  {:>10.3}  {:>5.1}%\n",
+                name,
+                elapsed.as_secs_f64() * 1000.0,
+                share(*elapsed)
+            ));
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_accumulates_in_first_run_order() {
+        let mut profile = Profile::default();
+        profile.record("walk", Duration::from_millis(5));
+        profile.record("parse[Rust]", Duration::from_millis(20));
+        profile.record("walk", Duration::from_millis(5));
+
+        let phases: Vec<(&str, u128)> = profile
+            .phases()
+            .iter()
+            .map(|(name, elapsed)| (name.as_str(), elapsed.as_millis()))
+            .collect();
+        assert_eq!(phases, vec![("walk", 10), ("parse[Rust]", 20)]);
+        assert_eq!(profile.total(), Duration::from_millis(30));
+
+        let report = profile.report();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].starts_with("walk"));
+        assert!(lines[1].ends_with("33.3%"));
+        assert!(lines[3].starts_with("total"));
+        assert!(lines[3].ends_with("100.0%"));
+    }
+}
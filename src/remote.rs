@@ -0,0 +1,251 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// URL schemes that are cloned instead of read from disk
+const URL_SCHEMES: [&str; 5] = ["https://", "http://", "git://", "ssh://", "file://"];
+
+/// Whether `target` names a git remote rather than a local path, e.g.
+/// `https://github.com/user/repo.git` or scp-style `git@github.com:user/repo.git`.
+/// Anything starting with `-` is not, so it can't be passed to git as an option.
+pub fn is_remote_url(target: &str) -> bool {
+    if target.starts_with('-') {
+        return false;
+    }
+    if URL_SCHEMES.iter().any(|scheme| target.starts_with(scheme)) {
+        return true;
+    }
+
+    // scp-style `user@host:path`; a local path would need a `/` before the `@`
+    match (target.find('@'), target.find(':')) {
+        (Some(at), Some(colon)) => at > 0 && at < colon && !target[..colon].contains('/'),
+        _ => false,
+    }
+}
+
+/// A shallow clone in a temporary directory, removed on drop unless kept
+#[derive(Debug)]
+pub struct RemoteCheckout {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl RemoteCheckout {
+    /// Take ownership of the checkout at `path`
+    pub fn new(path: PathBuf, keep: bool) -> Self {
+        Self { path, keep }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for RemoteCheckout {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+/// Pick a fresh directory under the system temp directory to clone into
+fn clone_dir() -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or(0);
+    std::env::temp_dir().join(format!("seiri-clone-{}-{nanos}", std::process::id()))
+}
+
+/// Turn git's stderr for a failed clone into an error message for `url`
+fn clone_error(url: &str, stderr: &str) -> String {
+    let lower = stderr.to_lowercase();
+    if lower.contains("authentication failed")
+        || lower.contains("could not read username")
+        || lower.contains("terminal prompts disabled")
+        || lower.contains("permission denied")
+    {
+        format!(
+            "Authentication failed while cloning {url}; check that the repository exists and that your git credentials can access it"
+        )
+    } else if lower.contains("not found")
+        || lower.contains("does not appear to be a git repository")
+        || lower.contains("not a git repository")
+    {
+        format!("{url} is not a git repository")
+    } else {
+        format!("Failed to clone {url}: {}", stderr.trim())
+    }
+}
+
+/// Shallow-clone `url` into a temporary directory. The clone is deleted when the
+/// returned checkout is dropped, unless `keep` is set.
+pub fn clone_remote(url: &str, keep: bool) -> Result<RemoteCheckout, String> {
+    let path = clone_dir();
+    let output = Command::new("git")
+        .args(["clone", "--depth", "1", "--quiet", "--", url])
+        .arg(&path)
+        // Fail instead of waiting for credentials on a terminal nobody is watching
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+
+    // Clean up whatever git left behind, even on failure
+    let checkout = RemoteCheckout::new(path, keep);
+    if !output.status.success() {
+        return Err(clone_error(url, &String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(checkout)
+}
+
+/// Check out revision `rev` of the git repository containing `repo_dir` into a temporary
+/// directory, leaving the repository's own working tree and index alone. The checkout is
+/// deleted when dropped.
+pub fn checkout_revision(repo_dir: &Path, rev: &str) -> Result<RemoteCheckout, String> {
+    let git = |args: &[&str], dir: &Path| {
+        Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to run git: {e}"))
+    };
+
+    let resolved = git(
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{rev}^{{commit}}"),
+        ],
+        repo_dir,
+    )?;
+    if !resolved.status.success() {
+        return Err(format!("Unknown git revision: {rev}"));
+    }
+    let commit = String::from_utf8_lossy(&resolved.stdout).trim().to_string();
+
+    // `--shared` borrows the repository's objects, so any commit it has can be checked out
+    let path = clone_dir();
+    let clone = Command::new("git")
+        .args(["clone", "--shared", "--no-checkout", "--quiet"])
+        .arg(repo_dir)
+        .arg(&path)
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    let checkout = RemoteCheckout::new(path, false);
+    if !clone.status.success() {
+        return Err(format!(
+            "Failed to check out {rev}: {}",
+            String::from_utf8_lossy(&clone.stderr).trim()
+        ));
+    }
+
+    let output = git(
+        &["checkout", "--quiet", "--detach", &commit],
+        checkout.path(),
+    )?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to check out {rev}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(checkout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_remote_url() {
+        assert!(is_remote_url("https://github.com/user/repo.git"));
+        assert!(is_remote_url("http://example.com/repo"));
+        assert!(is_remote_url("ssh://git@example.com/repo.git"));
+        assert!(is_remote_url("git@github.com:user/repo.git"));
+        assert!(is_remote_url("file:///tmp/repo"));
+
+        assert!(!is_remote_url("."));
+        assert!(!is_remote_url("src/main.rs"));
+        assert!(!is_remote_url("/home/me/project"));
+        assert!(!is_remote_url("dir/user@host:thing"));
+        assert!(!is_remote_url(r"C:\projects\seiri"));
+        assert!(!is_remote_url("-x@host:repo"));
+        assert!(!is_remote_url("--upload-pack=touch@host:repo"));
+    }
+
+    #[test]
+    fn test_checkout_removed_on_drop_unless_kept() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let removed = temp_dir.path().join("removed");
+        fs::create_dir_all(removed.join("src")).unwrap();
+        drop(RemoteCheckout::new(removed.clone(), false));
+        assert!(!removed.exists());
+
+        let kept = temp_dir.path().join("kept");
+        fs::create_dir_all(&kept).unwrap();
+        drop(RemoteCheckout::new(kept.clone(), true));
+        assert!(kept.exists());
+    }
+
+    #[test]
+    fn test_clone_error_messages() {
+        let url = "https://example.com/private.git";
+        assert!(
+            clone_error(
+                url,
+                "fatal: could not read Username for 'https://example.com': terminal prompts disabled"
+            )
+            .starts_with("Authentication failed")
+        );
+        assert_eq!(
+            clone_error(
+                url,
+                "fatal: repository 'https://example.com/private.git/' not found"
+            ),
+            format!("{url} is not a git repository")
+        );
+    }
+
+    #[test]
+    fn test_clone_local_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("source");
+        fs::create_dir_all(&source).unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&source)
+                .args([
+                    "-c",
+                    "user.name=seiri",
+                    "-c",
+                    "user.email=seiri@example.com",
+                ])
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "-q"]);
+        fs::write(source.join("main.rs"), "fn main() {}\n").unwrap();
+        git(&["add", "main.rs"]);
+        git(&["commit", "-q", "-m", "init"]);
+
+        let url = format!("file://{}", source.display());
+        let checkout = clone_remote(&url, false).unwrap();
+        let cloned = checkout.path().to_path_buf();
+        assert!(cloned.join("main.rs").exists());
+
+        drop(checkout);
+        assert!(!cloned.exists());
+
+        let missing = format!("file://{}", temp_dir.path().join("missing").display());
+        assert!(clone_remote(&missing, false).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::core::defs::Language;
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{DebounceEventResult, Debouncer, new_debouncer};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, channel};
+use std::time::Duration;
+
+/// How long to wait for a burst of file events to settle before reporting a change
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches a project directory for changes to supported source files
+pub struct ProjectWatcher {
+    // Dropping the debouncer stops the watch, so it must live as long as the receiver
+    _debouncer: Debouncer<RecommendedWatcher>,
+    events: Receiver<DebounceEventResult>,
+    /// Extra file extensions from `--language-config`
+    extension_map: HashMap<String, Language>,
+}
+
+impl ProjectWatcher {
+    /// Start watching `path` recursively. Events within `debounce` of each other are merged.
+    /// Files count as supported by their extension, with `extension_map` taking precedence.
+    pub fn new(
+        path: &Path,
+        debounce: Duration,
+        extension_map: &HashMap<String, Language>,
+    ) -> Result<Self, String> {
+        let (sender, events) = channel();
+        let mut debouncer = new_debouncer(debounce, sender)
+            .map_err(|e| format!("Failed to start file watcher: {e}"))?;
+        debouncer
+            .watcher()
+            .watch(path, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {e}", path.display()))?;
+
+        Ok(Self {
+            _debouncer: debouncer,
+            events,
+            extension_map: extension_map.clone(),
+        })
+    }
+
+    /// Block until at least one supported source file changes, returning the changed files
+    pub fn wait_for_change(&self) -> Result<HashSet<PathBuf>, String> {
+        loop {
+            let result = self
+                .events
+                .recv()
+                .map_err(|_| "File watcher stopped unexpectedly".to_string())?;
+            let changed = supported_paths(result, &self.extension_map)?;
+            if !changed.is_empty() {
+                return Ok(changed);
+            }
+        }
+    }
+
+    /// Like `wait_for_change`, but gives up and returns `Ok(None)` after `timeout`
+    pub fn wait_for_change_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<Option<HashSet<PathBuf>>, String> {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            match self.events.recv_timeout(remaining) {
+                Ok(result) => {
+                    let changed = supported_paths(result, &self.extension_map)?;
+                    if !changed.is_empty() {
+                        return Ok(Some(changed));
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => return Ok(None),
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err("File watcher stopped unexpectedly".to_string());
+                }
+            }
+        }
+    }
+}
+
+/// Keep only events for files seiri knows how to parse
+fn supported_paths(
+    result: DebounceEventResult,
+    extension_map: &HashMap<String, Language>,
+) -> Result<HashSet<PathBuf>, String> {
+    let events = result.map_err(|e| format!("File watcher error: {e}"))?;
+    Ok(events
+        .into_iter()
+        .map(|event| event.path)
+        .filter(|path| {
+            path.to_str()
+                .is_some_and(|name| Language::from_file_with_config(name, extension_map).is_some())
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_reports_supported_file_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        let extension_map = HashMap::from([("pyi".to_string(), Language::Python)]);
+        let watcher =
+            ProjectWatcher::new(&root, Duration::from_millis(50), &extension_map).unwrap();
+
+        // Unsupported files are ignored, extensions from the language config are not
+        fs::write(root.join("notes.txt"), "hello").unwrap();
+        fs::write(root.join("stub.pyi"), "x: int").unwrap();
+        fs::write(root.join("main.rs"), "fn main() {}").unwrap();
+
+        // The writes may be reported in more than one batch
+        let mut changed = HashSet::new();
+        while !changed.contains(&root.join("main.rs")) || !changed.contains(&root.join("stub.pyi"))
+        {
+            let batch = watcher
+                .wait_for_change_timeout(Duration::from_secs(5))
+                .unwrap()
+                .expect("no change reported");
+            changed.extend(batch);
+        }
+        assert!(changed.contains(&root.join("main.rs")));
+        assert!(changed.contains(&root.join("stub.pyi")));
+        assert!(!changed.contains(&root.join("notes.txt")));
+    }
+}
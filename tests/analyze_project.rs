@@ -0,0 +1,138 @@
+use seiri_cli::{AnalyzeOptions, Language, analyze_project, build_project, parse_file};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tempfile::TempDir;
+
+fn write(root: &Path, file: &str, content: &str) {
+    let path = root.join(file);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+#[test]
+fn test_analyze_project_returns_resolved_nodes() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write(
+        &root,
+        "main.rs",
+        "mod util;\n\nfn main() {\n    util::helper();\n}\n",
+    );
+    write(&root, "util.rs", "pub fn helper() {}\n");
+    write(
+        &root,
+        "scripts/build.py",
+        "import os\n\ndef run():\n    pass\n",
+    );
+
+    let graph_nodes = analyze_project(&root, AnalyzeOptions::default()).unwrap();
+
+    let files: HashSet<PathBuf> = graph_nodes
+        .iter()
+        .map(|node| node.data().file().clone())
+        .collect();
+    assert_eq!(
+        files,
+        HashSet::from([
+            root.join("main.rs"),
+            root.join("util.rs"),
+            root.join("scripts/build.py"),
+        ])
+    );
+
+    let main = graph_nodes
+        .iter()
+        .find(|node| node.data().file().ends_with("main.rs"))
+        .unwrap();
+    assert_eq!(main.edges(), &vec![root.join("util.rs")]);
+    assert!(main.data().functions().contains(&"main".to_string()));
+
+    let script = graph_nodes
+        .iter()
+        .find(|node| *node.data().language() == Language::Python)
+        .unwrap();
+    assert!(script.edges().is_empty());
+}
+
+#[test]
+fn test_analyze_project_respects_options() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write(&root, "main.rs", "fn main() {}\n");
+    write(&root, "tool.py", "def run():\n    pass\n");
+
+    let options = AnalyzeOptions {
+        hidden_languages: HashSet::from([Language::Python]),
+        ..Default::default()
+    };
+    let graph_nodes = analyze_project(&root, options).unwrap();
+    assert_eq!(graph_nodes.len(), 1);
+    assert_eq!(*graph_nodes[0].data().language(), Language::Rust);
+
+    let empty = TempDir::new().unwrap();
+    assert!(analyze_project(empty.path(), AnalyzeOptions::default()).is_err());
+}
+
+#[test]
+fn test_update_files_reparses_only_changed_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    write(&root, "main.rs", "mod a;\nmod b;\n\nfn main() {}\n");
+    write(&root, "a.rs", "pub fn a() {}\n");
+    write(&root, "b.rs", "pub fn b() {}\n");
+    write(&root, "c.rs", "pub fn c() {}\n");
+
+    let mut project = build_project(&root, &AnalyzeOptions::default()).unwrap();
+    let edges = |project: &seiri_cli::ProjectGraph, file: &str| {
+        let node = project
+            .graph_nodes
+            .iter()
+            .find(|node| node.data().file() == &root.join(file))
+            .unwrap();
+        node.edges().iter().cloned().collect::<HashSet<PathBuf>>()
+    };
+    assert_eq!(
+        edges(&project, "main.rs"),
+        HashSet::from([root.join("a.rs"), root.join("b.rs")])
+    );
+
+    let parsed = Mutex::new(Vec::new());
+    let counting_parse = |file: &Path, lang: Language| {
+        parsed.lock().unwrap().push(file.to_path_buf());
+        parse_file(file, lang)
+    };
+
+    // main now imports c instead of b
+    write(&root, "main.rs", "mod a;\nmod c;\n\nfn main() {}\n");
+    let changed = HashSet::from([root.join("main.rs")]);
+    assert!(project.update_files(&root, &changed, counting_parse));
+    assert_eq!(*parsed.lock().unwrap(), vec![root.join("main.rs")]);
+    assert_eq!(
+        edges(&project, "main.rs"),
+        HashSet::from([root.join("a.rs"), root.join("c.rs")])
+    );
+    assert_eq!(project.graph_nodes.len(), 4);
+    for sibling in ["a.rs", "b.rs", "c.rs"] {
+        assert!(edges(&project, sibling).is_empty());
+    }
+
+    // Deleting a file drops it and every edge to it, without parsing anything
+    parsed.lock().unwrap().clear();
+    fs::remove_file(root.join("c.rs")).unwrap();
+    let changed = HashSet::from([root.join("c.rs")]);
+    assert!(project.update_files(&root, &changed, counting_parse));
+    assert!(parsed.lock().unwrap().is_empty());
+    assert_eq!(project.graph_nodes.len(), 3);
+    assert_eq!(
+        edges(&project, "main.rs"),
+        HashSet::from([root.join("a.rs")])
+    );
+
+    // New files need a full walk
+    write(&root, "d.rs", "pub fn d() {}\n");
+    let changed = HashSet::from([root.join("d.rs")]);
+    assert!(!project.update_files(&root, &changed, counting_parse));
+    assert_eq!(project.graph_nodes.len(), 3);
+}
@@ -0,0 +1,34 @@
+use std::fs;
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Run the `seiri` binary in `dir` with `args`, returning its stdout
+fn run_in(dir: &std::path::Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_seiri-cli"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_quiet_run_prints_nothing() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("main.rs"), "mod util;\nfn main() {}\n").unwrap();
+    fs::write(root.join("util.rs"), "pub fn helper() {}\n").unwrap();
+
+    let stdout = run_in(root, &[".", "graph.json", "--no-cache", "--quiet"]);
+    assert_eq!(stdout, "");
+    assert!(root.join("graph.json").exists());
+
+    // By default the export is still announced
+    let stdout = run_in(root, &[".", "graph.json", "--no-cache"]);
+    assert_eq!(stdout, "Successfully exported to graph.json\n");
+}